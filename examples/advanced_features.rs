@@ -282,7 +282,7 @@ fn demo_custom_capabilities() -> rappct::Result<()> {
                 println!(
                     "    {}. {} (attributes: 0x{:x})",
                     i + 1,
-                    cap.sid_sddl,
+                    cap.sid,
                     cap.attributes
                 );
             }
@@ -605,12 +605,12 @@ fn demo_capability_acls() -> rappct::Result<()> {
         .build()?;
 
     if !caps.caps.is_empty() {
-        let cap_sid = &caps.caps[0].sid_sddl;
+        let cap_sid = caps.caps[0].sid.to_string();
         println!("→ Granting access to capability: {cap_sid}");
 
         match grant_to_capability(
             ResourcePath::File(test_file.clone()),
-            cap_sid,
+            &cap_sid,
             AccessMask::FILE_GENERIC_READ, // FILE_GENERIC_READ
         ) {
             Ok(_) => println!("✓ Capability-based ACL applied successfully"),
@@ -0,0 +1,596 @@
+//! Offline effective-access evaluation against a [`SecurityDescriptor`].
+//!
+//! [`effective_access`] reproduces the parts of `AccessCheck` that decide what an
+//! AppContainer can open. First the mandatory integrity check compares the token's level
+//! with the object's label (an unlabeled object counts as Medium, no-write-up). Then the
+//! DACL is walked for the token's user and groups. AppContainer tokens get a second walk for
+//! the package SID, its enabled capabilities, `ALL RESTRICTED APPLICATION PACKAGES` and,
+//! unless the token is LPAC, `ALL APPLICATION PACKAGES`. The granted access is what both
+//! walks grant and the label allows.
+//!
+//! Each walk follows the maximum-allowed rules: an ACE only decides bits no earlier ACE
+//! decided, so a deny ACE beats every allow ACE after it, which in canonical order is all of
+//! them. Conditional (callback) ACEs are not evaluated and are listed in
+//! [`AccessReport::unevaluated`]. Object ACEs that name an object type are skipped.
+//! Privileges and restricted tokens are out of scope.
+
+use super::{AccessMask, Ace, AceFlags, AceType, Acl, SecurityDescriptor};
+use crate::capability::SecurityCapabilities;
+use crate::sid::{AppContainerSid, Sid, SidAndAttributes, well_known};
+use crate::{AcError, Result};
+
+const DELETE: u32 = 0x0001_0000;
+const READ_CONTROL: u32 = 0x0002_0000;
+const WRITE_DAC: u32 = 0x0004_0000;
+const WRITE_OWNER: u32 = 0x0008_0000;
+const SYNCHRONIZE: u32 = 0x0010_0000;
+const MAXIMUM_ALLOWED: u32 = 0x0200_0000;
+const GENERIC_ALL: u32 = 0x1000_0000;
+const GENERIC_EXECUTE: u32 = 0x2000_0000;
+const GENERIC_WRITE: u32 = 0x4000_0000;
+const GENERIC_READ: u32 = 0x8000_0000;
+
+const NO_WRITE_UP: u32 = 0x1;
+const NO_READ_UP: u32 = 0x2;
+const NO_EXECUTE_UP: u32 = 0x4;
+
+/// Specific rights the generic rights (`GR`, `GW`, `GX`, `GA`) stand for on an object type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GenericMapping {
+    pub read: AccessMask,
+    pub write: AccessMask,
+    pub execute: AccessMask,
+    pub all: AccessMask,
+}
+
+impl GenericMapping {
+    /// Files and directories.
+    pub const FILE: Self = Self {
+        read: AccessMask(0x0012_0089),
+        write: AccessMask(0x0012_0116),
+        execute: AccessMask(0x0012_00A0),
+        all: AccessMask(0x001F_01FF),
+    };
+
+    /// Registry keys.
+    pub const REGISTRY: Self = Self {
+        read: AccessMask(0x0002_0019),
+        write: AccessMask(0x0002_0006),
+        execute: AccessMask(0x0002_0019),
+        all: AccessMask(0x000F_003F),
+    };
+
+    /// Replaces generic bits in `mask` with the specific rights they stand for and drops
+    /// `MAXIMUM_ALLOWED`.
+    pub fn map(&self, mask: AccessMask) -> AccessMask {
+        let m = mask.0;
+        let mut out = m & !(GENERIC_ALL | GENERIC_EXECUTE | GENERIC_WRITE | GENERIC_READ);
+        for (bit, specific) in [
+            (GENERIC_READ, self.read),
+            (GENERIC_WRITE, self.write),
+            (GENERIC_EXECUTE, self.execute),
+            (GENERIC_ALL, self.all),
+        ] {
+            if m & bit != 0 {
+                out |= specific.0;
+            }
+        }
+        AccessMask(out & !MAXIMUM_ALLOWED)
+    }
+}
+
+/// The parts of an access token that decide access: user, groups, AppContainer identity and
+/// integrity level.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenModel {
+    pub user: Sid,
+    /// Enabled groups, such as `WD`, `AU` and `BU` for an interactive user.
+    pub groups: Vec<Sid>,
+    /// Package SID of an AppContainer token.
+    pub package: Option<Sid>,
+    /// Capability SIDs with their `SE_GROUP_*` attributes; disabled entries never match and
+    /// `USE_FOR_DENY_ONLY` entries only match deny ACEs.
+    pub capabilities: Vec<SidAndAttributes>,
+    /// Whether the token is a less-privileged AppContainer, which ignores `ALL APPLICATION
+    /// PACKAGES`.
+    pub lpac: bool,
+    /// Mandatory integrity level RID ([`TokenModel::LOW`], [`TokenModel::MEDIUM`], ...).
+    pub integrity_level: u32,
+}
+
+impl TokenModel {
+    pub const UNTRUSTED: u32 = 0x0000;
+    pub const LOW: u32 = 0x1000;
+    pub const MEDIUM: u32 = 0x2000;
+    pub const HIGH: u32 = 0x3000;
+    pub const SYSTEM: u32 = 0x4000;
+
+    /// A medium-integrity token for `user` with no groups.
+    pub fn new(user: Sid) -> Self {
+        Self {
+            user,
+            groups: Vec::new(),
+            package: None,
+            capabilities: Vec::new(),
+            lpac: false,
+            integrity_level: Self::MEDIUM,
+        }
+    }
+
+    /// Adds enabled groups.
+    pub fn with_groups(mut self, groups: &[Sid]) -> Self {
+        self.groups.extend_from_slice(groups);
+        self
+    }
+
+    /// Makes this an AppContainer token for `package`, which also drops it to Low integrity
+    /// as `CreateProcess` does.
+    pub fn with_package(mut self, package: &AppContainerSid) -> Result<Self> {
+        let sid = package.as_sid().cloned().ok_or_else(|| {
+            AcError::InvalidSid(format!(
+                "'{}' is not a valid package SID",
+                package.as_string()
+            ))
+        })?;
+        self.package = Some(sid);
+        self.integrity_level = Self::LOW;
+        Ok(self)
+    }
+
+    /// Adds enabled capability SIDs.
+    pub fn with_capabilities(mut self, capabilities: &[Sid]) -> Self {
+        self.capabilities.extend(
+            capabilities
+                .iter()
+                .map(|sid| SidAndAttributes::new(sid.clone(), SidAndAttributes::ENABLED)),
+        );
+        self
+    }
+
+    pub fn with_lpac(mut self, lpac: bool) -> Self {
+        self.lpac = lpac;
+        self
+    }
+
+    pub fn with_integrity_level(mut self, level: u32) -> Self {
+        self.integrity_level = level;
+        self
+    }
+
+    /// Takes the package, capabilities (with their attributes) and LPAC flag of `sec`.
+    pub fn with_security_capabilities(self, sec: &SecurityCapabilities) -> Result<Self> {
+        let mut token = self.with_package(&sec.package)?.with_lpac(sec.lpac);
+        token.capabilities.extend(sec.caps.iter().cloned());
+        Ok(token)
+    }
+
+    pub fn is_app_container(&self) -> bool {
+        self.package.is_some()
+    }
+}
+
+/// Which DACL walk an [`AceEffect`] belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CheckPass {
+    /// The token's user and groups.
+    Principal,
+    /// The package SID, capabilities and AppContainer-wide groups.
+    AppContainer,
+}
+
+impl std::fmt::Display for CheckPass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Principal => "principal",
+            Self::AppContainer => "AppContainer",
+        })
+    }
+}
+
+/// Rights one ACE decided during a walk. Only the bits it decided first are listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AceEffect {
+    pub pass: CheckPass,
+    /// Position of the ACE in the DACL.
+    pub index: usize,
+    pub ace: Ace,
+    pub granted: AccessMask,
+    pub denied: AccessMask,
+}
+
+/// Result of [`effective_access`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessReport {
+    /// Access the token would be granted, with generic rights mapped.
+    pub granted: AccessMask,
+    /// What the walk for the user and groups granted.
+    pub principal_granted: AccessMask,
+    /// What the AppContainer walk granted; `None` for non-AppContainer tokens.
+    pub app_container_granted: Option<AccessMask>,
+    /// Rights the mandatory label withholds from the token's integrity level.
+    pub label_withheld: AccessMask,
+    /// `READ_CONTROL | WRITE_DAC` granted implicitly to the owner in each pass.
+    pub owner_rights: AccessMask,
+    /// The DACL is absent or `NO_ACCESS_CONTROL`, which grants everything.
+    pub null_dacl: bool,
+    pub effects: Vec<AceEffect>,
+    /// DACL positions of conditional ACEs that were not evaluated.
+    pub unevaluated: Vec<usize>,
+    mapping: GenericMapping,
+}
+
+impl AccessReport {
+    /// Whether every right in `desired` (generic rights allowed) is granted.
+    pub fn allows(&self, desired: AccessMask) -> bool {
+        let want = self.mapping.map(desired).0;
+        self.granted.0 & want == want
+    }
+
+    /// Names what decided `desired`: the label and the deny ACEs or missing grants behind
+    /// any withheld right, or the ACEs that granted it.
+    pub fn explain(&self, desired: AccessMask) -> String {
+        let want = self.mapping.map(desired).0;
+        let mut reasons = Vec::new();
+        if want & self.label_withheld.0 != 0 {
+            reasons.push(format!(
+                "mandatory label withholds {:#x}",
+                want & self.label_withheld.0
+            ));
+        }
+        let passes = [
+            (CheckPass::Principal, Some(self.principal_granted)),
+            (CheckPass::AppContainer, self.app_container_granted),
+        ];
+        for (pass, granted) in passes {
+            let Some(granted) = granted else { continue };
+            let mut missing = want & !granted.0;
+            for effect in self.effects.iter().filter(|e| e.pass == pass) {
+                let bits = effect.denied.0 & missing;
+                if bits != 0 {
+                    reasons.push(format!(
+                        "{pass}: ACE #{} {} denies {bits:#x}",
+                        effect.index, effect.ace
+                    ));
+                    missing &= !bits;
+                }
+            }
+            if missing != 0 {
+                reasons.push(format!("{pass}: no ACE grants {missing:#x}"));
+            }
+        }
+        if !reasons.is_empty() {
+            return format!("denied: {}", reasons.join("; "));
+        }
+        let mut grants = Vec::new();
+        if self.null_dacl {
+            grants.push("NULL DACL".to_string());
+        }
+        if want & self.owner_rights.0 != 0 {
+            grants.push("owner rights".to_string());
+        }
+        for effect in &self.effects {
+            if effect.granted.0 & want != 0 {
+                grants.push(format!(
+                    "{}: ACE #{} {}",
+                    effect.pass, effect.index, effect.ace
+                ));
+            }
+        }
+        format!("granted {want:#x} by {}", grants.join("; "))
+    }
+}
+
+/// Computes the access `token` gets to an object protected by `sd`.
+pub fn effective_access(
+    sd: &SecurityDescriptor,
+    token: &TokenModel,
+    mapping: GenericMapping,
+) -> AccessReport {
+    let label_withheld = AccessMask(label_withheld(sd, token.integrity_level, mapping));
+    let mut report = AccessReport {
+        granted: AccessMask(0),
+        principal_granted: AccessMask(0),
+        app_container_granted: None,
+        label_withheld,
+        owner_rights: AccessMask(0),
+        null_dacl: false,
+        effects: Vec::new(),
+        unevaluated: Vec::new(),
+        mapping,
+    };
+
+    let dacl = sd.dacl.as_ref().filter(|dacl| !dacl.is_null());
+    let Some(dacl) = dacl else {
+        report.null_dacl = true;
+        report.principal_granted = mapping.all;
+        if token.is_app_container() {
+            report.app_container_granted = Some(mapping.all);
+        }
+        report.granted = AccessMask(mapping.all.0 & !label_withheld.0);
+        return report;
+    };
+
+    let owner_rights_ace = dacl
+        .aces
+        .iter()
+        .any(|ace| applies_to_object(ace) && ace.sid == owner_rights_sid());
+    let owner_bits = |owns: bool| {
+        if owns && !owner_rights_ace {
+            READ_CONTROL | WRITE_DAC
+        } else {
+            0
+        }
+    };
+
+    let owns = sd
+        .owner
+        .as_ref()
+        .is_some_and(|owner| *owner == token.user || token.groups.contains(owner));
+    let principal_sids: Vec<&Sid> = std::iter::once(&token.user).chain(&token.groups).collect();
+    report.owner_rights.0 |= owner_bits(owns);
+    report.principal_granted = AccessMask(walk(
+        dacl,
+        owner_bits(owns),
+        |sid, _| principal_sids.contains(&sid) || (owns && *sid == owner_rights_sid()),
+        CheckPass::Principal,
+        mapping,
+        &mut report,
+    ));
+    let mut granted = report.principal_granted.0;
+
+    if let Some(package) = &token.package {
+        let owns = sd.owner.as_ref() == Some(package);
+        report.owner_rights.0 |= owner_bits(owns);
+        let app_granted = walk(
+            dacl,
+            owner_bits(owns),
+            |sid, deny| {
+                sid == package
+                    || (owns && *sid == owner_rights_sid())
+                    || (well_known::is_all_app_packages(sid)
+                        && (sid.sub_authorities() == [2, 2] || !token.lpac))
+                    || token.capabilities.iter().any(|cap| {
+                        cap.sid == *sid
+                            && (cap.attributes & SidAndAttributes::ENABLED != 0
+                                || (deny
+                                    && cap.attributes & SidAndAttributes::USE_FOR_DENY_ONLY != 0))
+                    })
+            },
+            CheckPass::AppContainer,
+            mapping,
+            &mut report,
+        );
+        report.app_container_granted = Some(AccessMask(app_granted));
+        granted &= app_granted;
+    }
+
+    report.granted = AccessMask(granted & !label_withheld.0);
+    report
+}
+
+/// Like [`effective_access`], parsing `sddl` first.
+pub fn effective_access_sddl(
+    sddl: &str,
+    token: &TokenModel,
+    mapping: GenericMapping,
+) -> Result<AccessReport> {
+    Ok(effective_access(
+        &SecurityDescriptor::parse(sddl)?,
+        token,
+        mapping,
+    ))
+}
+
+/// One maximum-allowed walk of `dacl`. `matches(sid, is_deny)` says whether an ACE applies.
+fn walk(
+    dacl: &Acl,
+    preset: u32,
+    matches: impl Fn(&Sid, bool) -> bool,
+    pass: CheckPass,
+    mapping: GenericMapping,
+    report: &mut AccessReport,
+) -> u32 {
+    let mut granted = preset;
+    let mut denied = 0u32;
+    for (index, ace) in dacl.aces.iter().enumerate() {
+        if !applies_to_object(ace) || ace.object_type.is_some() {
+            continue;
+        }
+        let deny = match ace.ace_type {
+            AceType::AccessAllowed | AceType::AccessAllowedObject => false,
+            AceType::AccessDenied | AceType::AccessDeniedObject => true,
+            t if t.is_allow() || t.is_deny() => {
+                if !report.unevaluated.contains(&index) {
+                    report.unevaluated.push(index);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if !matches(&ace.sid, deny) {
+            continue;
+        }
+        let bits = mapping.map(ace.mask).0 & !granted & !denied;
+        if bits == 0 {
+            continue;
+        }
+        let (granted_bits, denied_bits) = if deny {
+            denied |= bits;
+            (0, bits)
+        } else {
+            granted |= bits;
+            (bits, 0)
+        };
+        report.effects.push(AceEffect {
+            pass,
+            index,
+            ace: ace.clone(),
+            granted: AccessMask(granted_bits),
+            denied: AccessMask(denied_bits),
+        });
+    }
+    granted
+}
+
+/// Rights the object's mandatory label withholds from a token at `level`.
+fn label_withheld(sd: &SecurityDescriptor, level: u32, mapping: GenericMapping) -> u32 {
+    let label = sd.sacl.as_ref().and_then(|sacl| {
+        sacl.aces
+            .iter()
+            .filter(|ace| ace.ace_type == AceType::MandatoryLabel && applies_to_object(ace))
+            .find_map(|ace| well_known::integrity_level(&ace.sid).map(|l| (l, ace.mask.0)))
+    });
+    let (object_level, policy) = label.unwrap_or((TokenModel::MEDIUM, NO_WRITE_UP));
+    if level >= object_level {
+        return 0;
+    }
+    let mut withheld = 0;
+    if policy & NO_WRITE_UP != 0 {
+        withheld |= mapping.write.0 | DELETE | WRITE_DAC | WRITE_OWNER;
+    }
+    if policy & NO_READ_UP != 0 {
+        withheld |= mapping.read.0;
+    }
+    if policy & NO_EXECUTE_UP != 0 {
+        withheld |= mapping.execute.0;
+    }
+    withheld & !(READ_CONTROL | SYNCHRONIZE)
+}
+
+fn applies_to_object(ace: &Ace) -> bool {
+    !ace.flags.contains(AceFlags::INHERIT_ONLY)
+}
+
+fn owner_rights_sid() -> Sid {
+    Sid::from_parts(3, vec![4])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::derive_capability;
+
+    const PACKAGE: &str = "S-1-15-2-1-2-3-4-5-6-7";
+
+    fn sid(s: &str) -> Sid {
+        well_known::resolve(s).unwrap()
+    }
+
+    fn user() -> TokenModel {
+        TokenModel::new(sid("S-1-5-21-1-2-3-1001")).with_groups(&[sid("WD"), sid("BU")])
+    }
+
+    fn container(lpac: bool) -> TokenModel {
+        user()
+            .with_package(&AppContainerSid::from_sddl(PACKAGE))
+            .unwrap()
+            .with_lpac(lpac)
+    }
+
+    fn check(sddl: &str, token: &TokenModel) -> AccessReport {
+        effective_access_sddl(sddl, token, GenericMapping::FILE).unwrap()
+    }
+
+    #[test]
+    fn app_container_needs_both_passes() {
+        let sddl = "D:(A;;FA;;;SY)(A;;FA;;;BU)(A;;FR;;;AC)";
+        let report = check(sddl, &user());
+        assert_eq!(report.granted, AccessMask::GENERIC_ALL);
+        assert_eq!(report.app_container_granted, None);
+
+        let report = check(sddl, &container(false));
+        assert_eq!(report.principal_granted, AccessMask::GENERIC_ALL);
+        assert_eq!(report.granted, AccessMask(0x0012_0089));
+        assert!(report.allows(AccessMask(GENERIC_READ)));
+        assert!(!report.allows(AccessMask::FILE_GENERIC_WRITE));
+        let why = report.explain(AccessMask::FILE_GENERIC_WRITE);
+        assert!(why.contains("mandatory label withholds"), "{why}");
+        assert!(why.contains("AppContainer: no ACE grants"), "{why}");
+        assert!(
+            report
+                .explain(AccessMask::FILE_GENERIC_READ)
+                .contains("AppContainer: ACE #2 (A;;FR;;;AC)")
+        );
+    }
+
+    #[test]
+    fn lpac_ignores_all_application_packages() {
+        let report = check("D:(A;;FA;;;BU)(A;;FR;;;AC)", &container(true));
+        assert_eq!(report.granted, AccessMask(0));
+        let report = check(
+            "D:(A;;FA;;;BU)(A;;FR;;;AC)(A;;FR;;;S-1-15-2-2)",
+            &container(true),
+        );
+        assert_eq!(report.granted, AccessMask(0x0012_0089));
+    }
+
+    #[test]
+    fn deny_aces_take_precedence_over_later_allows() {
+        let sddl = format!("D:(D;;FW;;;{PACKAGE})(A;;FA;;;BU)(A;;FA;;;AC)S:(ML;;NW;;;LW)");
+        let report = check(&sddl, &container(false));
+        assert_eq!(report.label_withheld, AccessMask(0));
+        assert_eq!(report.granted, AccessMask(0x001F_01FF & !0x0012_0116));
+        let why = report.explain(AccessMask::FILE_GENERIC_WRITE);
+        assert!(
+            why.contains(&format!("AppContainer: ACE #0 (D;;FW;;;{PACKAGE}) denies")),
+            "{why}"
+        );
+
+        // Out of canonical order, the earlier allow wins, as in AccessCheck.
+        let report = check("D:(A;;FA;;;WD)(D;;FA;;;WD)", &user());
+        assert_eq!(report.granted, AccessMask::GENERIC_ALL);
+    }
+
+    #[test]
+    fn capabilities_honour_attributes() {
+        let cap = derive_capability("documentsLibrary")
+            .unwrap()
+            .capability_sids[0]
+            .clone();
+        let sddl = format!("D:(A;;FA;;;BU)(A;;FR;;;{cap})");
+        let enabled = container(false).with_capabilities(std::slice::from_ref(&cap));
+        assert!(check(&sddl, &enabled).allows(AccessMask::FILE_GENERIC_READ));
+
+        let mut deny_only = container(false);
+        deny_only.capabilities.push(SidAndAttributes::new(
+            cap.clone(),
+            SidAndAttributes::USE_FOR_DENY_ONLY,
+        ));
+        assert_eq!(check(&sddl, &deny_only).granted, AccessMask(0));
+        let denied = format!("D:(D;;FR;;;{cap})(A;;FA;;;BU)(A;;FA;;;AC)");
+        assert!(!check(&denied, &deny_only).allows(AccessMask::FILE_GENERIC_READ));
+    }
+
+    #[test]
+    fn label_owner_and_null_dacl() {
+        // Low integrity tokens cannot write to unlabeled (Medium) objects.
+        let report = check("D:NO_ACCESS_CONTROL", &container(false));
+        assert!(report.null_dacl);
+        assert!(report.allows(AccessMask::FILE_GENERIC_READ));
+        assert!(!report.allows(AccessMask(DELETE)));
+        let report = check("S:(ML;;NWNR;;;HI)", &user());
+        assert!(!report.allows(AccessMask::FILE_GENERIC_READ));
+        assert!(report.allows(AccessMask(READ_CONTROL)));
+
+        let owned = "O:S-1-5-21-1-2-3-1001D:";
+        assert_eq!(
+            check(owned, &user()).granted,
+            AccessMask(READ_CONTROL | WRITE_DAC)
+        );
+        assert_eq!(check(owned, &container(false)).granted, AccessMask(0));
+        assert_eq!(
+            check("O:S-1-5-21-1-2-3-1001D:(A;;FR;;;OW)", &user()).granted,
+            AccessMask(0x0012_0089)
+        );
+    }
+
+    #[test]
+    fn conditional_and_inherit_only_aces_are_skipped() {
+        let report = check(
+            "D:(XA;;FA;;;WD;(@User.Title == \"PM\"))(A;OICIIO;FA;;;WD)(A;;GR;;;WD)",
+            &user(),
+        );
+        assert_eq!(report.unevaluated, vec![0]);
+        assert_eq!(report.granted, AccessMask(0x0012_0089));
+    }
+}
@@ -0,0 +1,309 @@
+//! Scoped DACL grants that restore the original DACL when dropped.
+//!
+//! A [`GrantGuard`] saves each target's DACL, applies the grant the way [`GrantPlan`] plans
+//! it, and writes the saved DACLs back on drop, last target first. DACLs are read and written
+//! through a [`DaclStore`]: [`NativeDacls`] talks to Windows, [`MemoryDacls`] keeps
+//! descriptors in memory so guard logic can be exercised on any OS.
+
+use std::sync::{Mutex, PoisonError};
+
+use super::{AccessMask, Acl, AclFlags, GrantPlan, ResourcePath, SecurityDescriptor};
+use crate::sid::Sid;
+use crate::{AcError, Result};
+
+/// Where a [`GrantGuard`] reads and writes DACLs.
+pub trait DaclStore {
+    /// The target's DACL, as a descriptor with only its `D:` section set.
+    fn read_dacl(&self, target: &ResourcePath) -> Result<SecurityDescriptor>;
+    /// Replaces the target's DACL.
+    fn write_dacl(&self, target: &ResourcePath, dacl: &Acl) -> Result<()>;
+}
+
+impl<S: DaclStore + ?Sized> DaclStore for &S {
+    fn read_dacl(&self, target: &ResourcePath) -> Result<SecurityDescriptor> {
+        (**self).read_dacl(target)
+    }
+
+    fn write_dacl(&self, target: &ResourcePath, dacl: &Acl) -> Result<()> {
+        (**self).write_dacl(target, dacl)
+    }
+}
+
+/// The DACLs of real files, directories and registry keys. Windows only; elsewhere every call
+/// fails with [`AcError::UnsupportedPlatform`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NativeDacls;
+
+impl DaclStore for NativeDacls {
+    #[cfg_attr(not(windows), allow(unused_variables))]
+    fn read_dacl(&self, target: &ResourcePath) -> Result<SecurityDescriptor> {
+        #[cfg(windows)]
+        {
+            super::native::read_dacl(target)
+        }
+        #[cfg(not(windows))]
+        {
+            Err(AcError::UnsupportedPlatform)
+        }
+    }
+
+    #[cfg_attr(not(windows), allow(unused_variables))]
+    fn write_dacl(&self, target: &ResourcePath, dacl: &Acl) -> Result<()> {
+        #[cfg(windows)]
+        {
+            super::native::write_dacl(target, dacl)
+        }
+        #[cfg(not(windows))]
+        {
+            Err(AcError::UnsupportedPlatform)
+        }
+    }
+}
+
+/// DACLs kept in memory, keyed by target. Reading a target that was never inserted fails with
+/// [`AcError::ResourceNotFound`].
+#[derive(Debug, Default)]
+pub struct MemoryDacls {
+    dacls: Mutex<Vec<(ResourcePath, SecurityDescriptor)>>,
+}
+
+impl MemoryDacls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `target` with the DACL of `sd`, replacing any earlier entry.
+    pub fn insert(&self, target: ResourcePath, sd: &SecurityDescriptor) {
+        let dacl = SecurityDescriptor {
+            dacl: sd.dacl.clone(),
+            ..Default::default()
+        };
+        let mut dacls = self.dacls.lock().unwrap_or_else(PoisonError::into_inner);
+        match dacls.iter_mut().find(|(t, _)| *t == target) {
+            Some((_, existing)) => *existing = dacl,
+            None => dacls.push((target, dacl)),
+        }
+    }
+
+    /// The stored descriptor for `target`, if any.
+    pub fn get(&self, target: &ResourcePath) -> Option<SecurityDescriptor> {
+        let dacls = self.dacls.lock().unwrap_or_else(PoisonError::into_inner);
+        dacls
+            .iter()
+            .find(|(t, _)| t == target)
+            .map(|(_, sd)| sd.clone())
+    }
+}
+
+impl DaclStore for MemoryDacls {
+    fn read_dacl(&self, target: &ResourcePath) -> Result<SecurityDescriptor> {
+        self.get(target).ok_or_else(|| AcError::ResourceNotFound {
+            path: target.to_string(),
+            hint: "insert the target into MemoryDacls first",
+        })
+    }
+
+    fn write_dacl(&self, target: &ResourcePath, dacl: &Acl) -> Result<()> {
+        self.read_dacl(target)?;
+        let sd = SecurityDescriptor {
+            dacl: Some(dacl.clone()),
+            ..Default::default()
+        };
+        self.insert(target.clone(), &sd);
+        Ok(())
+    }
+}
+
+/// Grants that are undone when the guard is dropped.
+///
+/// ```no_run
+/// # use rappct::acl::{AccessMask, GrantGuard, ResourcePath};
+/// # let profile = rappct::AppContainerProfile::ensure("rappct.guard", "guard", None).unwrap();
+/// let sid = profile.sid.as_sid().unwrap();
+/// let _guard = GrantGuard::new(
+///     ResourcePath::Directory("C:\\work\\scratch".into()),
+///     sid,
+///     AccessMask::FILE_GENERIC_READ,
+/// )
+/// .unwrap();
+/// // ... run the sandboxed job ...
+/// // The directory's original DACL is restored here.
+/// ```
+pub struct GrantGuard<S: DaclStore = NativeDacls> {
+    store: S,
+    /// Targets with the DACL they had before the grant, in grant order.
+    saved: Vec<(ResourcePath, SecurityDescriptor)>,
+    active: bool,
+}
+
+impl GrantGuard {
+    /// Grants `mask` to `sid` on `target`, restoring the current DACL on drop.
+    pub fn new(target: ResourcePath, sid: &Sid, mask: AccessMask) -> Result<Self> {
+        Self::with_store(NativeDacls, target, sid, mask)
+    }
+
+    /// Applies every grant in order. If one fails, the grants already applied are rolled back
+    /// and its error is returned.
+    pub fn new_all(
+        grants: impl IntoIterator<Item = (ResourcePath, Sid, AccessMask)>,
+    ) -> Result<Self> {
+        Self::all_with_store(NativeDacls, grants)
+    }
+}
+
+impl<S: DaclStore> GrantGuard<S> {
+    /// [`new`](GrantGuard::new) reading and writing DACLs through `store`.
+    pub fn with_store(store: S, target: ResourcePath, sid: &Sid, mask: AccessMask) -> Result<Self> {
+        Self::all_with_store(store, [(target, sid.clone(), mask)])
+    }
+
+    /// [`new_all`](GrantGuard::new_all) reading and writing DACLs through `store`.
+    pub fn all_with_store(
+        store: S,
+        grants: impl IntoIterator<Item = (ResourcePath, Sid, AccessMask)>,
+    ) -> Result<Self> {
+        let mut guard = Self {
+            store,
+            saved: Vec::new(),
+            active: true,
+        };
+        for (target, sid, mask) in grants {
+            // Dropping `guard` on error restores the grants made so far.
+            guard.grant(target, sid, mask)?;
+        }
+        Ok(guard)
+    }
+
+    fn grant(&mut self, target: ResourcePath, sid: Sid, mask: AccessMask) -> Result<()> {
+        let current = self.store.read_dacl(&target)?;
+        let plan = GrantPlan::new(target, sid, mask, &current);
+        self.store.write_dacl(&plan.target, plan.after_dacl()?)?;
+        self.saved.push((plan.target, plan.before));
+        Ok(())
+    }
+
+    /// Targets the guard will restore, in grant order.
+    pub fn targets(&self) -> impl Iterator<Item = &ResourcePath> {
+        self.saved.iter().map(|(target, _)| target)
+    }
+
+    /// Restores the saved DACLs now, reporting the first failure. Every target is attempted
+    /// even when an earlier one fails.
+    pub fn restore(mut self) -> Result<()> {
+        self.restore_saved()
+    }
+
+    /// Keep the grants: nothing is restored on drop.
+    pub fn disable(mut self) -> Self {
+        self.active = false;
+        self
+    }
+
+    fn restore_saved(&mut self) -> Result<()> {
+        let mut first_err = None;
+        while let Some((target, before)) = self.saved.pop() {
+            let dacl = before
+                .dacl
+                .unwrap_or_else(|| Acl::default().with_flags(AclFlags::NO_ACCESS_CONTROL));
+            if let Err(e) = self.store.write_dacl(&target, &dacl) {
+                first_err.get_or_insert(e);
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+}
+
+impl<S: DaclStore> Drop for GrantGuard<S> {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.restore_saved();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PACKAGE: &str = "S-1-15-2-1-2-3-4-5-6-7";
+
+    fn dir(name: &str) -> ResourcePath {
+        ResourcePath::Directory(PathBuf::from(format!("C:/{name}")))
+    }
+
+    fn store(targets: &[(&ResourcePath, &str)]) -> MemoryDacls {
+        let store = MemoryDacls::new();
+        for (target, sddl) in targets {
+            store.insert((*target).clone(), &SecurityDescriptor::parse(sddl).unwrap());
+        }
+        store
+    }
+
+    fn sddl(store: &MemoryDacls, target: &ResourcePath) -> String {
+        store.get(target).unwrap().to_string()
+    }
+
+    #[test]
+    fn drop_restores_the_saved_dacl() {
+        let (a, b) = (dir("a"), dir("b"));
+        let store = store(&[(&a, "D:PAI(A;OICI;FA;;;SY)"), (&b, "D:NO_ACCESS_CONTROL")]);
+        let sid = Sid::parse(PACKAGE).unwrap();
+        let grants = [
+            (a.clone(), sid.clone(), AccessMask::FILE_GENERIC_READ),
+            (b.clone(), sid.clone(), AccessMask::FILE_GENERIC_READ),
+            // A second grant on the same target must not clobber the first restore.
+            (a.clone(), sid, AccessMask::FILE_GENERIC_WRITE),
+        ];
+        let guard = GrantGuard::all_with_store(&store, grants).unwrap();
+        assert_eq!(
+            sddl(&store, &a),
+            format!("D:PAI(A;OICI;FA;;;SY)(A;OICI;0x12019f;;;{PACKAGE})")
+        );
+        assert_eq!(sddl(&store, &b), format!("D:(A;OICI;FR;;;{PACKAGE})"));
+        assert_eq!(guard.targets().count(), 3);
+        drop(guard);
+        assert_eq!(sddl(&store, &a), "D:PAI(A;OICI;FA;;;SY)");
+        assert_eq!(sddl(&store, &b), "D:NO_ACCESS_CONTROL");
+    }
+
+    #[test]
+    fn failed_grant_rolls_back_earlier_ones() {
+        let (a, missing) = (dir("a"), dir("missing"));
+        let store = store(&[(&a, "D:(A;;FA;;;SY)")]);
+        let sid = Sid::parse(PACKAGE).unwrap();
+        let err = GrantGuard::all_with_store(
+            &store,
+            [
+                (a.clone(), sid.clone(), AccessMask::GENERIC_ALL),
+                (missing, sid, AccessMask::GENERIC_ALL),
+            ],
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, AcError::ResourceNotFound { .. }));
+        assert_eq!(sddl(&store, &a), "D:(A;;FA;;;SY)");
+    }
+
+    #[test]
+    fn disabled_guards_keep_the_grant() {
+        let a = ResourcePath::File(PathBuf::from("C:/a.txt"));
+        let store = store(&[(&a, "D:(A;;FA;;;SY)")]);
+        let sid = Sid::parse(PACKAGE).unwrap();
+        let guard =
+            GrantGuard::with_store(&store, a.clone(), &sid, AccessMask::FILE_GENERIC_READ).unwrap();
+        drop(guard.disable());
+        assert_eq!(
+            sddl(&store, &a),
+            format!("D:(A;;FA;;;SY)(A;;FR;;;{PACKAGE})")
+        );
+
+        let guard = GrantGuard::with_store(&store, a.clone(), &sid, AccessMask::FILE_GENERIC_WRITE)
+            .unwrap();
+        guard.restore().unwrap();
+        assert_eq!(
+            sddl(&store, &a),
+            format!("D:(A;;FA;;;SY)(A;;FR;;;{PACKAGE})")
+        );
+    }
+}
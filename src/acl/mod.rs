@@ -1,12 +1,31 @@
-//! ACL helpers for files/directories and registry keys (DACL grant).
+//! ACL helpers for files/directories and registry keys (DACL grant and revoke), plus SDDL
+//! security descriptor parsing in [`sddl`] and offline access checks ([`effective_access`]).
+
+mod access;
+mod guard;
+#[cfg(windows)]
+mod native;
+mod plan;
+mod revoke;
+pub mod sddl;
+
+pub use access::{
+    AccessReport, AceEffect, CheckPass, GenericMapping, TokenModel, effective_access,
+    effective_access_sddl,
+};
+pub use guard::{DaclStore, GrantGuard, MemoryDacls, NativeDacls};
+pub use plan::GrantPlan;
+pub use revoke::{revoke_from_capability, revoke_from_package};
+pub use sddl::{Ace, AceFlags, AceType, Acl, AclFlags, SecurityDescriptor};
 
 #[cfg(windows)]
 use crate::ffi::mem::LocalAllocGuard;
-use crate::sid::AppContainerSid;
+use crate::sid::{AppContainerSid, well_known};
 use crate::{AcError, Result};
 
 /// ACE inheritance flags for directory ACL grants.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AceInheritance(pub u32);
 
 impl AceInheritance {
@@ -28,7 +47,8 @@ impl AceInheritance {
 ///   `HKCU\\`/`HKLM\\` and full names `HKEY_CURRENT_USER\\`/`HKEY_LOCAL_MACHINE\\`).
 /// - `Directory` uses [`AceInheritance::SUB_CONTAINERS_AND_OBJECTS`] by default.
 ///   Use `DirectoryCustom` to override the inheritance flags.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResourcePath {
     File(std::path::PathBuf),
     Directory(std::path::PathBuf),
@@ -37,7 +57,23 @@ pub enum ResourcePath {
     RegistryKey(String),
 }
 
-#[derive(Clone, Copy, Debug)]
+impl std::fmt::Display for ResourcePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Directory(path) => write!(f, "directory {}", path.display()),
+            Self::DirectoryCustom(path, inheritance) => write!(
+                f,
+                "directory {} (inheritance {:#x})",
+                path.display(),
+                inheritance.0
+            ),
+            Self::RegistryKey(key) => write!(f, "registry key {key}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct AccessMask(pub u32);
 
 impl AccessMask {
@@ -62,6 +98,10 @@ impl AccessMask {
 }
 
 /// Grants the specified access to the AppContainer package SID on the target resource.
+///
+/// The rights are merged in with `SetEntriesInAclW`. [`GrantPlan::for_package`] previews
+/// the change without applying it, and [`GrantPlan::apply`] writes a reviewed plan instead;
+/// [`revoke_from_package`] undoes it.
 #[cfg_attr(not(windows), allow(unused_variables))]
 pub fn grant_to_package(
     target: ResourcePath,
@@ -80,16 +120,20 @@ pub fn grant_to_package(
 }
 
 /// Grants the specified access to a capability SID on the target resource.
+///
+/// `cap_sid_sddl` may also be a well-known SDDL alias (`AC`, `WD`, ...) or account name
+/// (`ALL RESTRICTED APPLICATION PACKAGES`); see [`crate::sid::well_known`].
 #[cfg_attr(not(windows), allow(unused_variables))]
 pub fn grant_to_capability(
     target: ResourcePath,
     cap_sid_sddl: &str,
     access: AccessMask,
 ) -> Result<()> {
+    let sid_sddl = resolve_trustee(cap_sid_sddl);
     #[cfg(windows)]
     // SAFETY: Calls platform ACL helper; pointer and lifetime contracts are encapsulated in `grant_sid_access`.
     unsafe {
-        grant_sid_access(target, cap_sid_sddl, access.0)
+        grant_sid_access(target, &sid_sddl, access.0)
     }
     #[cfg(not(windows))]
     {
@@ -97,24 +141,19 @@ pub fn grant_to_capability(
     }
 }
 
-#[cfg(windows)]
-#[allow(unsafe_op_in_unsafe_fn)]
-unsafe fn grant_sid_access(target: ResourcePath, sid_sddl: &str, access: u32) -> Result<()> {
-    use windows::Win32::Foundation::HANDLE;
-    use windows::Win32::Security::Authorization::{
-        ConvertStringSidToSidW, EXPLICIT_ACCESS_W, GetNamedSecurityInfoW, GetSecurityInfo,
-        SE_FILE_OBJECT, SE_REGISTRY_KEY, SetEntriesInAclW, SetNamedSecurityInfoW, SetSecurityInfo,
-        TRUSTEE_FORM, TRUSTEE_IS_SID, TRUSTEE_IS_WELL_KNOWN_GROUP, TRUSTEE_TYPE, TRUSTEE_W,
-    };
-    use windows::Win32::Security::{ACE_FLAGS, ACL, DACL_SECURITY_INFORMATION};
-    use windows::Win32::System::Registry::{
-        HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, KEY_WRITE, RegCloseKey,
-        RegOpenKeyExW,
-    };
-    use windows::core::{PCWSTR, PWSTR};
+/// Maps well-known aliases and account names to SID strings. Anything else, including
+/// domain-relative aliases, is passed through for `ConvertStringSidToSidW` to interpret.
+fn resolve_trustee(sid_or_alias: &str) -> String {
+    well_known::lookup(sid_or_alias)
+        .and_then(well_known::WellKnownSid::sid)
+        .map_or_else(|| sid_or_alias.to_string(), |sid| sid.to_string())
+}
 
-    // Pre-check: verify the target resource exists before attempting the ACL grant.
-    match &target {
+/// Verifies that a file or directory target exists before its ACL is touched. Registry key
+/// existence is validated when the key is opened.
+#[cfg(windows)]
+fn ensure_target_exists(target: &ResourcePath) -> Result<()> {
+    match target {
         ResourcePath::File(path) => {
             if !path.is_file() {
                 let hint = if path.exists() {
@@ -137,9 +176,52 @@ unsafe fn grant_sid_access(target: ResourcePath, sid_sddl: &str, access: u32) ->
             }
         }
         ResourcePath::RegistryKey(_) => {
-            // Registry key existence is validated by RegOpenKeyExW below.
+            // Registry key existence is validated by RegOpenKeyExW.
         }
     }
+    Ok(())
+}
+
+/// Splits an `HKCU\...`/`HKLM\...` spec into its root key and the UTF-16 subkey path.
+#[cfg(windows)]
+fn registry_root(spec: &str) -> Option<(windows::Win32::System::Registry::HKEY, Vec<u16>)> {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    const HKCU_PREFIX: &str = "HKCU\\";
+    const HKEY_CURRENT_USER_PREFIX: &str = "HKEY_CURRENT_USER\\";
+    const HKLM_PREFIX: &str = "HKLM\\";
+    const HKEY_LOCAL_MACHINE_PREFIX: &str = "HKEY_LOCAL_MACHINE\\";
+
+    let up = spec.to_ascii_uppercase();
+    let (root, rest) = if up.starts_with(HKCU_PREFIX) {
+        (HKEY_CURRENT_USER, &spec[HKCU_PREFIX.len()..])
+    } else if up.starts_with(HKEY_CURRENT_USER_PREFIX) {
+        (HKEY_CURRENT_USER, &spec[HKEY_CURRENT_USER_PREFIX.len()..])
+    } else if up.starts_with(HKLM_PREFIX) {
+        (HKEY_LOCAL_MACHINE, &spec[HKLM_PREFIX.len()..])
+    } else if up.starts_with(HKEY_LOCAL_MACHINE_PREFIX) {
+        (HKEY_LOCAL_MACHINE, &spec[HKEY_LOCAL_MACHINE_PREFIX.len()..])
+    } else {
+        return None;
+    };
+    let w: Vec<u16> = crate::ffi::wstr::to_utf16(rest);
+    Some((root, w))
+}
+
+#[cfg(windows)]
+#[allow(unsafe_op_in_unsafe_fn)]
+unsafe fn grant_sid_access(target: ResourcePath, sid_sddl: &str, access: u32) -> Result<()> {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Security::Authorization::{
+        ConvertStringSidToSidW, EXPLICIT_ACCESS_W, GetNamedSecurityInfoW, GetSecurityInfo,
+        SE_FILE_OBJECT, SE_REGISTRY_KEY, SetEntriesInAclW, SetNamedSecurityInfoW, SetSecurityInfo,
+        TRUSTEE_FORM, TRUSTEE_IS_SID, TRUSTEE_IS_WELL_KNOWN_GROUP, TRUSTEE_TYPE, TRUSTEE_W,
+    };
+    use windows::Win32::Security::{ACE_FLAGS, ACL, DACL_SECURITY_INFORMATION};
+    use windows::Win32::System::Registry::{HKEY, KEY_READ, KEY_WRITE, RegCloseKey, RegOpenKeyExW};
+    use windows::core::{PCWSTR, PWSTR};
+
+    ensure_target_exists(&target)?;
 
     // Convert SDDL to PSID
     let wide: Vec<u16> = crate::ffi::wstr::to_utf16(sid_sddl);
@@ -285,29 +367,7 @@ unsafe fn grant_sid_access(target: ResourcePath, sid_sddl: &str, access: u32) ->
             Ok(())
         }
         ResourcePath::RegistryKey(spec) => {
-            // Parse root and subkey
-            fn parse_root(spec: &str) -> Option<(HKEY, Vec<u16>)> {
-                const HKCU_PREFIX: &str = "HKCU\\";
-                const HKEY_CURRENT_USER_PREFIX: &str = "HKEY_CURRENT_USER\\";
-                const HKLM_PREFIX: &str = "HKLM\\";
-                const HKEY_LOCAL_MACHINE_PREFIX: &str = "HKEY_LOCAL_MACHINE\\";
-
-                let up = spec.to_ascii_uppercase();
-                let (root, rest) = if up.starts_with(HKCU_PREFIX) {
-                    (HKEY_CURRENT_USER, &spec[HKCU_PREFIX.len()..])
-                } else if up.starts_with(HKEY_CURRENT_USER_PREFIX) {
-                    (HKEY_CURRENT_USER, &spec[HKEY_CURRENT_USER_PREFIX.len()..])
-                } else if up.starts_with(HKLM_PREFIX) {
-                    (HKEY_LOCAL_MACHINE, &spec[HKLM_PREFIX.len()..])
-                } else if up.starts_with(HKEY_LOCAL_MACHINE_PREFIX) {
-                    (HKEY_LOCAL_MACHINE, &spec[HKEY_LOCAL_MACHINE_PREFIX.len()..])
-                } else {
-                    return None;
-                };
-                let w: Vec<u16> = crate::ffi::wstr::to_utf16(rest);
-                Some((root, w))
-            }
-            let Some((root, subkey_w)) = parse_root(&spec) else {
+            let Some((root, subkey_w)) = registry_root(&spec) else {
                 return Err(AcError::Win32(
                     "Unsupported registry root (use HKCU or HKLM)".into(),
                 ));
@@ -403,6 +463,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn trustee_aliases_resolve_to_sid_strings() {
+        use super::resolve_trustee;
+        assert_eq!(resolve_trustee("AC"), "S-1-15-2-1");
+        assert_eq!(
+            resolve_trustee("ALL RESTRICTED APPLICATION PACKAGES"),
+            "S-1-15-2-2"
+        );
+        assert_eq!(resolve_trustee("S-1-15-3-1"), "S-1-15-3-1");
+        // Domain-relative aliases and garbage are left for Win32 to interpret or reject.
+        assert_eq!(resolve_trustee("DA"), "DA");
+        assert_eq!(resolve_trustee("not-a-sid"), "not-a-sid");
+    }
+
     #[test]
     fn ace_inheritance_constants_match_win32_values() {
         // OBJECT_INHERIT_ACE = 0x1, CONTAINER_INHERIT_ACE = 0x2
@@ -0,0 +1,224 @@
+//! Reading and writing whole DACLs of [`ResourcePath`] targets through SDDL.
+
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Security::Authorization::{
+    ConvertSecurityDescriptorToStringSecurityDescriptorW,
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, GetNamedSecurityInfoW, GetSecurityInfo,
+    SDDL_REVISION_1, SE_FILE_OBJECT, SE_REGISTRY_KEY, SetNamedSecurityInfoW, SetSecurityInfo,
+};
+use windows::Win32::Security::{
+    ACL, DACL_SECURITY_INFORMATION, GetLengthSid, GetSecurityDescriptorDacl,
+    OBJECT_SECURITY_INFORMATION, PROTECTED_DACL_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR,
+    UNPROTECTED_DACL_SECURITY_INFORMATION,
+};
+use windows::Win32::System::Registry::{HKEY, KEY_READ, REG_SAM_FLAGS, RegCloseKey, RegOpenKeyExW};
+use windows::core::{BOOL, PCWSTR, PWSTR};
+
+use super::{Acl, AclFlags, ResourcePath, SecurityDescriptor, ensure_target_exists, registry_root};
+use crate::ffi::mem::LocalAllocGuard;
+use crate::ffi::sid::OwnedSid;
+use crate::sid::Sid;
+use crate::{AcError, Result};
+
+const WRITE_DAC: u32 = 0x0004_0000;
+
+/// Registry key handle closed on drop.
+struct RegKey(HKEY);
+
+impl RegKey {
+    /// Opens the key with `access`: `KEY_READ` to read the DACL, `WRITE_DAC` to replace it.
+    fn open(spec: &str, access: REG_SAM_FLAGS) -> Result<Self> {
+        let Some((root, subkey_w)) = registry_root(spec) else {
+            return Err(AcError::Win32(
+                "Unsupported registry root (use HKCU or HKLM)".into(),
+            ));
+        };
+        let mut hkey = HKEY(std::ptr::null_mut());
+        // SAFETY: `subkey_w` is NUL-terminated and `hkey` receives the opened handle.
+        let st =
+            unsafe { RegOpenKeyExW(root, PCWSTR(subkey_w.as_ptr()), Some(0), access, &mut hkey) };
+        if st.0 != 0 {
+            return Err(AcError::Win32(format!("RegOpenKeyExW failed: {st:?}")));
+        }
+        Ok(Self(hkey))
+    }
+}
+
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        // SAFETY: The handle was opened by RegOpenKeyExW and is closed exactly once.
+        let _ = unsafe { RegCloseKey(self.0) };
+    }
+}
+
+/// Reads the DACL of `target` as a descriptor with only its `D:` section set.
+pub(super) fn read_dacl(target: &ResourcePath) -> Result<SecurityDescriptor> {
+    ensure_target_exists(target)?;
+    let mut p_sd = PSECURITY_DESCRIPTOR(std::ptr::null_mut());
+    let (call, st) = match target {
+        ResourcePath::File(path)
+        | ResourcePath::Directory(path)
+        | ResourcePath::DirectoryCustom(path, _) => {
+            let path_w = crate::ffi::wstr::to_utf16_os(path.as_os_str());
+            // SAFETY: `path_w` is NUL-terminated; the OS allocates the descriptor via LocalAlloc.
+            let st = unsafe {
+                GetNamedSecurityInfoW(
+                    PCWSTR(path_w.as_ptr()),
+                    SE_FILE_OBJECT,
+                    DACL_SECURITY_INFORMATION,
+                    None,
+                    None,
+                    None,
+                    None,
+                    &mut p_sd,
+                )
+            };
+            ("GetNamedSecurityInfoW", st)
+        }
+        ResourcePath::RegistryKey(spec) => {
+            let key = RegKey::open(spec, KEY_READ)?;
+            // SAFETY: `key` is an open registry handle; the descriptor is LocalAlloc-managed.
+            let st = unsafe {
+                GetSecurityInfo(
+                    HANDLE(key.0.0),
+                    SE_REGISTRY_KEY,
+                    DACL_SECURITY_INFORMATION,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(&mut p_sd),
+                )
+            };
+            ("GetSecurityInfo", st)
+        }
+    };
+    if st.0 != 0 {
+        return Err(AcError::Win32(format!("{call} failed: {st:?}")));
+    }
+    // SAFETY: The descriptor came from LocalAlloc; the guard frees it once.
+    let sd_guard = unsafe { LocalAllocGuard::from_raw(p_sd.0) };
+    let mut text = PWSTR::null();
+    // SAFETY: `sd_guard` holds a valid self-relative descriptor; `text` receives a LocalAlloc
+    // string.
+    unsafe {
+        ConvertSecurityDescriptorToStringSecurityDescriptorW(
+            PSECURITY_DESCRIPTOR(sd_guard.as_ptr()),
+            SDDL_REVISION_1,
+            DACL_SECURITY_INFORMATION,
+            &mut text,
+            None,
+        )
+    }
+    .map_err(|e| {
+        AcError::Win32(format!(
+            "ConvertSecurityDescriptorToStringSecurityDescriptorW failed: {e}"
+        ))
+    })?;
+    // SAFETY: `text` is a NUL-terminated LocalAlloc string owned by the guard.
+    let text_guard = unsafe { LocalAllocGuard::from_raw(text.0) };
+    // SAFETY: The guard holds a valid NUL-terminated UTF-16 buffer.
+    let sddl = unsafe { text_guard.to_string_lossy() };
+    SecurityDescriptor::parse_with(&sddl, &resolve_alias)
+}
+
+/// Replaces the DACL of `target` with `dacl`, keeping or lifting inheritance protection as
+/// its `P` flag says.
+pub(super) fn write_dacl(target: &ResourcePath, dacl: &Acl) -> Result<()> {
+    ensure_target_exists(target)?;
+    let sddl = SecurityDescriptor {
+        dacl: Some(dacl.clone()),
+        ..Default::default()
+    }
+    .to_string();
+    let sddl_w = crate::ffi::wstr::to_utf16(&sddl);
+    let mut p_sd = PSECURITY_DESCRIPTOR(std::ptr::null_mut());
+    // SAFETY: `sddl_w` is NUL-terminated; the OS allocates the descriptor via LocalAlloc.
+    unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            PCWSTR(sddl_w.as_ptr()),
+            SDDL_REVISION_1,
+            &mut p_sd,
+            None,
+        )
+    }
+    .map_err(|e| {
+        AcError::Win32(format!(
+            "ConvertStringSecurityDescriptorToSecurityDescriptorW failed: {e}"
+        ))
+    })?;
+    // SAFETY: The descriptor came from LocalAlloc; the guard frees it once.
+    let sd_guard = unsafe { LocalAllocGuard::from_raw(p_sd.0) };
+    let mut present = BOOL(0);
+    let mut defaulted = BOOL(0);
+    let mut p_dacl: *mut ACL = std::ptr::null_mut();
+    // SAFETY: The descriptor is valid; `p_dacl` points into it and lives as long as the guard.
+    unsafe {
+        GetSecurityDescriptorDacl(
+            PSECURITY_DESCRIPTOR(sd_guard.as_ptr()),
+            &mut present,
+            &mut p_dacl,
+            &mut defaulted,
+        )
+    }
+    .map_err(|e| AcError::Win32(format!("GetSecurityDescriptorDacl failed: {e}")))?;
+    let protection = if dacl.flags.contains(AclFlags::PROTECTED) {
+        PROTECTED_DACL_SECURITY_INFORMATION
+    } else {
+        UNPROTECTED_DACL_SECURITY_INFORMATION
+    };
+    let info = OBJECT_SECURITY_INFORMATION(DACL_SECURITY_INFORMATION.0 | protection.0);
+    let new_dacl = (!p_dacl.is_null()).then_some(p_dacl as *const ACL);
+    let (call, st) = match target {
+        ResourcePath::File(path)
+        | ResourcePath::Directory(path)
+        | ResourcePath::DirectoryCustom(path, _) => {
+            let path_w = crate::ffi::wstr::to_utf16_os(path.as_os_str());
+            // SAFETY: `path_w` is NUL-terminated and `new_dacl` is null or a valid ACL.
+            let st = unsafe {
+                SetNamedSecurityInfoW(
+                    PCWSTR(path_w.as_ptr()),
+                    SE_FILE_OBJECT,
+                    info,
+                    None,
+                    None,
+                    new_dacl,
+                    None,
+                )
+            };
+            ("SetNamedSecurityInfoW", st)
+        }
+        ResourcePath::RegistryKey(spec) => {
+            let key = RegKey::open(spec, REG_SAM_FLAGS(WRITE_DAC))?;
+            // SAFETY: `key` is open with WRITE_DAC and `new_dacl` is null or a valid ACL.
+            let st = unsafe {
+                SetSecurityInfo(
+                    HANDLE(key.0.0),
+                    SE_REGISTRY_KEY,
+                    info,
+                    None,
+                    None,
+                    new_dacl,
+                    None,
+                )
+            };
+            ("SetSecurityInfo", st)
+        }
+    };
+    if st.0 != 0 {
+        return Err(AcError::Win32(format!("{call} failed: {st:?}")));
+    }
+    Ok(())
+}
+
+/// Resolves domain-relative aliases the way the descriptor's writer did, through
+/// `ConvertStringSidToSidW`.
+fn resolve_alias(alias: &str) -> Result<Sid> {
+    let owned = OwnedSid::from_sddl(alias)?;
+    // SAFETY: `owned` holds a valid SID of `GetLengthSid` bytes.
+    let bytes = unsafe {
+        let len = GetLengthSid(owned.as_psid()) as usize;
+        std::slice::from_raw_parts(owned.as_psid().0 as *const u8, len)
+    };
+    Sid::from_bytes(bytes)
+}
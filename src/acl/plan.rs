@@ -0,0 +1,309 @@
+//! Dry-run planning of DACL grants.
+//!
+//! A [`GrantPlan`] records what [`grant_to_package`](super::grant_to_package) or
+//! [`grant_to_capability`](super::grant_to_capability) would do to a target: the trustee,
+//! the allow ACE and the DACL before and after. Plans are plain values, so they can be
+//! printed for an operator, saved with the `serde` feature and compared in tests on any OS.
+//!
+//! The resulting DACL follows `SetEntriesInAclW` with `GRANT_ACCESS`: the rights are merged
+//! into an existing explicit allow ACE for the trustee with the same flags, otherwise a new
+//! ACE goes after the explicit ACEs and before the inherited ones. A NULL DACL is replaced by
+//! a DACL holding only the new ACE.
+
+use std::fmt;
+
+use super::{AccessMask, Ace, AceFlags, AceInheritance, AceType, Acl, AclFlags};
+use super::{ResourcePath, SecurityDescriptor};
+use crate::sid::{AppContainerSid, Sid, well_known};
+use crate::{AcError, Result};
+
+/// A DACL grant computed ahead of time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GrantPlan {
+    pub target: ResourcePath,
+    pub trustee: Sid,
+    /// The allow ACE the grant adds or merges into.
+    pub ace: Ace,
+    /// The target's DACL when the plan was made (`D:` section only).
+    pub before: SecurityDescriptor,
+    /// The DACL [`apply`](Self::apply) writes (`D:` section only).
+    pub after: SecurityDescriptor,
+}
+
+impl GrantPlan {
+    /// Plans a grant of `access` to `trustee` against a known DACL. Nothing is read from or
+    /// written to the target.
+    pub fn new(
+        target: ResourcePath,
+        trustee: Sid,
+        access: AccessMask,
+        current: &SecurityDescriptor,
+    ) -> Self {
+        let ace = Ace::allow(trustee.clone(), access).with_flags(ace_flags_for(&target));
+        let before = SecurityDescriptor {
+            dacl: current.dacl.clone(),
+            ..Default::default()
+        };
+        let after = SecurityDescriptor {
+            dacl: Some(merge_grant(current.dacl.as_ref(), &ace)),
+            ..Default::default()
+        };
+        Self {
+            target,
+            trustee,
+            ace,
+            before,
+            after,
+        }
+    }
+
+    /// Plans [`grant_to_package`](super::grant_to_package) against the target's current DACL.
+    pub fn for_package(
+        target: ResourcePath,
+        sid: &AppContainerSid,
+        access: AccessMask,
+    ) -> Result<Self> {
+        let trustee = sid.as_sid().cloned().ok_or_else(|| {
+            AcError::InvalidSid(format!("'{}' is not a valid package SID", sid.as_string()))
+        })?;
+        let current = read_dacl(&target)?;
+        Ok(Self::new(target, trustee, access, &current))
+    }
+
+    /// Plans [`grant_to_capability`](super::grant_to_capability) against the target's current
+    /// DACL. `cap_sid_sddl` may be a SID string, well-known alias or account name.
+    pub fn for_capability(
+        target: ResourcePath,
+        cap_sid_sddl: &str,
+        access: AccessMask,
+    ) -> Result<Self> {
+        let trustee = well_known::resolve(cap_sid_sddl)?;
+        let current = read_dacl(&target)?;
+        Ok(Self::new(target, trustee, access, &current))
+    }
+
+    /// Whether applying the plan would change the DACL.
+    pub fn changes_dacl(&self) -> bool {
+        self.before != self.after
+    }
+
+    /// Writes [`after`](Self::after) to the target.
+    ///
+    /// An opt-in alternative to [`grant_to_package`](super::grant_to_package) and
+    /// [`grant_to_capability`](super::grant_to_capability), which merge through
+    /// `SetEntriesInAclW` and are unaffected. The whole DACL is rewritten.
+    ///
+    /// Fails with [`AcError::StaleDacl`] without writing when the target's DACL no longer
+    /// matches [`before`](Self::before) at apply time. The check and the write are separate
+    /// calls, so a change made between them is still overwritten. Fails with
+    /// [`AcError::InvalidPlan`] when `after` has no DACL (only possible for a plan edited or
+    /// deserialized by hand).
+    pub fn apply(&self) -> Result<()> {
+        #[cfg(windows)]
+        {
+            let dacl = self.after_dacl()?;
+            let current = super::native::read_dacl(&self.target)?;
+            if current.dacl != self.before.dacl {
+                return Err(AcError::StaleDacl {
+                    target: self.target.to_string(),
+                    current: current.to_string(),
+                });
+            }
+            super::native::write_dacl(&self.target, dacl)
+        }
+        #[cfg(not(windows))]
+        {
+            Err(AcError::UnsupportedPlatform)
+        }
+    }
+}
+
+impl GrantPlan {
+    /// The DACL [`after`](Self::after) holds.
+    pub(super) fn after_dacl(&self) -> Result<&Acl> {
+        self.after.dacl.as_ref().ok_or_else(|| {
+            AcError::InvalidPlan(format!("plan for {} has no DACL to write", self.target))
+        })
+    }
+}
+
+impl fmt::Display for GrantPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "grant on {}", self.target)?;
+        writeln!(f, "  trustee: {}", self.trustee)?;
+        writeln!(f, "  ace:     {}", self.ace)?;
+        writeln!(f, "  before:  {}", self.before)?;
+        write!(f, "  after:   {}", self.after)
+    }
+}
+
+/// ACE flags a grant on `target` uses: none for files and registry keys, the directory's
+/// inheritance otherwise.
+pub(super) fn ace_flags_for(target: &ResourcePath) -> AceFlags {
+    match target {
+        ResourcePath::Directory(_) => AceInheritance::SUB_CONTAINERS_AND_OBJECTS.into(),
+        ResourcePath::DirectoryCustom(_, inheritance) => (*inheritance).into(),
+        ResourcePath::File(_) | ResourcePath::RegistryKey(_) => AceFlags::NONE,
+    }
+}
+
+fn merge_grant(current: Option<&Acl>, ace: &Ace) -> Acl {
+    let Some(current) = current.filter(|acl| !acl.is_null()) else {
+        let flags = current.map_or(AclFlags::NONE, |acl| {
+            AclFlags(acl.flags.0 & !AclFlags::NO_ACCESS_CONTROL.0)
+        });
+        return Acl::new(vec![ace.clone()]).with_flags(flags);
+    };
+    let mut acl = current.clone();
+    let existing = acl.aces.iter_mut().find(|e| {
+        e.ace_type == AceType::AccessAllowed
+            && !e.is_inherited()
+            && e.sid == ace.sid
+            && e.flags == ace.flags
+            && e.application_data.is_none()
+    });
+    match existing {
+        Some(existing) => existing.mask = AccessMask(existing.mask.0 | ace.mask.0),
+        None => {
+            let at = acl
+                .aces
+                .iter()
+                .position(Ace::is_inherited)
+                .unwrap_or(acl.aces.len());
+            acl.aces.insert(at, ace.clone());
+        }
+    }
+    acl
+}
+
+#[cfg(not(windows))]
+fn read_dacl(_target: &ResourcePath) -> Result<SecurityDescriptor> {
+    Err(AcError::UnsupportedPlatform)
+}
+
+#[cfg(windows)]
+fn read_dacl(target: &ResourcePath) -> Result<SecurityDescriptor> {
+    super::native::read_dacl(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PACKAGE: &str = "S-1-15-2-1-2-3-4-5-6-7";
+
+    fn plan(target: ResourcePath, current: &str, access: AccessMask) -> GrantPlan {
+        GrantPlan::new(
+            target,
+            Sid::parse(PACKAGE).unwrap(),
+            access,
+            &SecurityDescriptor::parse(current).unwrap(),
+        )
+    }
+
+    #[test]
+    fn new_ace_goes_between_explicit_and_inherited_aces() {
+        let p = plan(
+            ResourcePath::Directory(PathBuf::from("C:/data")),
+            "D:PAI(D;;FA;;;AN)(A;OICIID;FA;;;SY)",
+            AccessMask::FILE_GENERIC_READ,
+        );
+        assert_eq!(p.ace.to_string(), format!("(A;OICI;FR;;;{PACKAGE})"));
+        assert_eq!(
+            p.after.to_string(),
+            format!("D:PAI(D;;FA;;;AN)(A;OICI;FR;;;{PACKAGE})(A;OICIID;FA;;;SY)")
+        );
+        assert!(p.changes_dacl());
+        let shown = p.to_string();
+        assert!(shown.starts_with("grant on directory C:/data"), "{shown}");
+        assert!(shown.contains("before:  D:PAI(D;;FA;;;AN)(A;OICIID;FA;;;SY)"));
+    }
+
+    #[test]
+    fn matching_explicit_ace_is_merged() {
+        let current = format!("D:(A;;FR;;;{PACKAGE})(A;;FR;;;SY)");
+        let p = plan(
+            ResourcePath::File(PathBuf::from("C:/data/a.txt")),
+            &current,
+            AccessMask::FILE_GENERIC_WRITE,
+        );
+        assert_eq!(
+            p.after.to_string(),
+            format!("D:(A;;0x12019f;;;{PACKAGE})(A;;FR;;;SY)")
+        );
+        // Granting rights that are already there plans no change.
+        let p = plan(
+            ResourcePath::File(PathBuf::from("C:/data/a.txt")),
+            &current,
+            AccessMask::FILE_GENERIC_READ,
+        );
+        assert!(!p.changes_dacl());
+    }
+
+    #[test]
+    fn null_dacl_is_replaced() {
+        let p = plan(
+            ResourcePath::DirectoryCustom(PathBuf::from("C:/data"), AceInheritance::OBJECTS_ONLY),
+            "D:NO_ACCESS_CONTROL",
+            AccessMask::GENERIC_ALL,
+        );
+        assert_eq!(p.after.to_string(), format!("D:(A;OI;FA;;;{PACKAGE})"));
+        let p = plan(
+            ResourcePath::RegistryKey("HKCU\\Software\\rappct".into()),
+            "",
+            AccessMask(0x0002_0019),
+        );
+        assert_eq!(p.before.to_string(), "");
+        assert_eq!(p.after.to_string(), format!("D:(A;;KR;;;{PACKAGE})"));
+    }
+
+    #[test]
+    fn plans_without_a_dacl_are_rejected() {
+        let mut p = plan(
+            ResourcePath::File(PathBuf::from("C:/a.txt")),
+            "D:",
+            AccessMask(1),
+        );
+        assert!(p.after_dacl().is_ok());
+        p.after.dacl = None;
+        assert!(matches!(p.after_dacl(), Err(AcError::InvalidPlan(_))));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn reading_targets_needs_windows() {
+        let err = GrantPlan::for_capability(
+            ResourcePath::File(PathBuf::from("/tmp/x")),
+            "AC",
+            AccessMask::GENERIC_ALL,
+        )
+        .unwrap_err();
+        assert!(matches!(err, AcError::UnsupportedPlatform));
+        assert!(matches!(
+            plan(
+                ResourcePath::File(PathBuf::from("/tmp/x")),
+                "D:",
+                AccessMask(1)
+            )
+            .apply(),
+            Err(AcError::UnsupportedPlatform)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn plans_serialize_as_sddl() {
+        let p = plan(
+            ResourcePath::Directory(PathBuf::from("C:/data")),
+            "D:(A;;FA;;;SY)",
+            AccessMask::FILE_GENERIC_READ,
+        );
+        let json = serde_json::to_value(&p).unwrap();
+        assert_eq!(json["ace"], format!("(A;OICI;FR;;;{PACKAGE})"));
+        assert_eq!(json["before"], "D:(A;;FA;;;SY)");
+        let back: GrantPlan = serde_json::from_value(json).unwrap();
+        assert_eq!(back, p);
+    }
+}
@@ -0,0 +1,126 @@
+//! Removing explicit ACEs that earlier grants added.
+//!
+//! [`revoke_from_package`] and [`revoke_from_capability`] undo
+//! [`grant_to_package`](super::grant_to_package) and
+//! [`grant_to_capability`](super::grant_to_capability): every explicit ACE for the trustee is
+//! dropped from the DACL, or only those with a given mask. Inherited ACEs come from the parent
+//! and are left alone; they disappear when the parent's grant is revoked.
+
+use super::{AccessMask, Ace, Acl, ResourcePath};
+use crate::sid::{AppContainerSid, Sid, well_known};
+use crate::{AcError, Result};
+
+/// Removes the explicit ACEs for the AppContainer package SID from the target's DACL.
+///
+/// With `mask`, only ACEs whose access mask equals it are removed. Returns the number of
+/// ACEs removed; the DACL is not rewritten when that is zero.
+pub fn revoke_from_package(
+    target: ResourcePath,
+    sid: &AppContainerSid,
+    mask: Option<AccessMask>,
+) -> Result<usize> {
+    let trustee = sid.as_sid().ok_or_else(|| {
+        AcError::InvalidSid(format!("'{}' is not a valid package SID", sid.as_string()))
+    })?;
+    revoke_sid(&target, trustee, mask)
+}
+
+/// Removes the explicit ACEs for a capability SID from the target's DACL.
+///
+/// `cap_sid_sddl` may be a SID string, well-known alias or account name, as for
+/// [`grant_to_capability`](super::grant_to_capability). `mask` and the return value work as
+/// in [`revoke_from_package`].
+pub fn revoke_from_capability(
+    target: ResourcePath,
+    cap_sid_sddl: &str,
+    mask: Option<AccessMask>,
+) -> Result<usize> {
+    let trustee = well_known::resolve(cap_sid_sddl)?;
+    revoke_sid(&target, &trustee, mask)
+}
+
+#[cfg(windows)]
+fn revoke_sid(target: &ResourcePath, sid: &Sid, mask: Option<AccessMask>) -> Result<usize> {
+    let current = super::native::read_dacl(target)?;
+    let Some(dacl) = current.dacl.as_ref() else {
+        return Ok(0);
+    };
+    let (kept, removed) = remove_explicit(dacl, sid, mask);
+    if !removed.is_empty() {
+        super::native::write_dacl(target, &kept)?;
+    }
+    Ok(removed.len())
+}
+
+#[cfg(not(windows))]
+fn revoke_sid(_target: &ResourcePath, _sid: &Sid, _mask: Option<AccessMask>) -> Result<usize> {
+    Err(AcError::UnsupportedPlatform)
+}
+
+/// Splits `acl` into the ACEs a revoke keeps and the ones it removes: explicit ACEs of any
+/// type for `sid`, narrowed to those whose mask equals `mask` when given. ACL flags and the
+/// order of the kept ACEs are preserved.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn remove_explicit(acl: &Acl, sid: &Sid, mask: Option<AccessMask>) -> (Acl, Vec<Ace>) {
+    let (removed, kept): (Vec<Ace>, Vec<Ace>) = acl.aces.iter().cloned().partition(|ace| {
+        !ace.is_inherited() && ace.sid == *sid && mask.is_none_or(|mask| ace.mask == mask)
+    });
+    (Acl::new(kept).with_flags(acl.flags), removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acl::SecurityDescriptor;
+
+    const PACKAGE: &str = "S-1-15-2-1-2-3-4-5-6-7";
+
+    fn revoke(sddl: &str, mask: Option<AccessMask>) -> (String, usize) {
+        let sd = SecurityDescriptor::parse(sddl).unwrap();
+        let (kept, removed) = remove_explicit(
+            sd.dacl.as_ref().unwrap(),
+            &Sid::parse(PACKAGE).unwrap(),
+            mask,
+        );
+        let after = SecurityDescriptor {
+            dacl: Some(kept),
+            ..Default::default()
+        };
+        (after.to_string(), removed.len())
+    }
+
+    #[test]
+    fn explicit_aces_are_removed_and_inherited_ones_kept() {
+        let sddl = format!(
+            "D:AI(D;;FW;;;{PACKAGE})(A;OICI;FR;;;{PACKAGE})(A;;FA;;;SY)(A;OICIID;FA;;;{PACKAGE})"
+        );
+        assert_eq!(
+            revoke(&sddl, None),
+            (format!("D:AI(A;;FA;;;SY)(A;OICIID;FA;;;{PACKAGE})"), 2)
+        );
+    }
+
+    #[test]
+    fn mask_narrows_the_selection() {
+        let sddl = format!("D:P(A;;FR;;;{PACKAGE})(A;;FA;;;{PACKAGE})(A;;FR;;;SY)");
+        assert_eq!(
+            revoke(&sddl, Some(AccessMask::FILE_GENERIC_READ)),
+            (format!("D:P(A;;FA;;;{PACKAGE})(A;;FR;;;SY)"), 1)
+        );
+        assert_eq!(revoke(&sddl, Some(AccessMask::FILE_GENERIC_WRITE)).1, 0);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn revoking_needs_windows() {
+        let target = ResourcePath::Directory(std::path::PathBuf::from("/tmp/x"));
+        assert!(matches!(
+            revoke_from_capability(target.clone(), "AC", None),
+            Err(AcError::UnsupportedPlatform)
+        ));
+        assert!(matches!(
+            revoke_from_package(target, &AppContainerSid::from_sddl(PACKAGE), None),
+            Err(AcError::UnsupportedPlatform)
+        ));
+    }
+}
@@ -0,0 +1,922 @@
+//! SDDL security descriptor strings.
+//!
+//! [`SecurityDescriptor`] models the owner, group, DACL and SACL of a descriptor string such
+//! as `O:BAG:SYD:PAI(A;OICI;FA;;;SY)(A;;0x1200a9;;;S-1-15-2-1)` without calling Windows, so
+//! descriptors can be built, reviewed and compared on any host.
+//!
+//! Formatting follows `ConvertSecurityDescriptorToStringSecurityDescriptorW`: sections in
+//! `O`, `G`, `D`, `S` order, aliases for well-known SIDs, ACL flags as `P`, `AR`, `AI`, ACE
+//! flags in bit order, and rights as a whole-mask alias (`FA`, `KR`), as per-bit tokens
+//! (`SDGXGWGR`) or as a hex mask when neither covers every bit. Strings in that form
+//! round-trip byte for byte. Other spellings of the same descriptor (`0x1f01ff` for `FA`,
+//! `S-1-5-18` for `SY`, upper-case GUIDs) parse to equal values and format canonically.
+//!
+//! Domain-relative aliases (`DA`, `LA`, ...) need the domain SID; parse those with
+//! [`SecurityDescriptor::parse_in_domain`]. They format as full SIDs.
+
+use std::fmt;
+
+use super::{AccessMask, AceInheritance};
+use crate::sid::{Sid, well_known};
+use crate::{AcError, Result};
+
+/// ACE types, with their SDDL abbreviations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AceType {
+    /// `A`
+    AccessAllowed,
+    /// `D`
+    AccessDenied,
+    /// `AU`
+    SystemAudit,
+    /// `AL`
+    SystemAlarm,
+    /// `OA`
+    AccessAllowedObject,
+    /// `OD`
+    AccessDeniedObject,
+    /// `OU`
+    SystemAuditObject,
+    /// `OL`
+    SystemAlarmObject,
+    /// `XA`
+    AccessAllowedCallback,
+    /// `XD`
+    AccessDeniedCallback,
+    /// `ZA`
+    AccessAllowedCallbackObject,
+    /// `XU`
+    SystemAuditCallback,
+    /// `ML`
+    MandatoryLabel,
+    /// `RA`
+    ResourceAttribute,
+    /// `SP`
+    ScopedPolicyId,
+    /// `TL`
+    ProcessTrustLabel,
+    /// `FL`
+    AccessFilter,
+}
+
+const ACE_TYPES: &[(AceType, &str, u8)] = &[
+    (AceType::AccessAllowed, "A", 0x00),
+    (AceType::AccessDenied, "D", 0x01),
+    (AceType::SystemAudit, "AU", 0x02),
+    (AceType::SystemAlarm, "AL", 0x03),
+    (AceType::AccessAllowedObject, "OA", 0x05),
+    (AceType::AccessDeniedObject, "OD", 0x06),
+    (AceType::SystemAuditObject, "OU", 0x07),
+    (AceType::SystemAlarmObject, "OL", 0x08),
+    (AceType::AccessAllowedCallback, "XA", 0x09),
+    (AceType::AccessDeniedCallback, "XD", 0x0A),
+    (AceType::AccessAllowedCallbackObject, "ZA", 0x0B),
+    (AceType::SystemAuditCallback, "XU", 0x0D),
+    (AceType::MandatoryLabel, "ML", 0x11),
+    (AceType::ResourceAttribute, "RA", 0x12),
+    (AceType::ScopedPolicyId, "SP", 0x13),
+    (AceType::ProcessTrustLabel, "TL", 0x14),
+    (AceType::AccessFilter, "FL", 0x15),
+];
+
+impl AceType {
+    /// Every ACE type SDDL can express.
+    pub const ALL: &'static [AceType] = &[
+        Self::AccessAllowed,
+        Self::AccessDenied,
+        Self::SystemAudit,
+        Self::SystemAlarm,
+        Self::AccessAllowedObject,
+        Self::AccessDeniedObject,
+        Self::SystemAuditObject,
+        Self::SystemAlarmObject,
+        Self::AccessAllowedCallback,
+        Self::AccessDeniedCallback,
+        Self::AccessAllowedCallbackObject,
+        Self::SystemAuditCallback,
+        Self::MandatoryLabel,
+        Self::ResourceAttribute,
+        Self::ScopedPolicyId,
+        Self::ProcessTrustLabel,
+        Self::AccessFilter,
+    ];
+
+    fn entry(self) -> &'static (AceType, &'static str, u8) {
+        ACE_TYPES
+            .iter()
+            .find(|(t, _, _)| *t == self)
+            .expect("every AceType has a table entry")
+    }
+
+    /// SDDL abbreviation (`A`, `OA`, `ML`, ...).
+    pub fn as_sddl(self) -> &'static str {
+        self.entry().1
+    }
+
+    /// `ACE_HEADER::AceType` value.
+    pub fn code(self) -> u8 {
+        self.entry().2
+    }
+
+    /// Looks up an SDDL abbreviation. ASCII case-insensitive.
+    pub fn from_sddl(s: &str) -> Option<Self> {
+        ACE_TYPES
+            .iter()
+            .find(|(_, abbrev, _)| abbrev.eq_ignore_ascii_case(s))
+            .map(|(t, _, _)| *t)
+    }
+
+    /// Whether the ACE grants access (`A`, `OA`, `XA`, `ZA`).
+    pub fn is_allow(self) -> bool {
+        matches!(
+            self,
+            Self::AccessAllowed
+                | Self::AccessAllowedObject
+                | Self::AccessAllowedCallback
+                | Self::AccessAllowedCallbackObject
+        )
+    }
+
+    /// Whether the ACE denies access (`D`, `OD`, `XD`).
+    pub fn is_deny(self) -> bool {
+        matches!(
+            self,
+            Self::AccessDenied | Self::AccessDeniedObject | Self::AccessDeniedCallback
+        )
+    }
+
+    /// Whether the ACE carries object type GUIDs (`OA`, `OD`, `OU`, `OL`, `ZA`).
+    pub fn is_object(self) -> bool {
+        matches!(
+            self,
+            Self::AccessAllowedObject
+                | Self::AccessDeniedObject
+                | Self::SystemAuditObject
+                | Self::SystemAlarmObject
+                | Self::AccessAllowedCallbackObject
+        )
+    }
+
+    /// Whether the ACE may carry a trailing condition or attribute (`XA`, `XD`, `ZA`, `XU`,
+    /// `RA`).
+    pub fn has_application_data(self) -> bool {
+        matches!(
+            self,
+            Self::AccessAllowedCallback
+                | Self::AccessDeniedCallback
+                | Self::AccessAllowedCallbackObject
+                | Self::SystemAuditCallback
+                | Self::ResourceAttribute
+        )
+    }
+}
+
+impl fmt::Display for AceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_sddl())
+    }
+}
+
+/// ACE header flags (`OI`, `CI`, `ID`, ...), using the Win32 bit values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AceFlags(pub u8);
+
+impl AceFlags {
+    /// `OI`: inherited by child objects.
+    pub const OBJECT_INHERIT: Self = Self(0x01);
+    /// `CI`: inherited by child containers.
+    pub const CONTAINER_INHERIT: Self = Self(0x02);
+    /// `NP`: inherited one level only.
+    pub const NO_PROPAGATE_INHERIT: Self = Self(0x04);
+    /// `IO`: applies to children only, not the object itself.
+    pub const INHERIT_ONLY: Self = Self(0x08);
+    /// `ID`: inherited from a parent rather than set explicitly.
+    pub const INHERITED: Self = Self(0x10);
+    /// `SA`: audit successful access.
+    pub const SUCCESSFUL_ACCESS: Self = Self(0x40);
+    /// `FA`: audit failed access.
+    pub const FAILED_ACCESS: Self = Self(0x80);
+
+    pub const NONE: Self = Self(0);
+
+    /// Whether every bit of `other` is set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for AceFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl From<AceInheritance> for AceFlags {
+    fn from(inheritance: AceInheritance) -> Self {
+        Self((inheritance.0 & 0xFF) as u8)
+    }
+}
+
+const ACE_FLAGS: &[(&str, AceFlags)] = &[
+    ("OI", AceFlags::OBJECT_INHERIT),
+    ("CI", AceFlags::CONTAINER_INHERIT),
+    ("NP", AceFlags::NO_PROPAGATE_INHERIT),
+    ("IO", AceFlags::INHERIT_ONLY),
+    ("ID", AceFlags::INHERITED),
+    ("SA", AceFlags::SUCCESSFUL_ACCESS),
+    ("FA", AceFlags::FAILED_ACCESS),
+];
+
+/// ACL flags written after `D:` or `S:`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AclFlags(pub u8);
+
+impl AclFlags {
+    /// `P`: inheritable ACEs from the parent are blocked.
+    pub const PROTECTED: Self = Self(0x1);
+    /// `AR`: auto-inheritance requested.
+    pub const AUTO_INHERIT_REQ: Self = Self(0x2);
+    /// `AI`: ACEs were auto-inherited.
+    pub const AUTO_INHERITED: Self = Self(0x4);
+    /// `NO_ACCESS_CONTROL`: a NULL ACL, which grants everyone full access. Such an ACL has
+    /// no ACEs.
+    pub const NO_ACCESS_CONTROL: Self = Self(0x8);
+
+    pub const NONE: Self = Self(0);
+
+    /// Whether every bit of `other` is set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for AclFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+const ACL_FLAGS: &[(&str, AclFlags)] = &[
+    ("P", AclFlags::PROTECTED),
+    ("AR", AclFlags::AUTO_INHERIT_REQ),
+    ("AI", AclFlags::AUTO_INHERITED),
+    ("NO_ACCESS_CONTROL", AclFlags::NO_ACCESS_CONTROL),
+];
+
+// Aliases that stand for a whole mask. `KX` equals `KR`; the first match is written.
+const WHOLE_MASK_RIGHTS: &[(&str, u32)] = &[
+    ("FA", 0x001F_01FF),
+    ("FR", 0x0012_0089),
+    ("FW", 0x0012_0116),
+    ("FX", 0x0012_00A0),
+    ("KA", 0x000F_003F),
+    ("KR", 0x0002_0019),
+    ("KW", 0x0002_0006),
+    ("KX", 0x0002_0019),
+];
+
+// Single-bit rights in ascending bit order, which is the order Windows writes them in.
+const BIT_RIGHTS: &[(&str, u32)] = &[
+    ("CC", 0x0000_0001),
+    ("DC", 0x0000_0002),
+    ("LC", 0x0000_0004),
+    ("SW", 0x0000_0008),
+    ("RP", 0x0000_0010),
+    ("WP", 0x0000_0020),
+    ("DT", 0x0000_0040),
+    ("LO", 0x0000_0080),
+    ("CR", 0x0000_0100),
+    ("SD", 0x0001_0000),
+    ("RC", 0x0002_0000),
+    ("WD", 0x0004_0000),
+    ("WO", 0x0008_0000),
+    ("GA", 0x1000_0000),
+    ("GX", 0x2000_0000),
+    ("GW", 0x4000_0000),
+    ("GR", 0x8000_0000),
+];
+
+// Mandatory label policy bits, written instead of `CC`/`DC`/`LC` in `ML` ACEs.
+const LABEL_RIGHTS: &[(&str, u32)] = &[("NW", 0x1), ("NR", 0x2), ("NX", 0x4)];
+
+/// One access control entry.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ace {
+    pub ace_type: AceType,
+    pub flags: AceFlags,
+    pub mask: AccessMask,
+    /// Object type GUID of object ACEs, lower-case.
+    pub object_type: Option<String>,
+    /// Inherited object type GUID of object ACEs, lower-case.
+    pub inherited_object_type: Option<String>,
+    pub sid: Sid,
+    /// Conditional expression of callback ACEs or attribute of `RA` ACEs, verbatim and
+    /// including its outer parentheses.
+    pub application_data: Option<String>,
+}
+
+impl Ace {
+    /// An ACE of `ace_type` without flags, GUIDs or application data.
+    pub fn new(ace_type: AceType, sid: Sid, mask: AccessMask) -> Self {
+        Self {
+            ace_type,
+            flags: AceFlags::NONE,
+            mask,
+            object_type: None,
+            inherited_object_type: None,
+            sid,
+            application_data: None,
+        }
+    }
+
+    /// An `A` ACE.
+    pub fn allow(sid: Sid, mask: AccessMask) -> Self {
+        Self::new(AceType::AccessAllowed, sid, mask)
+    }
+
+    /// A `D` ACE.
+    pub fn deny(sid: Sid, mask: AccessMask) -> Self {
+        Self::new(AceType::AccessDenied, sid, mask)
+    }
+
+    pub fn with_flags(mut self, flags: AceFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Whether the ACE came from a parent (`ID`).
+    pub fn is_inherited(&self) -> bool {
+        self.flags.contains(AceFlags::INHERITED)
+    }
+
+    /// Parses a single `(type;flags;rights;guid;guid;sid[;data])` entry.
+    pub fn parse(s: &str) -> Result<Self> {
+        parse_ace_entry(s, &no_domain)
+    }
+}
+
+impl fmt::Display for Ace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({};", self.ace_type)?;
+        for (token, flag) in ACE_FLAGS {
+            if self.flags.contains(*flag) {
+                f.write_str(token)?;
+            }
+        }
+        write!(
+            f,
+            ";{};{};{};{}",
+            format_rights(self.mask, self.ace_type),
+            self.object_type.as_deref().unwrap_or(""),
+            self.inherited_object_type.as_deref().unwrap_or(""),
+            well_known::to_sddl_token(&self.sid)
+        )?;
+        if let Some(data) = &self.application_data {
+            write!(f, ";{data}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl std::str::FromStr for Ace {
+    type Err = AcError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Ace {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Ace {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A DACL or SACL: flags and ACEs in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Acl {
+    pub flags: AclFlags,
+    pub aces: Vec<Ace>,
+}
+
+impl Acl {
+    pub fn new(aces: Vec<Ace>) -> Self {
+        Self {
+            flags: AclFlags::NONE,
+            aces,
+        }
+    }
+
+    pub fn with_flags(mut self, flags: AclFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Whether this is a NULL ACL (`NO_ACCESS_CONTROL`).
+    pub fn is_null(&self) -> bool {
+        self.flags.contains(AclFlags::NO_ACCESS_CONTROL)
+    }
+}
+
+impl fmt::Display for Acl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (token, flag) in ACL_FLAGS {
+            if self.flags.contains(*flag) {
+                f.write_str(token)?;
+            }
+        }
+        self.aces.iter().try_for_each(|ace| write!(f, "{ace}"))
+    }
+}
+
+/// Owner, group, DACL and SACL of a security descriptor. Absent sections are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SecurityDescriptor {
+    pub owner: Option<Sid>,
+    pub group: Option<Sid>,
+    pub dacl: Option<Acl>,
+    pub sacl: Option<Acl>,
+}
+
+impl SecurityDescriptor {
+    /// Parses an SDDL string. Domain-relative aliases are rejected.
+    pub fn parse(s: &str) -> Result<Self> {
+        parse_descriptor(s, &no_domain)
+    }
+
+    /// Parses an SDDL string, resolving domain-relative aliases (`DA`, `DU`, ...) against
+    /// `domain` (`S-1-5-21-a-b-c`).
+    pub fn parse_in_domain(s: &str, domain: &Sid) -> Result<Self> {
+        if !well_known::is_domain_sid(domain) {
+            return Err(AcError::InvalidSid(format!(
+                "expected a domain SID (S-1-5-21-a-b-c), got '{domain}'"
+            )));
+        }
+        parse_descriptor(s, &|alias| well_known::resolve_in_domain(alias, domain))
+    }
+
+    /// Parses an SDDL string, resolving domain-relative aliases with `resolve`.
+    #[cfg(windows)]
+    pub(crate) fn parse_with(s: &str, resolve: DomainAliases<'_>) -> Result<Self> {
+        parse_descriptor(s, resolve)
+    }
+}
+
+impl fmt::Display for SecurityDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(owner) = &self.owner {
+            write!(f, "O:{}", well_known::to_sddl_token(owner))?;
+        }
+        if let Some(group) = &self.group {
+            write!(f, "G:{}", well_known::to_sddl_token(group))?;
+        }
+        if let Some(dacl) = &self.dacl {
+            write!(f, "D:{dacl}")?;
+        }
+        if let Some(sacl) = &self.sacl {
+            write!(f, "S:{sacl}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for SecurityDescriptor {
+    type Err = AcError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SecurityDescriptor {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SecurityDescriptor {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+fn invalid(message: String) -> AcError {
+    AcError::InvalidSddl(message)
+}
+
+fn parse_descriptor(s: &str, domain: DomainAliases<'_>) -> Result<SecurityDescriptor> {
+    let mut sd = SecurityDescriptor::default();
+    for (tag, body) in split_sections(s)? {
+        let duplicate = match tag {
+            b'O' => sd.owner.replace(parse_sid(body, domain)?).is_some(),
+            b'G' => sd.group.replace(parse_sid(body, domain)?).is_some(),
+            b'D' => sd.dacl.replace(parse_acl(body, domain)?).is_some(),
+            _ => sd.sacl.replace(parse_acl(body, domain)?).is_some(),
+        };
+        if duplicate {
+            return Err(invalid(format!(
+                "section '{}:' appears more than once",
+                char::from(tag)
+            )));
+        }
+    }
+    Ok(sd)
+}
+
+/// Splits `s` at the top-level `O:`, `G:`, `D:` and `S:` markers.
+fn split_sections(s: &str) -> Result<Vec<(u8, &str)>> {
+    let bytes = s.as_bytes();
+    let mut markers: Vec<(u8, usize)> = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if quoted {
+            quoted = b != b'"';
+        } else {
+            match b {
+                b'"' if depth > 0 => quoted = true,
+                b'(' => depth += 1,
+                b')' => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or_else(|| invalid(format!("unbalanced ')' in '{s}'")))?;
+                }
+                b'O' | b'G' | b'D' | b'S' if depth == 0 && bytes.get(i + 1) == Some(&b':') => {
+                    markers.push((b, i));
+                    i += 2;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+    if depth != 0 || quoted {
+        return Err(invalid(format!("unterminated ACE in '{s}'")));
+    }
+    if !s.is_empty() && markers.first().map(|&(_, at)| at) != Some(0) {
+        return Err(invalid(format!(
+            "expected 'O:', 'G:', 'D:' or 'S:' at the start of '{s}'"
+        )));
+    }
+    Ok(markers
+        .iter()
+        .enumerate()
+        .map(|(n, &(tag, at))| {
+            let end = markers.get(n + 1).map_or(s.len(), |&(_, next)| next);
+            (tag, &s[at + 2..end])
+        })
+        .collect())
+}
+
+/// Resolves a domain-relative alias (`DA`, `LA`, ...) to a SID.
+pub(crate) type DomainAliases<'a> = &'a dyn Fn(&str) -> Result<Sid>;
+
+fn no_domain(alias: &str) -> Result<Sid> {
+    Err(invalid(format!(
+        "'{alias}' is relative to a domain; use SecurityDescriptor::parse_in_domain"
+    )))
+}
+
+fn parse_sid(token: &str, domain: DomainAliases<'_>) -> Result<Sid> {
+    if let Some(entry) = well_known::from_alias(token) {
+        return match entry.sid() {
+            Some(sid) => Ok(sid),
+            None => domain(token),
+        };
+    }
+    Sid::parse(token).map_err(|_| invalid(format!("'{token}' is not a SID or SDDL alias")))
+}
+
+fn parse_acl(body: &str, domain: DomainAliases<'_>) -> Result<Acl> {
+    let (mut flag_text, mut rest) = body.split_at(body.find('(').unwrap_or(body.len()));
+    let mut acl = Acl::default();
+    'flags: while !flag_text.is_empty() {
+        for (token, flag) in ACL_FLAGS {
+            if let Some(tail) = flag_text.strip_prefix(token) {
+                acl.flags = acl.flags | *flag;
+                flag_text = tail;
+                continue 'flags;
+            }
+        }
+        return Err(invalid(format!("unknown ACL flags '{flag_text}'")));
+    }
+    while !rest.is_empty() {
+        let end = ace_end(rest)
+            .ok_or_else(|| invalid(format!("expected '(' to start an ACE at '{rest}'")))?;
+        acl.aces.push(parse_ace_entry(&rest[..end], domain)?);
+        rest = &rest[end..];
+    }
+    if acl.is_null() && !acl.aces.is_empty() {
+        return Err(invalid(
+            "a NO_ACCESS_CONTROL ACL cannot contain ACEs".to_string(),
+        ));
+    }
+    Ok(acl)
+}
+
+/// Length of the parenthesized entry at the start of `s`.
+fn ace_end(s: &str) -> Option<usize> {
+    if !s.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for (i, b) in s.bytes().enumerate() {
+        match b {
+            b'"' => quoted = !quoted,
+            b'(' if !quoted => depth += 1,
+            b')' if !quoted => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_ace_entry(entry: &str, domain: DomainAliases<'_>) -> Result<Ace> {
+    let inner = entry
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| invalid(format!("ACE '{entry}' must be enclosed in parentheses")))?;
+    let fields: Vec<&str> = inner.splitn(7, ';').collect();
+    let [
+        ace_type,
+        flags,
+        rights,
+        object_type,
+        inherited_object_type,
+        sid,
+        rest @ ..,
+    ] = fields.as_slice()
+    else {
+        return Err(invalid(format!(
+            "ACE '{entry}' needs six ';'-separated fields"
+        )));
+    };
+    let ace_type = AceType::from_sddl(ace_type)
+        .ok_or_else(|| invalid(format!("unknown ACE type '{ace_type}' in '{entry}'")))?;
+    let object_type = parse_guid(object_type, ace_type, entry)?;
+    let inherited_object_type = parse_guid(inherited_object_type, ace_type, entry)?;
+    let application_data = match rest.first() {
+        None => None,
+        Some(data) if ace_type.has_application_data() && data.starts_with('(') => {
+            Some(data.to_string())
+        }
+        Some(data) => {
+            return Err(invalid(format!(
+                "unexpected trailing field '{data}' in '{entry}'"
+            )));
+        }
+    };
+    Ok(Ace {
+        ace_type,
+        flags: parse_ace_flags(flags, entry)?,
+        mask: parse_rights(rights, entry)?,
+        object_type,
+        inherited_object_type,
+        sid: parse_sid(sid, domain)?,
+        application_data,
+    })
+}
+
+fn parse_ace_flags(text: &str, entry: &str) -> Result<AceFlags> {
+    let mut flags = AceFlags::NONE;
+    for token in two_letter_tokens(text, entry)? {
+        let (_, flag) = ACE_FLAGS
+            .iter()
+            .find(|(t, _)| t.eq_ignore_ascii_case(token))
+            .ok_or_else(|| invalid(format!("unknown ACE flag '{token}' in '{entry}'")))?;
+        flags = flags | *flag;
+    }
+    Ok(flags)
+}
+
+fn parse_rights(text: &str, entry: &str) -> Result<AccessMask> {
+    let number = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(u32::from_str_radix(hex, 16))
+    } else if !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit()) {
+        Some(text.parse::<u32>())
+    } else {
+        None
+    };
+    if let Some(number) = number {
+        return number
+            .map(AccessMask)
+            .map_err(|_| invalid(format!("invalid access mask '{text}' in '{entry}'")));
+    }
+    let mut mask = 0u32;
+    for token in two_letter_tokens(text, entry)? {
+        let (_, bits) = WHOLE_MASK_RIGHTS
+            .iter()
+            .chain(BIT_RIGHTS)
+            .chain(LABEL_RIGHTS)
+            .find(|(t, _)| t.eq_ignore_ascii_case(token))
+            .ok_or_else(|| invalid(format!("unknown access right '{token}' in '{entry}'")))?;
+        mask |= bits;
+    }
+    Ok(AccessMask(mask))
+}
+
+fn format_rights(mask: AccessMask, ace_type: AceType) -> String {
+    let mask = mask.0;
+    if mask == 0 {
+        return String::new();
+    }
+    let bit_rights = if ace_type == AceType::MandatoryLabel {
+        LABEL_RIGHTS
+    } else {
+        if let Some((alias, _)) = WHOLE_MASK_RIGHTS.iter().find(|(_, m)| *m == mask) {
+            return (*alias).to_string();
+        }
+        BIT_RIGHTS
+    };
+    let mut rest = mask;
+    let mut out = String::new();
+    for (token, bit) in bit_rights {
+        if mask & bit != 0 {
+            out.push_str(token);
+            rest &= !bit;
+        }
+    }
+    if rest == 0 { out } else { format!("{mask:#x}") }
+}
+
+fn two_letter_tokens<'a>(text: &'a str, entry: &str) -> Result<Vec<&'a str>> {
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Err(invalid(format!(
+            "malformed token list '{text}' in '{entry}'"
+        )));
+    }
+    Ok((0..text.len())
+        .step_by(2)
+        .map(|i| &text[i..i + 2])
+        .collect())
+}
+
+fn parse_guid(text: &str, ace_type: AceType, entry: &str) -> Result<Option<String>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if !ace_type.is_object() {
+        return Err(invalid(format!(
+            "only object ACEs take GUIDs, got '{text}' in '{entry}'"
+        )));
+    }
+    let well_formed = text.len() == 36
+        && text.bytes().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        });
+    if !well_formed {
+        return Err(invalid(format!("malformed GUID '{text}' in '{entry}'")));
+    }
+    Ok(Some(text.to_ascii_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Descriptors as written by ConvertSecurityDescriptorToStringSecurityDescriptorW.
+    const CANONICAL: &[&str] = &[
+        "O:SYG:SYD:PAI(A;OICIIO;SDGXGWGR;;;AU)(A;;0x1301bf;;;AU)(A;OICIIO;GA;;;SY)(A;;FA;;;SY)(A;OICI;0x1200a9;;;BU)(A;OICIIO;GA;;;BA)(A;;FA;;;BA)",
+        "O:BAG:SYD:AI(A;CIID;KR;;;BU)(A;CIID;KA;;;BA)(A;CIIOID;GA;;;CO)(A;CIID;KR;;;AC)",
+        "D:P(A;OICI;FA;;;SY)(A;;FR;;;S-1-15-2-2)(D;;FW;;;S-1-15-3-1024-1065365936-1281604716-3511738428-1654721687-432734479-3232135806-4053264122-3456934681)S:(ML;OICI;NW;;;LW)",
+        "D:(A;;CCLCSWRPWPDTLOCRRC;;;SY)(OA;;CR;ab721a53-1e2f-11d0-9819-00aa0040529b;;PS)",
+        "D:(XA;;FX;;;WD;(@User.Title == \"PM\" && (@User.Division == \"Finance\")))",
+        "S:PARAI(AU;SAFA;FA;;;WD)(RA;;;;;WD;(\"Project\",TS,0,\"Windows\",\"SQL\"))",
+        "O:S-1-5-21-1-2-3-500D:NO_ACCESS_CONTROL",
+        "",
+    ];
+
+    #[test]
+    fn canonical_strings_round_trip_exactly() {
+        for sddl in CANONICAL {
+            let sd = SecurityDescriptor::parse(sddl).unwrap();
+            assert_eq!(sd.to_string(), *sddl);
+            assert_eq!(sd.to_string().parse::<SecurityDescriptor>().unwrap(), sd);
+        }
+    }
+
+    #[test]
+    fn parses_structure() {
+        let sd = SecurityDescriptor::parse(CANONICAL[2]).unwrap();
+        assert_eq!(sd.owner, None);
+        let dacl = sd.dacl.unwrap();
+        assert_eq!(dacl.flags, AclFlags::PROTECTED);
+        assert_eq!(dacl.aces[0].sid.to_string(), "S-1-5-18");
+        assert_eq!(
+            dacl.aces[0].flags,
+            AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT
+        );
+        assert_eq!(dacl.aces[0].mask, AccessMask::GENERIC_ALL);
+        assert!(dacl.aces[2].ace_type.is_deny());
+        let label = &sd.sacl.unwrap().aces[0];
+        assert_eq!(label.ace_type, AceType::MandatoryLabel);
+        assert_eq!(label.mask, AccessMask(0x1));
+        assert_eq!(label.sid.to_string(), "S-1-16-4096");
+    }
+
+    #[test]
+    fn other_spellings_format_canonically() {
+        let sd = SecurityDescriptor::parse(
+            "D:(a;ci;0x1F01FF;;;S-1-5-18)(A;;GRGX;;;s-1-1-0)(OA;;CR;AB721A53-1E2F-11D0-9819-00AA0040529B;;PS)",
+        )
+        .unwrap();
+        assert_eq!(
+            sd.to_string(),
+            "D:(A;CI;FA;;;SY)(A;;GXGR;;;WD)(OA;;CR;ab721a53-1e2f-11d0-9819-00aa0040529b;;PS)"
+        );
+        let built = SecurityDescriptor {
+            dacl: Some(Acl::new(vec![
+                Ace::allow(Sid::parse("S-1-15-2-1").unwrap(), AccessMask(0x1200a9))
+                    .with_flags(AceInheritance::SUB_CONTAINERS_AND_OBJECTS.into()),
+            ])),
+            ..Default::default()
+        };
+        assert_eq!(built.to_string(), "D:(A;OICI;0x1200a9;;;AC)");
+    }
+
+    #[test]
+    fn domain_relative_aliases_need_a_domain() {
+        assert!(matches!(
+            SecurityDescriptor::parse("O:DAD:(A;;FA;;;DA)"),
+            Err(AcError::InvalidSddl(_))
+        ));
+        let domain = Sid::parse("S-1-5-21-1-2-3").unwrap();
+        let sd = SecurityDescriptor::parse_in_domain("O:DAD:(A;;FA;;;DA)", &domain).unwrap();
+        assert_eq!(
+            sd.to_string(),
+            "O:S-1-5-21-1-2-3-512D:(A;;FA;;;S-1-5-21-1-2-3-512)"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_strings() {
+        for bad in [
+            "X:SY",
+            "O:SYO:BA",
+            "O:nobody",
+            "D:(A;;FA;;SY)",
+            "D:(QQ;;FA;;;SY)",
+            "D:(A;ZZ;FA;;;SY)",
+            "D:(A;;FQ;;;SY)",
+            "D:(A;;0xZZ;;;SY)",
+            "D:(A;;FA;;;SY",
+            "D:(A;;FA;;;SY))",
+            "D:Q(A;;FA;;;SY)",
+            "D:(A;;FA;ab721a53-1e2f-11d0-9819-00aa0040529b;;SY)",
+            "D:(OA;;CR;not-a-guid;;SY)",
+            "D:(A;;FA;;;SY;(x))",
+            "D:NO_ACCESS_CONTROL(A;;FA;;;SY)",
+        ] {
+            assert!(
+                matches!(SecurityDescriptor::parse(bad), Err(AcError::InvalidSddl(_))),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn every_ace_type_round_trips() {
+        for ace_type in AceType::ALL {
+            assert_eq!(AceType::from_sddl(ace_type.as_sddl()), Some(*ace_type));
+            let ace = Ace::new(*ace_type, Sid::parse("S-1-1-0").unwrap(), AccessMask(0x1));
+            assert_eq!(Ace::parse(&ace.to_string()).unwrap(), ace);
+        }
+    }
+}
@@ -0,0 +1,412 @@
+//! `AppxManifest.xml` interop (feature: `appx`).
+//!
+//! Reads the `<Identity>` and `<Capabilities>` elements of a package manifest so an MSIX
+//! package and a rappct sandbox can share one capability list. Parsing is pure Rust.
+//! See: <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-capabilities>
+
+use std::io::Read;
+
+use super::{
+    CapabilityCatalog, CapabilityCategory, CapabilityName, KnownCapability, ManifestNamespace,
+    SecurityCapabilities, SecurityCapabilitiesBuilder, publisher_id,
+};
+use crate::profile::derive_sid_from_name;
+use crate::sid::AppContainerSid;
+use crate::{AcError, Result};
+
+/// Prefix of the `uap`, `uap2`, ... namespace URIs.
+const UAP_NS_PREFIX: &str = "http://schemas.microsoft.com/appx/manifest/uap/windows10";
+
+/// The manifest element a capability was declared with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ManifestCapabilityKind {
+    /// `<Capability>` in the foundation namespace.
+    Foundation,
+    /// `<uap:Capability>` (any `uap*` namespace).
+    Uap,
+    /// `<rescap:Capability>`.
+    Restricted,
+    /// `<iot:Capability>`.
+    Iot,
+    /// `<DeviceCapability>`.
+    Device,
+    /// `<uap4:CustomCapability>`.
+    Custom,
+}
+
+impl ManifestCapabilityKind {
+    /// The element `capability` is declared with, or `None` for LPAC-only capabilities
+    /// that have no manifest element.
+    pub fn for_capability(capability: CapabilityName) -> Option<Self> {
+        if matches!(capability, CapabilityName::Custom(_)) {
+            return Some(Self::Custom);
+        }
+        let info = capability.info();
+        match info.category() {
+            CapabilityCategory::Device => Some(Self::Device),
+            CapabilityCategory::Lpac => None,
+            CapabilityCategory::General | CapabilityCategory::Restricted => {
+                match info.manifest_namespace() {
+                    ManifestNamespace::Foundation => Some(Self::Foundation),
+                    ManifestNamespace::Rescap => Some(Self::Restricted),
+                    ManifestNamespace::Iot => Some(Self::Iot),
+                    _ => Some(Self::Uap),
+                }
+            }
+        }
+    }
+
+    /// The element as usually written in a manifest.
+    pub const fn element(self) -> &'static str {
+        match self {
+            Self::Foundation => "Capability",
+            Self::Uap => "uap:Capability",
+            Self::Restricted => "rescap:Capability",
+            Self::Iot => "iot:Capability",
+            Self::Device => "DeviceCapability",
+            Self::Custom => "uap4:CustomCapability",
+        }
+    }
+}
+
+/// Namespace and qualified element `capability` is exported with, or `None` for LPAC-only
+/// capabilities. `uap` capabilities use the versioned namespace that introduced them.
+fn manifest_element(capability: CapabilityName) -> Option<(ManifestNamespace, String)> {
+    let kind = ManifestCapabilityKind::for_capability(capability)?;
+    let namespace = match kind {
+        ManifestCapabilityKind::Custom => ManifestNamespace::Uap4,
+        ManifestCapabilityKind::Device => ManifestNamespace::Foundation,
+        _ => capability.info().manifest_namespace(),
+    };
+    let local = match kind {
+        ManifestCapabilityKind::Device => "DeviceCapability",
+        ManifestCapabilityKind::Custom => "CustomCapability",
+        _ => "Capability",
+    };
+    let element = match namespace.prefix() {
+        Some(prefix) => format!("{prefix}:{local}"),
+        None => local.to_string(),
+    };
+    Some((namespace, element))
+}
+
+/// One entry of the `<Capabilities>` element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ManifestCapability {
+    /// The `Name` attribute as written.
+    pub name: String,
+    pub kind: ManifestCapabilityKind,
+    /// The catalog capability the name resolved to, if any.
+    pub capability: Option<CapabilityName>,
+}
+
+/// The `<Identity>` element of a package manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AppxIdentity {
+    pub name: String,
+    pub publisher: String,
+    pub version: Option<String>,
+    pub processor_architecture: Option<String>,
+    pub resource_id: Option<String>,
+}
+
+impl AppxIdentity {
+    /// AppContainer profile name for the package: its package family name
+    /// (`Name_<publisherId>`), lowercased.
+    pub fn profile_name(&self) -> String {
+        format!("{}_{}", self.name, publisher_id(&self.publisher)).to_lowercase()
+    }
+
+    /// AppContainer SID of [`profile_name`](Self::profile_name).
+    pub fn derive_profile_sid(&self) -> Result<AppContainerSid> {
+        derive_sid_from_name(&self.profile_name())
+    }
+}
+
+/// Something in the manifest that needs a human decision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ManifestWarning {
+    /// The name matches no catalog capability; it is not imported.
+    UnknownCapability {
+        kind: ManifestCapabilityKind,
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    /// A restricted capability; imported, but normally needs Store approval.
+    RestrictedCapability { capability: CapabilityName },
+}
+
+impl std::fmt::Display for ManifestWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCapability {
+                kind,
+                name,
+                suggestion,
+            } => {
+                write!(f, "unknown capability <{} Name=\"{name}\">", kind.element())?;
+                match suggestion {
+                    Some(suggestion) => write!(f, " (did you mean '{suggestion}'?)"),
+                    None => Ok(()),
+                }
+            }
+            Self::RestrictedCapability { capability } => {
+                write!(f, "restricted capability '{capability}'")
+            }
+        }
+    }
+}
+
+/// Capabilities imported from a manifest, from
+/// [`SecurityCapabilitiesBuilder::from_appx_manifest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppxManifestCapabilities {
+    identity: Option<AppxIdentity>,
+    declared: Vec<ManifestCapability>,
+    warnings: Vec<ManifestWarning>,
+}
+
+impl AppxManifestCapabilities {
+    /// Parses the text of an `AppxManifest.xml`.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let doc =
+            roxmltree::Document::parse(xml).map_err(|e| AcError::InvalidManifest(e.to_string()))?;
+        let package = doc.root_element();
+        if package.tag_name().name() != "Package" {
+            return Err(AcError::InvalidManifest(format!(
+                "root element is <{}>, expected <Package>",
+                package.tag_name().name()
+            )));
+        }
+        let child = |name: &str| {
+            package
+                .children()
+                .find(|node| node.is_element() && node.tag_name().name() == name)
+        };
+
+        let identity = match child("Identity") {
+            Some(node) => {
+                let attr = |name: &str| node.attribute(name).map(str::to_string);
+                Some(AppxIdentity {
+                    name: attr("Name").ok_or_else(|| {
+                        AcError::InvalidManifest("<Identity> has no Name".to_string())
+                    })?,
+                    publisher: attr("Publisher").ok_or_else(|| {
+                        AcError::InvalidManifest("<Identity> has no Publisher".to_string())
+                    })?,
+                    version: attr("Version"),
+                    processor_architecture: attr("ProcessorArchitecture"),
+                    resource_id: attr("ResourceId"),
+                })
+            }
+            None => None,
+        };
+
+        let mut declared = Vec::new();
+        let mut warnings = Vec::new();
+        let elements = child("Capabilities")
+            .into_iter()
+            .flat_map(|caps| caps.children().filter(|node| node.is_element()));
+        for node in elements {
+            let tag = node.tag_name();
+            let kind = match tag.name() {
+                "Capability" => match tag.namespace() {
+                    Some(ns) if ns == ManifestNamespace::Rescap.uri() => {
+                        ManifestCapabilityKind::Restricted
+                    }
+                    Some(ns) if ns == ManifestNamespace::Iot.uri() => ManifestCapabilityKind::Iot,
+                    Some(ns) if ns.starts_with(UAP_NS_PREFIX) => ManifestCapabilityKind::Uap,
+                    _ => ManifestCapabilityKind::Foundation,
+                },
+                "DeviceCapability" => ManifestCapabilityKind::Device,
+                "CustomCapability" => ManifestCapabilityKind::Custom,
+                _ => continue,
+            };
+            let name = node.attribute("Name").ok_or_else(|| {
+                AcError::InvalidManifest(format!("<{}> has no Name", kind.element()))
+            })?;
+            let resolved = match kind {
+                // Custom capability names are publisher-scoped; only exact, registered
+                // names resolve.
+                ManifestCapabilityKind::Custom => CapabilityName::from_name(name).ok_or(None),
+                _ => name.parse::<CapabilityName>().map_err(|e| match e {
+                    AcError::UnknownCapability { suggestion, .. } => suggestion,
+                    _ => None,
+                }),
+            };
+            let capability = match resolved {
+                Ok(capability) => {
+                    if kind == ManifestCapabilityKind::Restricted
+                        || capability.info().category() == CapabilityCategory::Restricted
+                    {
+                        warnings.push(ManifestWarning::RestrictedCapability { capability });
+                    }
+                    Some(capability)
+                }
+                Err(suggestion) => {
+                    warnings.push(ManifestWarning::UnknownCapability {
+                        kind,
+                        name: name.to_string(),
+                        suggestion,
+                    });
+                    None
+                }
+            };
+            declared.push(ManifestCapability {
+                name: name.to_string(),
+                kind,
+                capability,
+            });
+        }
+
+        Ok(Self {
+            identity,
+            declared,
+            warnings,
+        })
+    }
+
+    /// The package `<Identity>`, if the manifest has one.
+    pub fn identity(&self) -> Option<&AppxIdentity> {
+        self.identity.as_ref()
+    }
+
+    /// Every `<Capabilities>` entry, in document order.
+    pub fn declared(&self) -> &[ManifestCapability] {
+        &self.declared
+    }
+
+    /// The entries that resolved to catalog capabilities, in document order.
+    pub fn capabilities(&self) -> Vec<KnownCapability> {
+        self.declared
+            .iter()
+            .filter_map(|entry| entry.capability)
+            .collect()
+    }
+
+    pub fn warnings(&self) -> &[ManifestWarning] {
+        &self.warnings
+    }
+
+    /// A builder for `sid` holding the resolved capabilities.
+    pub fn with_profile_sid(&self, sid: &AppContainerSid) -> SecurityCapabilitiesBuilder {
+        SecurityCapabilitiesBuilder::new(sid).with_known(&self.capabilities())
+    }
+
+    /// A builder for the AppContainer derived from the manifest identity.
+    pub fn with_identity_sid(&self) -> Result<SecurityCapabilitiesBuilder> {
+        let identity = self
+            .identity
+            .as_ref()
+            .ok_or_else(|| AcError::InvalidManifest("manifest has no <Identity>".to_string()))?;
+        Ok(self.with_profile_sid(&identity.derive_profile_sid()?))
+    }
+}
+
+impl SecurityCapabilitiesBuilder {
+    /// Imports the `<Identity>` and `<Capabilities>` of an `AppxManifest.xml`.
+    ///
+    /// Unknown and restricted entries are reported through
+    /// [`AppxManifestCapabilities::warnings`]; unknown ones are not imported.
+    pub fn from_appx_manifest(mut reader: impl Read) -> Result<AppxManifestCapabilities> {
+        let mut xml = String::new();
+        reader
+            .read_to_string(&mut xml)
+            .map_err(|e| AcError::InvalidManifest(format!("read failed: {e}")))?;
+        AppxManifestCapabilities::parse(&xml)
+    }
+}
+
+/// Escapes `value` for use in a double-quoted XML attribute.
+fn escape_attribute(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl SecurityCapabilities {
+    /// Renders the capabilities as an `AppxManifest.xml` `<Capabilities>` fragment.
+    ///
+    /// Each SID is named through [`CapabilityCatalog::lookup_sid_value`] and written with
+    /// its element (`Capability`, `uap:Capability` or the `uapN` namespace of its
+    /// [`ManifestNamespace`], `rescap:Capability`, `iot:Capability`,
+    /// `uap4:CustomCapability`, `DeviceCapability`), device capabilities last as the schema
+    /// requires. The prefixes used are declared on `<Capabilities>`. LPAC-only capabilities
+    /// have no manifest element and are kept as comments. Fails with
+    /// [`AcError::UnnamedCapabilitySids`] listing every SID the catalog cannot name.
+    pub fn to_appx_capabilities(&self, catalog: &CapabilityCatalog) -> Result<String> {
+        let mut names: Vec<CapabilityName> = Vec::with_capacity(self.caps.len());
+        let mut unnamed = Vec::new();
+        for cap in &self.caps {
+            match catalog.lookup_sid_value(&cap.sid) {
+                Some(name) if names.contains(&name) => {}
+                Some(name) => names.push(name),
+                None => unnamed.push(cap.sid.to_string()),
+            }
+        }
+        if !unnamed.is_empty() {
+            return Err(AcError::UnnamedCapabilitySids(unnamed));
+        }
+
+        let entries: Vec<(CapabilityName, Option<(ManifestNamespace, String)>)> = {
+            let mut entries: Vec<_> = names
+                .into_iter()
+                .map(|name| (name, manifest_element(name)))
+                .collect();
+            // Stable: keeps the caller's order within each group.
+            entries.sort_by_key(|(name, _)| {
+                ManifestCapabilityKind::for_capability(*name)
+                    == Some(ManifestCapabilityKind::Device)
+            });
+            entries
+        };
+
+        let mut namespaces: Vec<(&str, &str)> = Vec::new();
+        for ns in entries
+            .iter()
+            .filter_map(|(_, element)| element.as_ref())
+            .filter_map(|(ns, _)| Some((ns.prefix()?, ns.uri())))
+        {
+            if !namespaces.contains(&ns) {
+                namespaces.push(ns);
+            }
+        }
+        let mut xml = String::from("<Capabilities");
+        for (prefix, uri) in namespaces {
+            xml.push_str(&format!(" xmlns:{prefix}=\"{uri}\""));
+        }
+        xml.push_str(">\n");
+        for (name, element) in entries {
+            let name = escape_attribute(name.as_str());
+            match element {
+                Some((_, element)) => xml.push_str(&format!("  <{element} Name=\"{name}\" />\n")),
+                None => xml.push_str(&format!(
+                    "  <!-- LPAC-only, no manifest element: {name} -->\n"
+                )),
+            }
+        }
+        xml.push_str("</Capabilities>\n");
+        Ok(xml)
+    }
+}
+
+impl SecurityCapabilitiesBuilder {
+    /// Builds a copy of the builder and renders it with
+    /// [`SecurityCapabilities::to_appx_capabilities`].
+    pub fn to_appx_capabilities(&self, catalog: &CapabilityCatalog) -> Result<String> {
+        self.clone().build()?.to_appx_capabilities(catalog)
+    }
+}
@@ -0,0 +1,119 @@
+//! Process-wide memo of capability name derivations.
+//!
+//! Every capability SID the crate derives from a name goes through [`derive`], so the
+//! catalog, the builders and the launch path share one table. Entries are keyed by the name
+//! as given; derivation is deterministic, so cached and uncached results are identical and
+//! the cache can be turned off or cleared at any time.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+use super::DerivedCapability;
+use crate::Result;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+fn cache() -> &'static RwLock<HashMap<String, DerivedCapability>> {
+    static CACHE: OnceLock<RwLock<HashMap<String, DerivedCapability>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Counters of the capability SID cache, from [`capability_cache_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CapabilityCacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that derived the SIDs, including those made while the cache was disabled.
+    pub misses: u64,
+    /// Names currently cached.
+    pub entries: usize,
+}
+
+/// Derives `name` through the cache, computing and storing it on a miss.
+pub(super) fn derive(
+    name: &str,
+    compute: fn(&str) -> Result<DerivedCapability>,
+) -> Result<DerivedCapability> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        MISSES.fetch_add(1, Ordering::Relaxed);
+        return compute(name);
+    }
+    if let Some(hit) = cache()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+    {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        return Ok(hit.clone());
+    }
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    let derived = compute(name)?;
+    cache()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(name.to_string(), derived.clone());
+    Ok(derived)
+}
+
+/// Derives and caches `names` ahead of time, e.g. before launching many sandboxed jobs.
+///
+/// Pass [`WELL_KNOWN_CAPABILITY_NAMES`](super::WELL_KNOWN_CAPABILITY_NAMES) to cover the
+/// whole built-in table. Stops at the first name that cannot be derived.
+pub fn prewarm_capability_cache<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    for name in names {
+        super::derive_capability(name)?;
+    }
+    Ok(())
+}
+
+/// Current hit and miss counts and the number of cached names.
+pub fn capability_cache_stats() -> CapabilityCacheStats {
+    CapabilityCacheStats {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        entries: cache()
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len(),
+    }
+}
+
+/// Enables or disables the cache; while disabled every lookup derives afresh.
+pub fn set_capability_cache_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Drops every cached name. The hit and miss counters keep running.
+pub fn clear_capability_cache() {
+    cache()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::derive_capability;
+
+    #[test]
+    fn cached_and_uncached_results_match() {
+        let name = "rappct.cacheTest";
+        let before = capability_cache_stats();
+        prewarm_capability_cache([name]).unwrap();
+        let cached = derive_capability(name).unwrap();
+        let after = capability_cache_stats();
+        assert!(after.misses > before.misses);
+        assert!(after.hits > before.hits);
+        assert!(after.entries >= 1);
+
+        set_capability_cache_enabled(false);
+        let uncached = derive_capability(name);
+        set_capability_cache_enabled(true);
+        assert_eq!(uncached.unwrap(), cached);
+        assert!(prewarm_capability_cache([""]).is_err());
+    }
+}
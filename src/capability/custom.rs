@@ -0,0 +1,475 @@
+//! Capabilities supplied at runtime rather than by the capability table.
+//!
+//! Custom capabilities are registered process-wide the first time a catalog successfully
+//! merges them, so [`CapabilityName::Custom`] stays `Copy` and its metadata lives as long as
+//! the process. Registering the same definition again is a no-op; different metadata for an
+//! already registered name is a conflict. A batch is checked and registered under one lock,
+//! so it lands whole or not at all. Only names are registered, and [`explain_sid`] knows the
+//! SIDs derived from them; precomputed SIDs are not registered: they belong to the catalog
+//! that merged the definition.
+//!
+//! [`explain_sid`]: super::explain_sid
+
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+use super::table::CustomName;
+use super::{CapabilityCategory, CapabilityInfo, CapabilityName, RiskCategory, RiskTier, explain};
+use crate::sid::{Sid, classify};
+use crate::{AcError, Result};
+
+/// One entry of a user-supplied capability catalog.
+///
+/// With the `config` feature, catalog files hold a list of these under `capability`:
+///
+/// ```toml
+/// [[capability]]
+/// name = "contosoTelemetry"
+/// description = "Contoso telemetry broker"
+/// category = "restricted"
+/// risk_tier = "high"
+/// risk_categories = ["network_egress", "user_data"]
+/// # Optional: use this SID instead of deriving one from the name.
+/// sid = "S-1-15-3-1024-..."
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct CapabilityDefinition {
+    /// Manifest name of the capability.
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub category: CapabilityCategory,
+    /// First Windows build providing the capability; `0` when unknown.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_build: u32,
+    /// Documentation links.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub docs: Vec<String>,
+    /// Precomputed capability SID; derived from `name` when absent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sid: Option<Sid>,
+    /// Defaults to [`RiskTier::Medium`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub risk_tier: RiskTier,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub risk_categories: Vec<RiskCategory>,
+}
+
+impl CapabilityDefinition {
+    /// A definition with only a name; the SID is derived from it.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+}
+
+type Registry = BTreeMap<&'static str, &'static CapabilityInfo>;
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+fn conflict(name: &str, reason: &'static str) -> AcError {
+    AcError::CatalogConflict {
+        name: name.to_string(),
+        reason,
+    }
+}
+
+/// Checks what `def` can get wrong on its own: its name and SID form, and clashes with the
+/// built-in table.
+pub(super) fn check_definition(def: &CapabilityDefinition) -> Result<()> {
+    if def.name.is_empty() {
+        return Err(AcError::InvalidName {
+            name: String::new(),
+            reason: "empty capability name",
+        });
+    }
+    if CapabilityName::ALL
+        .iter()
+        .any(|cap| cap.as_str().eq_ignore_ascii_case(&def.name))
+    {
+        return Err(conflict(&def.name, "name is already a built-in capability"));
+    }
+    if let Some(sid) = &def.sid
+        && !classify(sid).is_capability()
+    {
+        return Err(AcError::InvalidSid(format!(
+            "{sid} is not a capability SID (custom capability '{}')",
+            def.name
+        )));
+    }
+    Ok(())
+}
+
+/// Validates `def` against the built-in table and `registry`. Returns the registered name
+/// when an identical definition is already there.
+fn check(def: &CapabilityDefinition, registry: &Registry) -> Result<Option<CapabilityName>> {
+    check_definition(def)?;
+    let Some((&name, &info)) = registry
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&def.name))
+    else {
+        return Ok(None);
+    };
+    let same = name == def.name
+        && info.description() == def.description
+        && info.category() == def.category
+        && info.min_build() == def.min_build
+        && info.docs().iter().eq(def.docs.iter())
+        && info.risk_tier() == def.risk_tier
+        && info.risk_categories() == def.risk_categories.as_slice();
+    if same {
+        Ok(Some(info.name()))
+    } else {
+        Err(conflict(
+            &def.name,
+            "already registered with a different definition",
+        ))
+    }
+}
+
+/// Registers every definition of `defs` process-wide and returns their names in order, or
+/// fails without registering any. Names in `defs` must be distinct.
+pub(super) fn register_all(defs: &[&CapabilityDefinition]) -> Result<Vec<CapabilityName>> {
+    let mut registry = registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let existing = defs
+        .iter()
+        .map(|def| check(def, &registry))
+        .collect::<Result<Vec<_>>>()?;
+    let mut added = Vec::new();
+    let names = defs
+        .iter()
+        .zip(existing)
+        .map(|(def, existing)| {
+            existing.unwrap_or_else(|| {
+                let info = insert(&mut registry, def);
+                added.push(info.as_str());
+                info.name()
+            })
+        })
+        .collect();
+    drop(registry);
+
+    explain::register_capability_names(added);
+    Ok(names)
+}
+
+/// Leaks `def` into a `'static` [`CapabilityInfo`] and adds it to `registry`.
+fn insert(registry: &mut Registry, def: &CapabilityDefinition) -> &'static CapabilityInfo {
+    // Leaked once per distinct definition; see the module docs.
+    let name: &'static str = Box::leak(def.name.clone().into_boxed_str());
+    let docs: Vec<&'static str> = def
+        .docs
+        .iter()
+        .map(|doc| &*Box::leak(doc.clone().into_boxed_str()))
+        .collect();
+    let info: &'static CapabilityInfo = Box::leak(Box::new(CapabilityInfo::custom(
+        CustomName::new(name),
+        Box::leak(def.description.clone().into_boxed_str()),
+        def.category,
+        def.min_build,
+        Box::leak(docs.into_boxed_slice()),
+        def.risk_tier,
+        Box::leak(def.risk_categories.clone().into_boxed_slice()),
+    )));
+    registry.insert(name, info);
+    info
+}
+
+/// Metadata of a registered custom capability.
+pub(super) fn info(name: &str) -> Option<&'static CapabilityInfo> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+        .copied()
+}
+
+/// Names of every registered custom capability.
+pub(super) fn names() -> Vec<&'static str> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .keys()
+        .copied()
+        .collect()
+}
+
+/// Serialization format of capability catalog and policy files.
+#[cfg(feature = "config")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CatalogFormat {
+    Toml,
+    Json,
+}
+
+#[cfg(feature = "config")]
+impl CatalogFormat {
+    /// Format implied by the extension of `path` (`.toml` or `.json`).
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        if ext.eq_ignore_ascii_case("toml") {
+            Some(Self::Toml)
+        } else if ext.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogFile {
+    #[serde(default, alias = "capabilities")]
+    capability: Vec<CapabilityDefinition>,
+}
+
+/// Parses the definitions of a catalog file.
+#[cfg(feature = "config")]
+pub(super) fn parse(
+    reader: impl std::io::Read,
+    format: CatalogFormat,
+) -> Result<Vec<CapabilityDefinition>> {
+    let file: CatalogFile = deserialize(reader, format, AcError::InvalidCatalog)?;
+    Ok(file.capability)
+}
+
+/// Reads a TOML or JSON document, wrapping failures with `invalid`.
+#[cfg(feature = "config")]
+pub(super) fn deserialize<T: serde::de::DeserializeOwned>(
+    mut reader: impl std::io::Read,
+    format: CatalogFormat,
+    invalid: fn(String) -> AcError,
+) -> Result<T> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|e| invalid(format!("read failed: {e}")))?;
+    match format {
+        CatalogFormat::Toml => toml::from_str(&text).map_err(|e| invalid(e.to_string())),
+        CatalogFormat::Json => serde_json::from_str(&text).map_err(|e| invalid(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{CapabilityCatalog, SecurityCapabilitiesBuilder, explain_sid};
+    use crate::sid::AppContainerSid;
+
+    const FOREIGN_SID: &str = "S-1-15-3-1024-11-22-33-44-55-66-77-88";
+
+    fn def(name: &str) -> CapabilityDefinition {
+        CapabilityDefinition {
+            description: format!("{name} test capability"),
+            category: CapabilityCategory::Restricted,
+            ..CapabilityDefinition::new(name)
+        }
+    }
+
+    #[test]
+    fn merged_entries_resolve_by_name_and_sid() {
+        let mut catalog = CapabilityCatalog::new().unwrap();
+        let mut pinned = def("rappctTestPinned");
+        pinned.sid = Some(Sid::parse(FOREIGN_SID).unwrap());
+        catalog
+            .merge(&[def("rappctTestDerived"), pinned.clone()])
+            .unwrap();
+
+        let derived = catalog.lookup("rappctTestDerived").unwrap();
+        assert!(matches!(derived.name(), CapabilityName::Custom(_)));
+        assert_eq!(derived.info().category(), CapabilityCategory::Restricted);
+        assert_eq!(
+            derived.sid().sid,
+            crate::capability::derive_named_capability_sids(&["rappctTestDerived"]).unwrap()[0].sid
+        );
+        let pinned_cap = catalog.lookup("rappctTestPinned").unwrap();
+        assert_eq!(pinned_cap.sid().sid.to_string(), FOREIGN_SID);
+        assert_eq!(catalog.lookup_sid(FOREIGN_SID), Some(pinned_cap.name()));
+        // Only the name is registered process-wide: the derived SID is explained, the
+        // precomputed one is known to this catalog alone.
+        assert_eq!(explain_sid(FOREIGN_SID), None);
+        assert_eq!(
+            CapabilityName::from_name("rappctTestPinned"),
+            Some(pinned_cap.name())
+        );
+
+        // The precomputed SID belongs to the catalog; other builders derive from the name.
+        let builder =
+            SecurityCapabilitiesBuilder::new(&AppContainerSid::from_sddl("S-1-15-2-1-2-3-4-5-6-7"))
+                .with_named(&["rappctTestPinned"]);
+        let derived_sid = crate::capability::derive_named_capability_sids(&["rappctTestPinned"])
+            .unwrap()[0]
+            .sid
+            .clone();
+        assert_eq!(builder.clone().build().unwrap().caps[0].sid, derived_sid);
+        assert_eq!(
+            explain_sid(&derived_sid.to_string()).as_deref(),
+            Some("rappctTestPinned")
+        );
+        let built = builder.with_catalog(&catalog).build().unwrap();
+        assert_eq!(built.caps[0].sid.to_string(), FOREIGN_SID);
+
+        // Registering the same definition again is fine; the catalog rejects duplicates.
+        let mut other = CapabilityCatalog::new().unwrap();
+        other.merge(std::slice::from_ref(&pinned)).unwrap();
+        let err = catalog.merge(&[pinned]).unwrap_err();
+        assert!(matches!(err, AcError::CatalogConflict { .. }), "{err}");
+    }
+
+    #[test]
+    fn conflicts_are_detected() {
+        let mut catalog = CapabilityCatalog::new().unwrap();
+        let conflict = |result: Result<()>| {
+            assert!(
+                matches!(result, Err(AcError::CatalogConflict { .. })),
+                "{result:?}"
+            );
+        };
+        conflict(catalog.merge(&[def("INTERNETCLIENT")]));
+        conflict(catalog.merge(&[def("rappctTestTwice"), def("rappctTestTwice")]));
+        assert!(catalog.lookup("rappctTestTwice").is_err());
+
+        let mut same_sid = def("rappctTestSameSid");
+        same_sid.sid = Some(catalog.lookup("lpacCom").unwrap().sid().sid.clone());
+        conflict(catalog.merge(&[same_sid]));
+
+        catalog.merge(&[def("rappctTestRedefined")]).unwrap();
+        let mut redefined = def("rappctTestRedefined");
+        redefined.description = "something else".into();
+        conflict(CapabilityCatalog::new().unwrap().merge(&[redefined]));
+
+        let mut not_capability = def("rappctTestBadSid");
+        not_capability.sid = Some(Sid::parse("S-1-5-18").unwrap());
+        assert!(matches!(
+            catalog.merge(&[not_capability]),
+            Err(AcError::InvalidSid(_))
+        ));
+    }
+
+    #[test]
+    fn failed_merge_leaves_no_global_trace() {
+        let name = "rappctTestRolledBack";
+        let mut catalog = CapabilityCatalog::new().unwrap();
+        let mut first = def(name);
+        first.sid = Some(Sid::parse("S-1-15-3-1024-9-8-7-6-5-4-3-2").unwrap());
+        let err = catalog.merge(&[first.clone(), def("lpacCom")]).unwrap_err();
+        assert!(matches!(err, AcError::CatalogConflict { .. }), "{err}");
+
+        assert!(catalog.lookup(name).is_err());
+        assert_eq!(CapabilityName::from_name(name), None);
+        assert!(!names().contains(&name));
+        assert_eq!(explain_sid("S-1-15-3-1024-9-8-7-6-5-4-3-2"), None);
+
+        // A corrected definition under the same name is not a conflict.
+        first.description = "corrected".into();
+        catalog.merge(&[first]).unwrap();
+        assert_eq!(
+            catalog.lookup(name).unwrap().info().description(),
+            "corrected"
+        );
+    }
+
+    #[test]
+    fn concurrent_merges_land_whole_or_not_at_all() {
+        let results: Vec<(String, bool)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    scope.spawn(move || {
+                        let own = format!("rappctTestRace{i}");
+                        let mut shared = def("rappctTestRaceShared");
+                        shared.description = format!("variant {i}");
+                        let merged = CapabilityCatalog::new()
+                            .unwrap()
+                            .merge(&[def(&own), shared])
+                            .is_ok();
+                        (own, merged)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(results.iter().filter(|(_, merged)| *merged).count(), 1);
+        for (own, merged) in results {
+            assert_eq!(CapabilityName::from_name(&own).is_some(), merged, "{own}");
+        }
+    }
+
+    #[test]
+    fn lookup_suggests_custom_names() {
+        let mut catalog = CapabilityCatalog::new().unwrap();
+        catalog.merge(&[def("contosoTelemetryBroker")]).unwrap();
+        match catalog.lookup("contosoTelemetryBrokr") {
+            Err(AcError::UnknownCapability { suggestion, .. }) => {
+                assert_eq!(suggestion, Some("contosoTelemetryBroker"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn parses_toml_and_json_catalogs() {
+        let toml = format!(
+            r#"
+            [[capability]]
+            name = "rappctTestToml"
+            description = "from TOML"
+            category = "device"
+            min_build = 22000
+            docs = ["https://example.invalid/toml"]
+            risk_tier = "critical"
+            risk_categories = ["devices", "input_injection"]
+
+            [[capability]]
+            name = "rappctTestTomlSid"
+            sid = "{FOREIGN_SID}"
+            "#
+        )
+        .replace("11-22", "12-22");
+        let catalog = CapabilityCatalog::from_reader(toml.as_bytes(), CatalogFormat::Toml).unwrap();
+        let info = catalog.lookup("rappctTestToml").unwrap().info();
+        assert_eq!(info.description(), "from TOML");
+        assert_eq!(info.category(), CapabilityCategory::Device);
+        assert!(!info.available_on(19045));
+        assert_eq!(info.docs(), ["https://example.invalid/toml"]);
+        assert_eq!(info.risk_tier(), RiskTier::Critical);
+        assert_eq!(
+            info.risk_categories(),
+            [RiskCategory::Devices, RiskCategory::InputInjection]
+        );
+        let sid_info = catalog.lookup("rappctTestTomlSid").unwrap().info();
+        assert_eq!(sid_info.risk_tier(), RiskTier::Medium);
+
+        let json = r#"{"capabilities": [{"name": "rappctTestJson", "category": "lpac"}]}"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("caps.json");
+        std::fs::write(&path, json).unwrap();
+        let catalog = CapabilityCatalog::from_path(&path).unwrap();
+        assert_eq!(
+            catalog.lookup("rappctTestJson").unwrap().info().category(),
+            CapabilityCategory::Lpac
+        );
+
+        let unknown_field = "[[capability]]\nname = \"x\"\ncolour = \"red\"\n";
+        assert!(matches!(
+            CapabilityCatalog::from_reader(unknown_field.as_bytes(), CatalogFormat::Toml),
+            Err(AcError::InvalidCatalog(_))
+        ));
+        assert!(matches!(
+            CapabilityCatalog::from_path(dir.path().join("caps.yaml")),
+            Err(AcError::InvalidCatalog(_))
+        ));
+    }
+}
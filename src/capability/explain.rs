@@ -0,0 +1,185 @@
+//! Reverse resolution of capability SIDs to names.
+//!
+//! Hashed capability SIDs cannot be inverted, but the set of capability names in use is
+//! small. [`CapabilityDictionary`] precomputes the hash of every name it knows and matches
+//! SIDs against that table, offline and on any OS.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use super::{CapabilityName, table::CAPABILITY_INFO};
+use crate::sid::{CapabilityForm, Sid, SidClass, classify, derive};
+
+type HashRids = [u32; derive::CAPABILITY_HASH_RIDS];
+
+/// Capability names indexed by their SID hash.
+#[derive(Clone, Debug, Default)]
+pub struct CapabilityDictionary {
+    by_hash: HashMap<HashRids, String>,
+}
+
+impl CapabilityDictionary {
+    /// A dictionary of every capability in the capability table.
+    pub fn new() -> Self {
+        let mut dict = Self::empty();
+        dict.extend(CAPABILITY_INFO.iter().map(|info| info.as_str()));
+        dict
+    }
+
+    /// A dictionary without any names.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Adds `name`. Names differing only in case hash identically; the first spelling added
+    /// is kept.
+    pub fn insert(&mut self, name: &str) {
+        if name.is_empty() {
+            return;
+        }
+        self.by_hash
+            .entry(derive::capability_rids(name))
+            .or_insert_with(|| name.to_string());
+    }
+
+    /// Adds `name` under an explicit capability SID, for capabilities whose SID is not
+    /// derived from their name. Only hashed-form SIDs can be matched; others are ignored.
+    pub fn insert_sid(&mut self, name: &str, sid: &Sid) {
+        if classify(sid) != SidClass::Capability(CapabilityForm::Hashed) {
+            return;
+        }
+        if let Some(hash) = hash_rids(sid) {
+            self.by_hash.entry(hash).or_insert_with(|| name.to_string());
+        }
+    }
+
+    /// Adds every name of `names`.
+    pub fn extend<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        for name in names {
+            self.insert(name);
+        }
+    }
+
+    /// Number of distinct names.
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+
+    /// Name behind a capability SID (hashed or legacy) or capability group SID.
+    pub fn explain(&self, sid: &Sid) -> Option<&str> {
+        match classify(sid) {
+            SidClass::Capability(CapabilityForm::Legacy { rid }) => CapabilityName::ALL
+                .iter()
+                .find(|cap| cap.legacy_rid() == Some(rid))
+                .map(|cap| cap.as_str()),
+            SidClass::Capability(CapabilityForm::Hashed) | SidClass::CapabilityGroup => {
+                self.by_hash.get(&hash_rids(sid)?).map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses `sid_sddl` and explains it; `None` for unparseable or unknown SIDs.
+    pub fn explain_str(&self, sid_sddl: &str) -> Option<&str> {
+        let sid = Sid::parse(sid_sddl).ok()?;
+        self.explain(&sid)
+    }
+}
+
+/// The trailing hash sub-authorities of a hashed capability or capability group SID.
+fn hash_rids(sid: &Sid) -> Option<HashRids> {
+    let subs = sid.sub_authorities();
+    subs.get(subs.len().checked_sub(derive::CAPABILITY_HASH_RIDS)?..)?
+        .try_into()
+        .ok()
+}
+
+fn global() -> &'static RwLock<CapabilityDictionary> {
+    static DICTIONARY: OnceLock<RwLock<CapabilityDictionary>> = OnceLock::new();
+    DICTIONARY.get_or_init(|| RwLock::new(CapabilityDictionary::new()))
+}
+
+/// Adds custom capability names to the process-wide dictionary used by [`explain_sid`].
+pub fn register_capability_names<'a>(names: impl IntoIterator<Item = &'a str>) {
+    global()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .extend(names);
+}
+
+/// Returns the capability name behind a capability or capability group SID string.
+///
+/// Uses the process-wide dictionary: the capability table plus any names added with
+/// [`register_capability_names`].
+pub fn explain_sid(sid_sddl: &str) -> Option<String> {
+    global()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .explain_str(sid_sddl)
+        .map(str::to_string)
+}
+
+/// Bulk form of [`explain_sid`]; the output is parallel to the input.
+pub fn explain_sids<S: AsRef<str>>(sids: impl IntoIterator<Item = S>) -> Vec<Option<String>> {
+    let dict = global()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    sids.into_iter()
+        .map(|sid| dict.explain_str(sid.as_ref()).map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LPAC_COM_SID: &str = "S-1-15-3-1024-2405443489-874036122-4286035555-1823921565-1746547431-2453885448-3625952902-991631256";
+
+    #[test]
+    fn explains_hashed_legacy_and_group_sids() {
+        let dict = CapabilityDictionary::new();
+        assert_eq!(dict.explain_str(LPAC_COM_SID), Some("lpacCom"));
+        assert_eq!(dict.explain_str("S-1-15-3-7"), Some("documentsLibrary"));
+        let group = LPAC_COM_SID.replacen("S-1-15-3-1024-", "S-1-5-32-", 1);
+        assert_eq!(dict.explain_str(&group), Some("lpacCom"));
+        assert_eq!(dict.explain_str("S-1-5-18"), None);
+        assert_eq!(dict.explain_str("garbage"), None);
+    }
+
+    #[test]
+    fn custom_names_extend_the_dictionary() {
+        let custom = crate::capability::derive_named_capability_sids(&["Contoso.Widget"])
+            .unwrap()
+            .remove(0)
+            .sid;
+        let mut dict = CapabilityDictionary::new();
+        assert_eq!(dict.explain(&custom), None);
+        dict.insert("Contoso.Widget");
+        dict.insert("CONTOSO.WIDGET");
+        assert_eq!(dict.explain(&custom), Some("Contoso.Widget"));
+        assert_eq!(dict.len(), CAPABILITY_INFO.len() + 1);
+    }
+
+    #[test]
+    fn global_dictionary_explains_and_accepts_registrations() {
+        let custom = crate::capability::derive_named_capability_sids(&["rappct.explainTest"])
+            .unwrap()
+            .remove(0)
+            .sid
+            .to_string();
+        assert_eq!(explain_sid(LPAC_COM_SID).as_deref(), Some("lpacCom"));
+        register_capability_names(["rappct.explainTest"]);
+        assert_eq!(
+            explain_sids([LPAC_COM_SID, custom.as_str(), "S-1-1-0"]),
+            vec![
+                Some("lpacCom".to_string()),
+                Some("rappct.explainTest".to_string()),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,1732 @@
+//! Capability catalog and builders for AppContainer and LPAC scenarios.
+//!
+//! AppContainer capabilities describe which system resources a sandboxed process may access,
+//! whereas LPAC (Low Privilege AppContainer) builds on that surface with a more restrictive
+//! default policy. The catalog provided here covers the general-use, restricted, device and
+//! LPAC-oriented capabilities published by Microsoft (see [`CapabilityInfo`] for the metadata
+//! kept per entry) and is used both for friendly name resolution and for constructing
+//! `SECURITY_CAPABILITIES` structures at the FFI boundary.
+//! Capability SIDs are derived offline with the same hashing scheme as
+//! `DeriveCapabilitySidsFromName`, so the catalog and builders work on every host.
+//! See: <https://learn.microsoft.com/windows/win32/secauthz/appcontainer-capabilities>
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(windows)]
+use crate::ffi::mem::LocalAllocGuard;
+use crate::ffi::sid::OwnedSid;
+#[cfg(windows)]
+use crate::ffi::wstr::WideString;
+use crate::sid::{
+    APP_PACKAGE_AUTHORITY, AppContainerSid, BUILTIN_DOMAIN_RID, CAPABILITY_BASE_RID,
+    CAPABILITY_HASHED_RID, CapabilityForm, NT_AUTHORITY, Sid, SidAndAttributes, SidClass, classify,
+    derive,
+};
+use crate::{AcError, Result};
+
+#[cfg(feature = "appx")]
+mod appx;
+mod cache;
+mod custom;
+mod explain;
+mod parse;
+mod policy;
+mod risk;
+mod sccd;
+mod set;
+mod table;
+
+#[cfg(feature = "appx")]
+pub use appx::{
+    AppxIdentity, AppxManifestCapabilities, ManifestCapability, ManifestCapabilityKind,
+    ManifestWarning,
+};
+pub use cache::{
+    CapabilityCacheStats, capability_cache_stats, clear_capability_cache, prewarm_capability_cache,
+    set_capability_cache_enabled,
+};
+pub use custom::CapabilityDefinition;
+#[cfg(feature = "config")]
+pub use custom::CatalogFormat;
+pub use explain::{CapabilityDictionary, explain_sid, explain_sids, register_capability_names};
+pub use parse::capability_aliases;
+pub use policy::{CapabilityPolicy, PolicyViolation, PolicyViolationKind};
+pub use risk::{CapabilityRisk, CategoryRisk, RiskCategory, RiskFinding, RiskReport, RiskTier};
+pub use sccd::{CustomCapability, publisher_id};
+pub use set::{CapabilityDiff, CapabilitySet, DiffEntry};
+pub use table::{
+    CapabilityCategory, CapabilityInfo, CapabilityName, CustomName, ManifestNamespace,
+    WELL_KNOWN_CAPABILITY_NAMES,
+};
+
+// windows::Win32::Security::SE_GROUP_ENABLED is not consistently available across crate versions.
+// Use the documented value directly.
+const SE_GROUP_ENABLED_CONST: u32 = 0x0000_0004;
+
+/// Leading sub-authorities of hashed capability SIDs (`S-1-15-3-1024-...`).
+const CAPABILITY_SID_PREFIX_RIDS: [u32; 2] = [CAPABILITY_BASE_RID, CAPABILITY_HASHED_RID];
+
+/// Back-compat alias for the previous enum name.
+pub type KnownCapability = CapabilityName;
+
+impl CapabilityName {
+    /// Metadata from the capability table, or from the catalog that loaded a custom
+    /// capability.
+    pub fn info(self) -> &'static CapabilityInfo {
+        match self.table_index() {
+            Some(index) => &table::CAPABILITY_INFO[index],
+            None => custom::info(self.as_str()).expect("custom capabilities are registered"),
+        }
+    }
+
+    /// Back-compat alias retained for existing callers.
+    pub const fn as_name(self) -> &'static str {
+        self.as_str()
+    }
+
+    /// Exact-match lookup over the table and registered custom capabilities.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|cap| cap.as_str() == name)
+            .or_else(|| custom::info(name).map(CapabilityInfo::name))
+    }
+
+    /// Fixed RID of the pre-Windows 10 capability SID (`S-1-15-3-<rid>`), for the
+    /// capabilities that have one (`SECURITY_CAPABILITY_*` in `winnt.h`).
+    pub const fn legacy_rid(self) -> Option<u32> {
+        match self {
+            CapabilityName::InternetClient => Some(1),
+            CapabilityName::InternetClientServer => Some(2),
+            CapabilityName::PrivateNetworkClientServer => Some(3),
+            CapabilityName::PicturesLibrary => Some(4),
+            CapabilityName::VideosLibrary => Some(5),
+            CapabilityName::MusicLibrary => Some(6),
+            CapabilityName::DocumentsLibrary => Some(7),
+            CapabilityName::EnterpriseAuthentication => Some(8),
+            CapabilityName::SharedUserCertificates => Some(9),
+            CapabilityName::RemovableStorage => Some(10),
+            CapabilityName::Appointments => Some(11),
+            CapabilityName::Contacts => Some(12),
+            _ => None,
+        }
+    }
+
+    /// The legacy capability SID (`S-1-15-3-<rid>`), if this capability has one.
+    pub fn legacy_sid(self) -> Option<Sid> {
+        self.legacy_rid()
+            .map(|rid| Sid::from_parts(APP_PACKAGE_AUTHORITY, vec![CAPABILITY_BASE_RID, rid]))
+    }
+}
+
+/// Which capability SID forms [`SecurityCapabilitiesBuilder::build`] emits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum CapabilitySidForm {
+    /// Hashed SIDs only (`S-1-15-3-1024-...`), as `DeriveCapabilitySidsFromName` returns.
+    #[default]
+    Hashed,
+    /// Legacy RID SIDs (`S-1-15-3-<rid>`) where one exists, hashed SIDs otherwise.
+    Legacy,
+    /// Hashed SIDs, each followed by its legacy SID where one exists.
+    Both,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CapabilityName {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CapabilityName {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::from_name(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown capability '{s}'")))
+    }
+}
+
+impl core::fmt::Display for CapabilityName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+const ALL_CAPABILITY_NAMES: &[CapabilityName] = CapabilityName::ALL;
+
+const CAPABILITY_NAME_STRINGS: &[&str] = WELL_KNOWN_CAPABILITY_NAMES;
+
+pub fn known_caps_to_named(caps: &[CapabilityName]) -> Vec<&'static str> {
+    caps.iter().map(|c| c.as_str()).collect()
+}
+
+// Static list of known/supported capability names for suggestions.
+static KNOWN_CAP_NAMES: &[&str] = CAPABILITY_NAME_STRINGS;
+
+#[cfg_attr(not(any(windows, test)), allow(dead_code))]
+fn suggest_capability_name(name: &str) -> Option<&'static str> {
+    parse::suggest(name, KNOWN_CAP_NAMES.iter().copied().chain(custom::names()))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capability {
+    name: CapabilityName,
+    sid: SidAndAttributes,
+    legacy_sid: Option<SidAndAttributes>,
+    group_sid: Option<Sid>,
+}
+
+/// A SID with its classification, as produced by [`CapabilityCatalog::label_sid`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SidLabel {
+    pub sid: Sid,
+    pub class: SidClass,
+    /// The capability name when the SID is a capability the catalog knows.
+    pub capability: Option<CapabilityName>,
+}
+
+impl std::fmt::Display for SidLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.capability {
+            Some(name) => write!(f, "{} [{}: {name}]", self.sid, self.class),
+            None => write!(f, "{} [{}]", self.sid, self.class),
+        }
+    }
+}
+
+pub struct CapabilityCatalog {
+    by_name: BTreeMap<CapabilityName, Capability>,
+    by_friendly: BTreeMap<&'static str, CapabilityName>,
+    by_sid: BTreeMap<Sid, CapabilityName>,
+}
+
+impl Capability {
+    fn from_name(name: CapabilityName) -> Result<Self> {
+        Ok(Self::with_sid(name, derive_capability_sid(name.as_str())?))
+    }
+
+    fn with_sid(name: CapabilityName, sid: SidAndAttributes) -> Self {
+        let legacy_sid = name
+            .legacy_sid()
+            .map(|sid| SidAndAttributes::new(sid, SE_GROUP_ENABLED_CONST));
+        let group_sid = capability_group_sid(&sid.sid);
+        Self {
+            name,
+            sid,
+            legacy_sid,
+            group_sid,
+        }
+    }
+
+    pub fn try_from_str(friendly: &str) -> Result<Self> {
+        CapabilityCatalog::new().and_then(|catalog| Self::try_from_catalog(&catalog, friendly))
+    }
+
+    pub fn try_from_catalog(catalog: &CapabilityCatalog, friendly: &str) -> Result<Self> {
+        catalog.lookup(friendly).cloned()
+    }
+
+    pub fn name(&self) -> CapabilityName {
+        self.name
+    }
+
+    /// Metadata from the capability table or the catalog entry.
+    pub fn info(&self) -> &'static CapabilityInfo {
+        self.name.info()
+    }
+
+    pub fn sid(&self) -> &SidAndAttributes {
+        &self.sid
+    }
+
+    /// The legacy RID form of the capability SID, if the capability has one.
+    pub fn legacy_sid(&self) -> Option<&SidAndAttributes> {
+        self.legacy_sid.as_ref()
+    }
+
+    /// The capability group SID (`S-1-5-32-<hash>`); `None` when a custom entry supplied a
+    /// legacy-form SID.
+    pub fn group_sid(&self) -> Option<&Sid> {
+        self.group_sid.as_ref()
+    }
+
+    /// The capability and group SIDs, as [`derive_capability`] returns them.
+    pub fn derived(&self) -> DerivedCapability {
+        DerivedCapability {
+            group_sids: self.group_sid.iter().cloned().collect(),
+            capability_sids: vec![self.sid.sid.clone()],
+        }
+    }
+
+    #[cfg(windows)]
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn to_sid(&self) -> Result<OwnedSid> {
+        use windows::Win32::Security::Authorization::ConvertStringSidToSidW;
+        use windows::Win32::Security::PSID;
+        use windows::core::PCWSTR;
+
+        let wide = WideString::from_str(&self.sid.sid.to_string());
+        let mut psid = PSID::default();
+        // SAFETY: The SDDL string is formatted from a parsed `Sid`, so `ConvertStringSidToSidW` receives a well-formed SID description and returns a pointer
+        // allocated through LocalAlloc. We wrap the resulting PSID in `OwnedSid` to ensure it is
+        // freed exactly once.
+        unsafe {
+            ConvertStringSidToSidW(PCWSTR(wide.as_pcwstr().0), &mut psid)
+                .map_err(|e| AcError::Win32(format!("ConvertStringSidToSidW failed: {e:?}")))?;
+            // SAFETY: ConvertStringSidToSidW allocates via LocalAlloc; wrap in OwnedSid to release.
+            OwnedSid::from_localfree_psid(psid.0)
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[allow(dead_code)]
+    pub(crate) fn to_sid(&self) -> Result<OwnedSid> {
+        let _ = self;
+        Err(AcError::UnsupportedPlatform)
+    }
+}
+
+impl CapabilityCatalog {
+    pub fn new() -> Result<Self> {
+        Self::from_names(ALL_CAPABILITY_NAMES)
+    }
+
+    pub fn from_names(names: &[CapabilityName]) -> Result<Self> {
+        let mut by_name = BTreeMap::new();
+        let mut by_sid = BTreeMap::new();
+        for &name in names {
+            if let Entry::Vacant(slot) = by_name.entry(name) {
+                let capability = Capability::from_name(name)?;
+                let sid_keys = std::iter::once(&capability.sid)
+                    .chain(capability.legacy_sid.as_ref())
+                    .map(|s| s.sid.clone())
+                    .collect::<Vec<_>>();
+                slot.insert(capability);
+                for key in sid_keys {
+                    by_sid.entry(key).or_insert(name);
+                }
+            }
+        }
+        let mut by_friendly = BTreeMap::new();
+        for &name in names {
+            by_friendly.entry(name.as_str()).or_insert(name);
+        }
+        Ok(Self {
+            by_name,
+            by_friendly,
+            by_sid,
+        })
+    }
+
+    /// The built-in catalog extended with the entries of a catalog file.
+    #[cfg(feature = "config")]
+    pub fn from_reader(reader: impl std::io::Read, format: CatalogFormat) -> Result<Self> {
+        let mut catalog = Self::new()?;
+        catalog.merge_reader(reader, format)?;
+        Ok(catalog)
+    }
+
+    /// The built-in catalog extended with the entries of the `.toml` or `.json` file at
+    /// `path`.
+    #[cfg(feature = "config")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let mut catalog = Self::new()?;
+        catalog.merge_path(path)?;
+        Ok(catalog)
+    }
+
+    /// Adds the entries of a catalog file; see [`merge`](Self::merge).
+    #[cfg(feature = "config")]
+    pub fn merge_reader(
+        &mut self,
+        reader: impl std::io::Read,
+        format: CatalogFormat,
+    ) -> Result<()> {
+        self.merge(&custom::parse(reader, format)?)
+    }
+
+    /// Adds the entries of the `.toml` or `.json` file at `path`; see [`merge`](Self::merge).
+    #[cfg(feature = "config")]
+    pub fn merge_path(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = CatalogFormat::from_path(path).ok_or_else(|| {
+            AcError::InvalidCatalog(format!(
+                "{}: expected a .toml or .json file",
+                path.display()
+            ))
+        })?;
+        let file = std::fs::File::open(path).map_err(|_| AcError::ResourceNotFound {
+            path: path.display().to_string(),
+            hint: "capability catalog file",
+        })?;
+        self.merge_reader(std::io::BufReader::new(file), format)
+    }
+
+    /// Adds custom capabilities to the catalog.
+    ///
+    /// The definitions are checked and registered process-wide as one batch (see
+    /// [`CapabilityName::Custom`]) so that names resolve and [`explain_sid`] knows the SIDs
+    /// derived from them. Precomputed SIDs stay with this catalog: [`lookup_sid`](Self::lookup_sid) names them,
+    /// [`explain_sid`] does not, and [`SecurityCapabilitiesBuilder::with_catalog`] builds with
+    /// them. Fails without
+    /// modifying the catalog or the process-wide registry with [`AcError::CatalogConflict`]
+    /// when a name is built in, already in the catalog, or registered with a different
+    /// definition, or when a SID belongs to another entry.
+    pub fn merge(&mut self, defs: &[CapabilityDefinition]) -> Result<()> {
+        let mut checked: Vec<(&CapabilityDefinition, SidAndAttributes)> =
+            Vec::with_capacity(defs.len());
+        for def in defs {
+            custom::check_definition(def)?;
+            let listed = |name: &str| name.eq_ignore_ascii_case(&def.name);
+            if self.by_friendly.keys().any(|name| listed(name))
+                || checked.iter().any(|(other, _)| listed(&other.name))
+            {
+                return Err(AcError::CatalogConflict {
+                    name: def.name.clone(),
+                    reason: "capability is already in the catalog",
+                });
+            }
+            let sid = match &def.sid {
+                Some(sid) => SidAndAttributes::new(sid.clone(), SE_GROUP_ENABLED_CONST),
+                None => derive_capability_sid(&def.name)?,
+            };
+            if self.by_sid.contains_key(&sid.sid)
+                || checked.iter().any(|(_, other)| other.sid == sid.sid)
+            {
+                return Err(AcError::CatalogConflict {
+                    name: def.name.clone(),
+                    reason: "SID already belongs to another capability",
+                });
+            }
+            checked.push((def, sid));
+        }
+        let batch: Vec<&CapabilityDefinition> = checked.iter().map(|(def, _)| *def).collect();
+        let names = custom::register_all(&batch)?;
+        for (name, (_, sid)) in names.into_iter().zip(checked) {
+            let capability = Capability::with_sid(name, sid);
+            self.by_sid.insert(capability.sid.sid.clone(), name);
+            self.by_friendly.insert(name.as_str(), name);
+            self.by_name.insert(name, capability);
+        }
+        Ok(())
+    }
+
+    /// Adds a custom capability with a derived SID; see [`merge`](Self::merge).
+    pub fn register_custom(&mut self, capability: &CustomCapability) -> Result<CapabilityName> {
+        self.merge(&[capability.definition()])?;
+        CapabilityName::from_name(capability.as_str()).ok_or_else(|| AcError::UnknownCapability {
+            name: capability.to_string(),
+            suggestion: None,
+        })
+    }
+
+    pub fn capability(&self, name: CapabilityName) -> Option<&Capability> {
+        self.by_name.get(&name)
+    }
+
+    /// Iterates over the catalog's capabilities in table order, followed by custom
+    /// capabilities ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = &Capability> {
+        self.by_name.values()
+    }
+
+    /// Iterates over the capabilities of `category`.
+    pub fn by_category(&self, category: CapabilityCategory) -> impl Iterator<Item = &Capability> {
+        self.iter()
+            .filter(move |cap| cap.info().category() == category)
+    }
+
+    /// Iterates over the capabilities available on Windows build `build`.
+    pub fn available_on(&self, build: u32) -> impl Iterator<Item = &Capability> {
+        self.iter()
+            .filter(move |cap| cap.info().available_on(build))
+    }
+
+    /// Case-insensitive substring search over capability names and descriptions.
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a Capability> + 'a {
+        let query = query.to_lowercase();
+        self.iter().filter(move |cap| {
+            let info = cap.info();
+            info.as_str().to_lowercase().contains(&query)
+                || info.description().to_lowercase().contains(&query)
+        })
+    }
+
+    /// Resolves a capability name. Exact names are tried first, then the forgiving
+    /// spellings and aliases [`CapabilityName::from_str`](std::str::FromStr) accepts.
+    pub fn lookup(&self, friendly: &str) -> Result<&Capability> {
+        let name = self
+            .by_friendly
+            .get(friendly)
+            .copied()
+            .or_else(|| parse::resolve(friendly, self.by_name.keys().copied()));
+        match name.and_then(|name| self.by_name.get(&name)) {
+            Some(cap) => Ok(cap),
+            None => {
+                let suggestion = parse::suggest(
+                    friendly,
+                    KNOWN_CAP_NAMES.iter().copied().chain(
+                        self.by_friendly
+                            .keys()
+                            .copied()
+                            .filter(|name| !KNOWN_CAP_NAMES.contains(name)),
+                    ),
+                );
+                Err(AcError::UnknownCapability {
+                    name: friendly.to_string(),
+                    suggestion,
+                })
+            }
+        }
+    }
+
+    /// Resolves a capability SID string, hashed or legacy form, to its name.
+    ///
+    /// The string is parsed first, so any spelling of the same SID (case of the `S`, hex
+    /// components) resolves identically. Unparseable strings return `None`.
+    pub fn lookup_sid(&self, sid_sddl: &str) -> Option<CapabilityName> {
+        Sid::parse(sid_sddl)
+            .ok()
+            .and_then(|sid| self.lookup_sid_value(&sid))
+    }
+
+    /// Resolves a parsed capability SID to its name.
+    pub fn lookup_sid_value(&self, sid: &Sid) -> Option<CapabilityName> {
+        if !classify(sid).is_capability() {
+            return None;
+        }
+        self.by_sid.get(sid).copied()
+    }
+
+    /// Labels any SID with its [`SidClass`] and, for capabilities in this catalog, its name.
+    pub fn label_sid(&self, sid: &Sid) -> SidLabel {
+        SidLabel {
+            sid: sid.clone(),
+            class: classify(sid),
+            capability: self.lookup_sid_value(sid),
+        }
+    }
+}
+
+/// Derive capability SIDs from names.
+///
+/// The SIDs are computed in Rust and match `DeriveCapabilitySidsFromName`, so this works on
+/// every host. On Windows, [`derive_named_capability_sids_win32`] runs the Userenv path for
+/// cross-checking. Each name goes through the same cache as [`derive_capability`].
+pub fn derive_named_capability_sids(names: &[&str]) -> Result<Vec<SidAndAttributes>> {
+    names
+        .iter()
+        .map(|&name| derive_capability_sid(name))
+        .collect()
+}
+
+fn derive_capability_sid(name: &str) -> Result<SidAndAttributes> {
+    let mut derived = derive_capability(name)?;
+    Ok(SidAndAttributes::new(
+        derived.capability_sids.swap_remove(0),
+        SE_GROUP_ENABLED_CONST,
+    ))
+}
+
+fn derive_capability_uncached(name: &str) -> Result<DerivedCapability> {
+    if name.is_empty() {
+        return Err(AcError::UnknownCapability {
+            name: String::new(),
+            suggestion: None,
+        });
+    }
+    #[cfg(feature = "tracing")]
+    tracing::trace!("derive_capability: name={}", name);
+    let mut sub_authorities = CAPABILITY_SID_PREFIX_RIDS.to_vec();
+    sub_authorities.extend_from_slice(&derive::capability_rids(name));
+    let capability = Sid::from_parts(APP_PACKAGE_AUTHORITY, sub_authorities);
+    Ok(DerivedCapability {
+        group_sids: capability_group_sid(&capability).into_iter().collect(),
+        capability_sids: vec![capability],
+    })
+}
+
+/// Capability and capability group SIDs derived from one capability name.
+///
+/// Mirrors the two outputs of `DeriveCapabilitySidsFromName`: capability SIDs go into
+/// `SECURITY_CAPABILITIES`, while group SIDs suit restricting SID lists and ACEs granted to
+/// the group form.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DerivedCapability {
+    /// Capability group SIDs (`S-1-5-32-<hash>`).
+    pub group_sids: Vec<Sid>,
+    /// Capability SIDs (`S-1-15-3-1024-<hash>`).
+    pub capability_sids: Vec<Sid>,
+}
+
+/// Derives the capability and capability group SIDs for `name`.
+///
+/// Computed in Rust and identical to `DeriveCapabilitySidsFromName`; on Windows,
+/// [`derive_capability_win32`] runs the Userenv path for cross-checking. Results are
+/// memoized process-wide (see [`capability_cache_stats`]).
+pub fn derive_capability(name: &str) -> Result<DerivedCapability> {
+    cache::derive(name, derive_capability_uncached)
+}
+
+/// Capability group SID sharing the hash of a hashed capability SID; `None` for legacy SIDs.
+fn capability_group_sid(capability: &Sid) -> Option<Sid> {
+    if classify(capability) != SidClass::Capability(CapabilityForm::Hashed) {
+        return None;
+    }
+    let mut sub_authorities = vec![BUILTIN_DOMAIN_RID];
+    sub_authorities
+        .extend_from_slice(&capability.sub_authorities()[CAPABILITY_SID_PREFIX_RIDS.len()..]);
+    Some(Sid::from_parts(NT_AUTHORITY, sub_authorities))
+}
+
+/// Legacy SID for a capability name, matched case-insensitively like the hashed derivation.
+fn legacy_capability_sid(name: &str) -> Option<SidAndAttributes> {
+    ALL_CAPABILITY_NAMES
+        .iter()
+        .find(|cap| cap.as_str().eq_ignore_ascii_case(name))
+        .and_then(|cap| cap.legacy_sid())
+        .map(|sid| SidAndAttributes::new(sid, SE_GROUP_ENABLED_CONST))
+}
+
+/// Derive capability SIDs through Userenv's `DeriveCapabilitySidsFromName`.
+///
+/// Returns the same SIDs as [`derive_named_capability_sids`]; kept as an optional
+/// cross-check against the operating system.
+#[cfg(windows)]
+pub fn derive_named_capability_sids_win32(names: &[&str]) -> Result<Vec<SidAndAttributes>> {
+    let mut out = Vec::new();
+    for &name in names {
+        let mut sids = derive_single_capability_sids_win32(name)?;
+        out.append(&mut sids);
+    }
+    Ok(out)
+}
+
+#[cfg(windows)]
+fn derive_single_capability_sids_win32(name: &str) -> Result<Vec<SidAndAttributes>> {
+    Ok(derive_capability_win32(name)?
+        .capability_sids
+        .into_iter()
+        .map(|sid| SidAndAttributes::new(sid, SE_GROUP_ENABLED_CONST))
+        .collect())
+}
+
+/// [`derive_capability`] through Userenv's `DeriveCapabilitySidsFromName`.
+#[cfg(windows)]
+pub fn derive_capability_win32(name: &str) -> Result<DerivedCapability> {
+    // Some toolchains don't surface DeriveCapabilitySidsFromName via windows-rs; bind manually.
+    #[link(name = "Userenv")]
+    unsafe extern "system" {
+        fn DeriveCapabilitySidsFromName(
+            CapName: windows::core::PCWSTR,
+            CapGroupSids: *mut *mut *mut core::ffi::c_void,
+            CapGroupSidCount: *mut u32,
+            CapabilitySids: *mut *mut *mut core::ffi::c_void,
+            CapabilitySidCount: *mut u32,
+        ) -> i32;
+    }
+    // SAFETY: We pass a valid PCWSTR and receive LocalAlloc-managed SID arrays, which
+    // `take_sid_array` frees exactly once after reading only within the reported bounds.
+    // See Windows API docs for contracts.
+    unsafe {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("derive_capability_win32: name={}", name);
+        let wide = WideString::from_str(name);
+        let mut group_sids: *mut *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut group_count: u32 = 0;
+        let mut cap_sids: *mut *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut cap_count: u32 = 0;
+        let ok = DeriveCapabilitySidsFromName(
+            wide.as_pcwstr(),
+            &mut group_sids as *mut _ as *mut _,
+            &mut group_count,
+            &mut cap_sids as *mut _ as *mut _,
+            &mut cap_count,
+        );
+        if ok == 0 {
+            #[cfg(feature = "tracing")]
+            {
+                use windows::Win32::Foundation::GetLastError;
+                let gle = GetLastError().0;
+                tracing::error!(
+                    "DeriveCapabilitySidsFromName failed: name={}, GLE={}",
+                    name,
+                    gle
+                );
+            }
+            let suggestion: Option<&'static str> = suggest_capability_name(name);
+            if !group_sids.is_null() {
+                let _ = LocalAllocGuard::<*mut std::ffi::c_void>::from_raw(group_sids);
+            }
+            if !cap_sids.is_null() {
+                let _ = LocalAllocGuard::<*mut std::ffi::c_void>::from_raw(cap_sids);
+            }
+            return Err(AcError::UnknownCapability {
+                name: name.to_string(),
+                suggestion,
+            });
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            "DeriveCapabilitySidsFromName: name={}, group_count={}, cap_count={}",
+            name,
+            group_count,
+            cap_count
+        );
+        // Take both arrays before checking either result so neither leaks.
+        let group_sids = take_sid_array(group_sids, group_count, name, "group");
+        let capability_sids = take_sid_array(cap_sids, cap_count, name, "capability");
+        Ok(DerivedCapability {
+            group_sids: group_sids?,
+            capability_sids: capability_sids?,
+        })
+    }
+}
+
+/// Converts and frees a SID array returned by `DeriveCapabilitySidsFromName`.
+///
+/// # Safety
+/// `array` must be null or a LocalAlloc'd array of `count` LocalAlloc'd SIDs (or nulls)
+/// that the caller no longer uses.
+#[cfg(windows)]
+unsafe fn take_sid_array(
+    array: *mut *mut std::ffi::c_void,
+    count: u32,
+    name: &str,
+    kind: &str,
+) -> Result<Vec<Sid>> {
+    use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows::core::PWSTR;
+
+    if array.is_null() {
+        return if count == 0 {
+            Ok(Vec::new())
+        } else {
+            Err(AcError::Win32(format!(
+                "DeriveCapabilitySidsFromName returned null {kind} SID array for '{name}' (count={count})"
+            )))
+        };
+    }
+    let mut out = Vec::with_capacity(count as usize);
+    let mut conversion_error: Option<AcError> = None;
+    // SAFETY: The caller guarantees `array` holds `count` entries, each null or an owned
+    // LocalAlloc'd SID; every entry and the array itself are wrapped in guards to free once.
+    unsafe {
+        for i in 0..count as usize {
+            let sid_ptr: *mut std::ffi::c_void = *array.add(i);
+            if sid_ptr.is_null() {
+                continue;
+            }
+            let sid_guard = LocalAllocGuard::<std::ffi::c_void>::from_raw(sid_ptr);
+            if conversion_error.is_some() {
+                continue;
+            }
+            let mut sddl = PWSTR::null();
+            match ConvertSidToStringSidW(
+                windows::Win32::Security::PSID(sid_guard.as_ptr()),
+                &mut sddl,
+            ) {
+                Ok(()) => {
+                    let sddl_guard = LocalAllocGuard::<u16>::from_raw(sddl.0);
+                    match Sid::parse(&sddl_guard.to_string_lossy()) {
+                        Ok(sid) => out.push(sid),
+                        Err(e) => conversion_error = Some(e),
+                    }
+                }
+                Err(e) => {
+                    conversion_error = Some(AcError::Win32(format!(
+                        "ConvertSidToStringSidW failed for {kind} SID of '{name}': {e:?}"
+                    )));
+                }
+            }
+        }
+        let _ = LocalAllocGuard::<*mut std::ffi::c_void>::from_raw(array);
+    }
+    if let Some(err) = conversion_error {
+        return Err(err);
+    }
+    if out.len() != count as usize {
+        return Err(AcError::Win32(format!(
+            "Derived {count} {kind} SID(s) for '{name}' but converted {}",
+            out.len()
+        )));
+    }
+    Ok(out)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityCapabilities {
+    pub package: AppContainerSid,
+    pub caps: Vec<SidAndAttributes>,
+    pub lpac: bool,
+}
+
+impl SecurityCapabilities {
+    /// Checks that every entry of `caps` is a capability SID.
+    ///
+    /// Called by [`SecurityCapabilitiesBuilder::build`] and before launch, since the fields
+    /// are public and a user, group or package SID in `SECURITY_CAPABILITIES` makes
+    /// `CreateProcessW` fail with a generic parameter error.
+    pub fn validate(&self) -> Result<()> {
+        match self
+            .caps
+            .iter()
+            .find(|cap| !classify(&cap.sid).is_capability())
+        {
+            Some(cap) => Err(AcError::InvalidSid(format!(
+                "{} is not a capability SID ({})",
+                cap.sid,
+                classify(&cap.sid)
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SecurityCapabilitiesBuilder {
+    package: AppContainerSid,
+    child: Option<String>,
+    caps_named: Vec<String>,
+    /// Names from [`with_custom`](Self::with_custom), hashed as-is.
+    caps_custom: Vec<String>,
+    caps_sids: Vec<Sid>,
+    attributes: Vec<(AttributeTarget, u32)>,
+    lpac: bool,
+    sid_form: CapabilitySidForm,
+    policy: Option<CapabilityPolicy>,
+    /// Custom capability SIDs from [`with_catalog`](Self::with_catalog).
+    catalog_sids: BTreeMap<CapabilityName, Sid>,
+}
+
+/// Capability whose attributes a [`SecurityCapabilitiesBuilder`] overrides.
+#[derive(Clone, Debug)]
+enum AttributeTarget {
+    Name(String),
+    Sid(Sid),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum UseCase {
+    /// Internet-enabled scrape-like workloads with minimal extra rights.
+    SecureWebScraper,
+    /// LPAC default baseline with registry-focused access pattern.
+    IsolatedBuildEnvironment,
+    /// Networked tool with private network capability, typically paired with loopback checks.
+    NetworkConstrainedTool,
+    /// Minimal LPAC-only capability set.
+    MinimalLpac,
+    /// Safer desktop-style baseline for interactive workloads.
+    FullDesktopApp,
+    /// No preset; callers should add capabilities explicitly.
+    Custom,
+}
+
+pub struct UseCaseCapabilities {
+    caps_named: Vec<String>,
+    lpac: bool,
+}
+
+impl UseCaseCapabilities {
+    pub fn with_profile_sid(self, sid: &AppContainerSid) -> SecurityCapabilitiesBuilder {
+        SecurityCapabilitiesBuilder {
+            package: sid.clone(),
+            child: None,
+            caps_named: self.caps_named,
+            caps_custom: Vec::new(),
+            caps_sids: Vec::new(),
+            attributes: Vec::new(),
+            lpac: self.lpac,
+            sid_form: CapabilitySidForm::default(),
+            policy: None,
+            catalog_sids: BTreeMap::new(),
+        }
+    }
+
+    #[cfg(test)]
+    fn named_caps_for_test(&self) -> &[String] {
+        &self.caps_named
+    }
+}
+
+impl SecurityCapabilitiesBuilder {
+    pub fn new(pkg: &AppContainerSid) -> Self {
+        Self {
+            package: pkg.clone(),
+            child: None,
+            caps_named: vec![],
+            caps_custom: vec![],
+            caps_sids: vec![],
+            attributes: vec![],
+            lpac: false,
+            sid_form: CapabilitySidForm::default(),
+            policy: None,
+            catalog_sids: BTreeMap::new(),
+        }
+    }
+    pub fn with_known(mut self, caps: &[KnownCapability]) -> Self {
+        let names = known_caps_to_named(caps);
+        self.caps_named
+            .extend(names.into_iter().map(|s| s.to_string()));
+        self
+    }
+    /// Adds capabilities by name. [`build`](Self::build) resolves each like
+    /// [`CapabilityName::from_str`](std::str::FromStr) (case, separators, aliases, registered
+    /// custom capabilities) and fails with [`AcError::UnknownCapability`] for anything else.
+    pub fn with_named(mut self, names: &[&str]) -> Self {
+        if names.is_empty() {
+            return self;
+        }
+        self.caps_named.extend(names.iter().map(|s| s.to_string()));
+        self
+    }
+    /// Adds validated custom capabilities; their names are hashed as-is.
+    pub fn with_custom(mut self, caps: &[CustomCapability]) -> Self {
+        self.caps_custom
+            .extend(caps.iter().map(|cap| cap.as_str().to_string()));
+        self
+    }
+    /// Adds capability SIDs as-is, e.g. ones read from a token or an ACL.
+    ///
+    /// [`build`](Self::build) rejects SIDs outside the capability ranges.
+    pub fn with_sids(mut self, sids: &[Sid]) -> Self {
+        self.caps_sids.extend_from_slice(sids);
+        self
+    }
+    /// Sets the `SID_AND_ATTRIBUTES` flags of capability `name` instead of
+    /// [`SidAndAttributes::ENABLED`], for every SID form emitted for it.
+    ///
+    /// `name` must also be added to the builder; [`build`](Self::build) fails otherwise.
+    pub fn with_attributes(mut self, name: &str, attributes: u32) -> Self {
+        self.attributes
+            .push((AttributeTarget::Name(name.to_string()), attributes));
+        self
+    }
+    /// Sets the `SID_AND_ATTRIBUTES` flags of the capability `sid`, added by name or by
+    /// [`with_sids`](Self::with_sids).
+    pub fn with_sid_attributes(mut self, sid: &Sid, attributes: u32) -> Self {
+        self.attributes
+            .push((AttributeTarget::Sid(sid.clone()), attributes));
+        self
+    }
+    /// Opinionated minimal LPAC defaults (skeleton). Add "registryRead", "lpacCom".
+    pub fn with_lpac_defaults(mut self) -> Self {
+        self.lpac = true;
+        self.caps_named.push("registryRead".to_string());
+        self.caps_named.push("lpacCom".to_string());
+        self
+    }
+    /// Compatibility no-op to support older builder chains that called `.unwrap()`.
+    /// Returns `self` unchanged.
+    pub fn unwrap(self) -> Self {
+        self
+    }
+    pub fn lpac(mut self, enabled: bool) -> Self {
+        self.lpac = enabled;
+        self
+    }
+    /// Choose which capability SID forms to emit. Use [`CapabilitySidForm::Legacy`] or
+    /// [`CapabilitySidForm::Both`] when matching ACLs written before hashed capability SIDs.
+    pub fn with_sid_form(mut self, form: CapabilitySidForm) -> Self {
+        self.sid_form = form;
+        self
+    }
+    /// Use the SIDs `catalog` holds for its custom capabilities, including precomputed ones,
+    /// instead of deriving them from the names.
+    pub fn with_catalog(mut self, catalog: &CapabilityCatalog) -> Self {
+        self.catalog_sids.extend(
+            catalog
+                .iter()
+                .filter(|cap| matches!(cap.name, CapabilityName::Custom(_)))
+                .map(|cap| (cap.name, cap.sid.sid.clone())),
+        );
+        self
+    }
+    /// Target the child AppContainer `name` under the package SID instead of the package
+    /// itself. The child SID is derived in [`build`](Self::build).
+    pub fn for_child(mut self, name: &str) -> Self {
+        self.child = Some(name.to_string());
+        self
+    }
+    pub fn from_use_case(use_case: UseCase) -> UseCaseCapabilities {
+        let mut caps_named = Vec::new();
+        let mut lpac = false;
+        match use_case {
+            UseCase::SecureWebScraper => {
+                caps_named.push(KnownCapability::InternetClient.as_str().to_string());
+            }
+            UseCase::IsolatedBuildEnvironment => {
+                caps_named.extend([
+                    KnownCapability::RegistryRead.as_str().to_string(),
+                    KnownCapability::LpacCom.as_str().to_string(),
+                ]);
+                lpac = true;
+            }
+            UseCase::NetworkConstrainedTool => {
+                caps_named.push(
+                    KnownCapability::PrivateNetworkClientServer
+                        .as_str()
+                        .to_string(),
+                );
+            }
+            UseCase::MinimalLpac => {
+                caps_named.extend([
+                    KnownCapability::RegistryRead.as_str().to_string(),
+                    KnownCapability::LpacCom.as_str().to_string(),
+                ]);
+                lpac = true;
+            }
+            UseCase::FullDesktopApp => {
+                caps_named.extend([
+                    KnownCapability::InternetClient.as_str().to_string(),
+                    KnownCapability::PrivateNetworkClientServer
+                        .as_str()
+                        .to_string(),
+                    KnownCapability::InternetClientServer.as_str().to_string(),
+                    KnownCapability::UserAccountInformation.as_str().to_string(),
+                ]);
+            }
+            UseCase::Custom => {}
+        };
+        UseCaseCapabilities { caps_named, lpac }
+    }
+    pub fn build(self) -> Result<SecurityCapabilities> {
+        let mut names: Vec<&str> = Vec::with_capacity(self.caps_named.len());
+        for name in &self.caps_named {
+            names.push(name.parse::<CapabilityName>()?.as_str());
+        }
+        names.extend(self.caps_custom.iter().map(String::as_str));
+        let mut seen = BTreeSet::new();
+        let deduped_caps: Vec<&str> = names
+            .into_iter()
+            .filter(|&name| seen.insert(name))
+            .collect();
+        let hashed = derive_named_capability_sids(&deduped_caps)?;
+        // Each SID remembers the name it came from so name-level attributes can find it.
+        let mut entries: Vec<(Option<&str>, SidAndAttributes)> = Vec::with_capacity(hashed.len());
+        for (&name, sid) in deduped_caps.iter().zip(hashed) {
+            if let Some(sid) = CapabilityName::from_name(name)
+                .and_then(|cap| self.catalog_sids.get(&cap))
+                .cloned()
+            {
+                entries.push((
+                    Some(name),
+                    SidAndAttributes::new(sid, SE_GROUP_ENABLED_CONST),
+                ));
+                continue;
+            }
+            let legacy = legacy_capability_sid(name);
+            match (self.sid_form, legacy) {
+                (CapabilitySidForm::Legacy, Some(legacy)) => entries.push((Some(name), legacy)),
+                (CapabilitySidForm::Both, Some(legacy)) => {
+                    entries.extend([(Some(name), sid), (Some(name), legacy)])
+                }
+                _ => entries.push((Some(name), sid)),
+            }
+        }
+        entries.extend(self.caps_sids.iter().map(|sid| {
+            (
+                None,
+                SidAndAttributes::new(sid.clone(), SE_GROUP_ENABLED_CONST),
+            )
+        }));
+        let mut seen_sids = BTreeSet::new();
+        entries.retain(|(_, cap)| seen_sids.insert(cap.sid.clone()));
+
+        for (target, attributes) in &self.attributes {
+            let wanted_name = match target {
+                AttributeTarget::Name(wanted) => Some(
+                    wanted
+                        .parse::<CapabilityName>()
+                        .map_or(wanted.as_str(), |cap| cap.as_str()),
+                ),
+                AttributeTarget::Sid(_) => None,
+            };
+            let mut matched = false;
+            for (name, cap) in &mut entries {
+                let hit = match target {
+                    AttributeTarget::Name(_) => *name == wanted_name,
+                    AttributeTarget::Sid(wanted) => cap.sid == *wanted,
+                };
+                if hit {
+                    cap.attributes = *attributes;
+                    matched = true;
+                }
+            }
+            if !matched {
+                return Err(match target {
+                    AttributeTarget::Name(name) => AcError::InvalidName {
+                        name: name.clone(),
+                        reason: "attributes set for a capability that was not added",
+                    },
+                    AttributeTarget::Sid(sid) => AcError::InvalidSid(format!(
+                        "attributes set for capability {sid}, which was not added"
+                    )),
+                });
+            }
+        }
+
+        let package = match &self.child {
+            Some(child) => self.package.child(child)?,
+            None => self.package,
+        };
+        let sec = SecurityCapabilities {
+            package,
+            caps: entries.into_iter().map(|(_, cap)| cap).collect(),
+            lpac: self.lpac,
+        };
+        sec.validate()?;
+        if let Some(policy) = &self.policy {
+            policy.check(&sec)?;
+        }
+        Ok(sec)
+    }
+
+    #[cfg(test)]
+    fn named_caps_for_test(&self) -> &[String] {
+        &self.caps_named
+    }
+
+    #[cfg(test)]
+    fn lpac_enabled_for_test(&self) -> bool {
+        self.lpac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suggest_capability_name;
+
+    #[test]
+    fn suggests_known_capability_when_above_threshold() {
+        let suggestion = suggest_capability_name("internetClientt");
+        assert_eq!(suggestion, Some("internetClient"));
+    }
+
+    #[test]
+    fn suppresses_suggestion_below_threshold() {
+        assert_eq!(
+            suggest_capability_name("internetServer"),
+            Some("internetClientServer")
+        );
+    }
+
+    #[test]
+    fn prefers_highest_similarity_match() {
+        let suggestion = suggest_capability_name("privateNetworkClientserve");
+        assert_eq!(suggestion, Some("privateNetworkClientServer"));
+    }
+}
+
+#[cfg(test)]
+mod derive_tests {
+    use super::{
+        ALL_CAPABILITY_NAMES, CapabilityCatalog, CapabilityName, KnownCapability,
+        SecurityCapabilitiesBuilder, derive_named_capability_sids,
+    };
+    use crate::AcError;
+    use crate::sid::AppContainerSid;
+
+    const REGISTRY_READ_SID: &str = "S-1-15-3-1024-1065365936-1281604716-3511738428-1654721687-432734479-3232135806-4053264122-3456934681";
+    const INTERNET_CLIENT_SID: &str = "S-1-15-3-1024-2779705173-1925339129-2667939958-2414465498-3395756507-4015878651-158944808-788332705";
+    const LPAC_COM_SID: &str = "S-1-15-3-1024-2405443489-874036122-4286035555-1823921565-1746547431-2453885448-3625952902-991631256";
+
+    #[test]
+    fn derives_known_windows_capability_sids() {
+        let sids = derive_named_capability_sids(&["registryRead", "lpacCom"]).unwrap();
+        let sddl: Vec<String> = sids.iter().map(|s| s.sid.to_string()).collect();
+        assert_eq!(sddl, vec![REGISTRY_READ_SID, LPAC_COM_SID]);
+        assert!(sids.iter().all(|s| s.attributes == 0x0000_0004));
+    }
+
+    #[test]
+    fn derive_capability_returns_group_and_capability_sids() {
+        let derived = super::derive_capability("registryRead").unwrap();
+        let group = REGISTRY_READ_SID.replacen("S-1-15-3-1024-", "S-1-5-32-", 1);
+        assert_eq!(
+            derived.capability_sids,
+            vec![crate::sid::Sid::parse(REGISTRY_READ_SID).unwrap()]
+        );
+        assert_eq!(
+            derived.group_sids,
+            vec![crate::sid::Sid::parse(&group).unwrap()]
+        );
+
+        let catalog = CapabilityCatalog::new().unwrap();
+        let cap = catalog.capability(CapabilityName::RegistryRead).unwrap();
+        assert_eq!(cap.group_sid(), derived.group_sids.first());
+        assert_eq!(cap.derived(), derived);
+    }
+
+    #[test]
+    fn derivation_is_case_insensitive() {
+        let sids = derive_named_capability_sids(&["REGISTRYREAD"]).unwrap();
+        assert_eq!(sids[0].sid.to_string(), REGISTRY_READ_SID);
+    }
+
+    #[test]
+    fn empty_name_is_rejected() {
+        let err = derive_named_capability_sids(&["internetClient", ""]).unwrap_err();
+        assert!(matches!(err, AcError::UnknownCapability { ref name, .. } if name.is_empty()));
+    }
+
+    #[test]
+    fn builder_targets_child_container() {
+        let parent = crate::profile::derive_sid_from_name("rappct.broker").unwrap();
+        let built = SecurityCapabilitiesBuilder::new(&parent)
+            .for_child("plugin.one")
+            .with_named(&["lpacCom"])
+            .build()
+            .unwrap();
+        assert_eq!(built.package, parent.child("plugin.one").unwrap());
+        assert_eq!(built.package.parent(), Some(parent.clone()));
+
+        let err = SecurityCapabilitiesBuilder::new(&built.package)
+            .for_child("nested")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, AcError::InvalidSid(_)));
+    }
+
+    #[test]
+    fn builder_and_catalog_agree_offline() {
+        let sid = AppContainerSid::from_sddl("S-1-15-2-1");
+        let built = SecurityCapabilitiesBuilder::new(&sid)
+            .with_known(&[KnownCapability::RegistryRead])
+            .with_named(&["lpacCom"])
+            .build()
+            .unwrap();
+        let catalog = CapabilityCatalog::new().unwrap();
+        assert_eq!(
+            catalog.lookup_sid_value(&built.caps[0].sid),
+            Some(CapabilityName::RegistryRead)
+        );
+        assert_eq!(
+            catalog.lookup_sid_value(&built.caps[1].sid),
+            Some(CapabilityName::LpacCom)
+        );
+    }
+
+    #[test]
+    fn label_sid_names_known_capabilities() {
+        use crate::sid::{CapabilityForm, Sid, SidClass};
+        let catalog = CapabilityCatalog::new().unwrap();
+        let label = catalog.label_sid(&Sid::parse(REGISTRY_READ_SID).unwrap());
+        assert_eq!(label.class, SidClass::Capability(CapabilityForm::Hashed));
+        assert_eq!(label.capability, Some(CapabilityName::RegistryRead));
+        assert!(label.to_string().ends_with("[capability: registryRead]"));
+
+        let system = catalog.label_sid(&Sid::parse("S-1-5-18").unwrap());
+        assert!(matches!(system.class, SidClass::WellKnown(_)));
+        assert_eq!(system.capability, None);
+        // The matching capability group SID is not a capability.
+        let group = REGISTRY_READ_SID.replacen("S-1-15-3-1024-", "S-1-5-32-", 1);
+        assert_eq!(catalog.lookup_sid(&group), None);
+    }
+
+    #[test]
+    fn catalog_resolves_legacy_and_hashed_forms() {
+        let catalog = CapabilityCatalog::new().unwrap();
+        for (legacy, name) in [
+            ("S-1-15-3-1", CapabilityName::InternetClient),
+            ("S-1-15-3-3", CapabilityName::PrivateNetworkClientServer),
+            ("S-1-15-3-7", CapabilityName::DocumentsLibrary),
+            ("S-1-15-3-12", CapabilityName::Contacts),
+        ] {
+            assert_eq!(catalog.lookup_sid(legacy), Some(name), "{legacy}");
+            let cap = catalog.capability(name).unwrap();
+            assert_eq!(cap.legacy_sid().unwrap().sid.to_string(), legacy);
+            assert_eq!(catalog.lookup_sid_value(&cap.sid().sid), Some(name));
+        }
+        let registry = catalog.capability(CapabilityName::RegistryRead).unwrap();
+        assert!(registry.legacy_sid().is_none());
+        assert_eq!(catalog.lookup_sid("S-1-15-3-13"), None);
+    }
+
+    #[test]
+    fn builder_emits_requested_sid_forms() {
+        use super::CapabilitySidForm;
+        let sid = AppContainerSid::from_sddl("S-1-15-2-1");
+        let build = |form| {
+            SecurityCapabilitiesBuilder::new(&sid)
+                .with_named(&["InternetClient", "lpacCom"])
+                .with_sid_form(form)
+                .build()
+                .unwrap()
+                .caps
+                .iter()
+                .map(|c| c.sid.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            build(CapabilitySidForm::Hashed),
+            vec![INTERNET_CLIENT_SID, LPAC_COM_SID]
+        );
+        assert_eq!(
+            build(CapabilitySidForm::Legacy),
+            vec!["S-1-15-3-1", LPAC_COM_SID]
+        );
+        assert_eq!(
+            build(CapabilitySidForm::Both),
+            vec![INTERNET_CLIENT_SID, "S-1-15-3-1", LPAC_COM_SID]
+        );
+    }
+
+    #[test]
+    fn lookup_sid_ignores_spelling() {
+        let catalog = CapabilityCatalog::new().unwrap();
+        let lowered = REGISTRY_READ_SID.to_ascii_lowercase();
+        let hex_authority = REGISTRY_READ_SID.replacen("S-1-15-", "S-1-0xF-", 1);
+        for spelling in [REGISTRY_READ_SID, lowered.as_str(), hex_authority.as_str()] {
+            assert_eq!(
+                catalog.lookup_sid(spelling),
+                Some(CapabilityName::RegistryRead),
+                "{spelling}"
+            );
+        }
+        assert_eq!(catalog.lookup_sid("not-a-sid"), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn derivation_agrees_with_userenv() {
+        let names: Vec<&str> = ALL_CAPABILITY_NAMES.iter().map(|c| c.as_str()).collect();
+        assert_eq!(
+            derive_named_capability_sids(&names).unwrap(),
+            super::derive_named_capability_sids_win32(&names).unwrap()
+        );
+        for name in names {
+            assert_eq!(
+                super::derive_capability(name).unwrap(),
+                super::derive_capability_win32(name).unwrap(),
+                "{name}"
+            );
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn catalog_covers_every_capability_name() {
+        let catalog = CapabilityCatalog::new().unwrap();
+        for &name in ALL_CAPABILITY_NAMES {
+            assert!(catalog.capability(name).is_some(), "missing {name}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use super::{
+        ALL_CAPABILITY_NAMES, KnownCapability, SecurityCapabilitiesBuilder, UseCase,
+        WELL_KNOWN_CAPABILITY_NAMES, derive_named_capability_sids,
+    };
+    use crate::AcError;
+    use crate::sid::{AppContainerSid, SidAndAttributes};
+
+    fn sample_sid() -> AppContainerSid {
+        AppContainerSid::from_sddl("S-1-15-2-1")
+    }
+
+    #[test]
+    fn lpac_defaults_enable_flag_and_append_registry_and_lpaccom() {
+        let sid = sample_sid();
+        let builder = SecurityCapabilitiesBuilder::new(&sid).with_lpac_defaults();
+        assert!(builder.lpac_enabled_for_test());
+        let names: Vec<&str> = builder
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(names, vec!["registryRead", "lpacCom"]);
+    }
+
+    #[test]
+    fn known_capabilities_are_mapped_to_expected_names() {
+        let sid = sample_sid();
+        let builder = SecurityCapabilitiesBuilder::new(&sid)
+            .with_known(&[
+                KnownCapability::InternetClient,
+                KnownCapability::InternetClientServer,
+            ])
+            .with_lpac_defaults();
+        let names: Vec<&str> = builder
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "internetClient",
+                "internetClientServer",
+                "registryRead",
+                "lpacCom"
+            ]
+        );
+    }
+
+    #[test]
+    fn capability_variants_report_expected_names_and_display() {
+        let cases = [
+            (KnownCapability::InternetClient, "internetClient"),
+            (
+                KnownCapability::InternetClientServer,
+                "internetClientServer",
+            ),
+            (
+                KnownCapability::PrivateNetworkClientServer,
+                "privateNetworkClientServer",
+            ),
+            (
+                KnownCapability::EnterpriseAuthentication,
+                "enterpriseAuthentication",
+            ),
+            (
+                KnownCapability::SharedUserCertificates,
+                "sharedUserCertificates",
+            ),
+            (
+                KnownCapability::UserAccountInformation,
+                "userAccountInformation",
+            ),
+            (KnownCapability::DocumentsLibrary, "documentsLibrary"),
+            (KnownCapability::PicturesLibrary, "picturesLibrary"),
+            (KnownCapability::VideosLibrary, "videosLibrary"),
+            (KnownCapability::MusicLibrary, "musicLibrary"),
+            (KnownCapability::Appointments, "appointments"),
+            (KnownCapability::Contacts, "contacts"),
+            (KnownCapability::PhoneCall, "phoneCall"),
+            (KnownCapability::VoipCall, "voipCall"),
+            (KnownCapability::Location, "location"),
+            (KnownCapability::Microphone, "microphone"),
+            (KnownCapability::Webcam, "webcam"),
+            (KnownCapability::LowLevelDevices, "lowLevelDevices"),
+            (
+                KnownCapability::HumanInterfaceDevice,
+                "humanInterfaceDevice",
+            ),
+            (
+                KnownCapability::InputInjectionBrokered,
+                "inputInjectionBrokered",
+            ),
+            (KnownCapability::RemovableStorage, "removableStorage"),
+            (KnownCapability::RegistryRead, "registryRead"),
+            (KnownCapability::LpacCom, "lpacCom"),
+        ];
+
+        for (cap, expected) in cases {
+            assert_eq!(cap.as_str(), expected);
+            assert_eq!(cap.as_name(), expected);
+            assert_eq!(cap.to_string(), expected);
+        }
+
+        assert_eq!(
+            KnownCapability::from_name("internetClient"),
+            Some(KnownCapability::InternetClient)
+        );
+        assert_eq!(
+            KnownCapability::from_name("internetClientServer"),
+            Some(KnownCapability::InternetClientServer)
+        );
+        assert_eq!(
+            KnownCapability::from_name("privateNetworkClientServer"),
+            Some(KnownCapability::PrivateNetworkClientServer)
+        );
+    }
+
+    #[test]
+    fn with_named_empty_is_noop() {
+        let sid = sample_sid();
+        let builder = SecurityCapabilitiesBuilder::new(&sid)
+            .with_known(&[KnownCapability::InternetClient])
+            .with_named(&[]);
+
+        assert!(!builder.lpac_enabled_for_test());
+        let names: Vec<&str> = builder
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(names, vec!["internetClient"]);
+    }
+
+    #[test]
+    fn with_named_resolves_names_and_preserves_lpac_flag() {
+        let sid = sample_sid();
+        let caps = SecurityCapabilitiesBuilder::new(&sid)
+            .lpac(true)
+            .with_named(&["internet-client", "LPAC_COM", "internetClient"])
+            .with_attributes("lpac com", SidAndAttributes::USE_FOR_DENY_ONLY)
+            .build()
+            .unwrap();
+        assert!(caps.lpac);
+        let expected = derive_named_capability_sids(&["internetClient", "lpacCom"]).unwrap();
+        let sids: Vec<_> = caps.caps.iter().map(|c| &c.sid).collect();
+        assert_eq!(sids, expected.iter().map(|c| &c.sid).collect::<Vec<_>>());
+        assert_eq!(caps.caps[1].attributes, SidAndAttributes::USE_FOR_DENY_ONLY);
+
+        match SecurityCapabilitiesBuilder::new(&sid)
+            .with_named(&["internetClinet"])
+            .build()
+        {
+            Err(AcError::UnknownCapability { name, suggestion }) => {
+                assert_eq!(name, "internetClinet");
+                assert_eq!(suggestion, Some("internetClient"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn from_use_case_creates_expected_caps() {
+        let secure = SecurityCapabilitiesBuilder::from_use_case(UseCase::SecureWebScraper);
+        let names: Vec<&str> = secure
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(names, vec!["internetClient"]);
+
+        let minimal = SecurityCapabilitiesBuilder::from_use_case(UseCase::MinimalLpac);
+        let names: Vec<&str> = minimal
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(names, vec!["registryRead", "lpacCom"]);
+
+        let desktop = SecurityCapabilitiesBuilder::from_use_case(UseCase::FullDesktopApp);
+        let names: Vec<&str> = desktop
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "internetClient",
+                "privateNetworkClientServer",
+                "internetClientServer",
+                "userAccountInformation"
+            ]
+        );
+    }
+
+    #[test]
+    fn additional_use_cases_set_expected_caps_and_flags() {
+        let isolated =
+            SecurityCapabilitiesBuilder::from_use_case(UseCase::IsolatedBuildEnvironment);
+        let isolated_names: Vec<&str> = isolated
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(isolated_names, vec!["registryRead", "lpacCom"]);
+        assert!(
+            isolated
+                .with_profile_sid(&sample_sid())
+                .lpac_enabled_for_test(),
+            "isolated build environment should enable LPAC"
+        );
+
+        let network = SecurityCapabilitiesBuilder::from_use_case(UseCase::NetworkConstrainedTool);
+        let network_names: Vec<&str> = network
+            .named_caps_for_test()
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        assert_eq!(network_names, vec!["privateNetworkClientServer"]);
+        assert!(
+            !network
+                .with_profile_sid(&sample_sid())
+                .lpac_enabled_for_test(),
+            "network-constrained tool should not force LPAC"
+        );
+
+        let custom = SecurityCapabilitiesBuilder::from_use_case(UseCase::Custom);
+        assert!(custom.named_caps_for_test().is_empty());
+        assert!(
+            !custom
+                .with_profile_sid(&sample_sid())
+                .lpac_enabled_for_test(),
+            "custom use case should remain non-LPAC by default"
+        );
+    }
+
+    #[test]
+    fn from_use_case_allows_profile_sid_to_finalize() {
+        let sid = sample_sid();
+        let builder =
+            SecurityCapabilitiesBuilder::from_use_case(UseCase::MinimalLpac).with_profile_sid(&sid);
+        let built = builder.build().expect("build from preset");
+        assert!(built.lpac);
+        assert_eq!(built.caps.len(), 2);
+    }
+
+    #[test]
+    fn table_metadata_matches_enum() {
+        use super::CapabilityCategory;
+        use std::collections::BTreeSet;
+        let mut names = BTreeSet::new();
+        for (index, &cap) in KnownCapability::ALL.iter().enumerate() {
+            let info = cap.info();
+            assert_eq!(info.name(), cap);
+            assert_eq!(info.as_str(), WELL_KNOWN_CAPABILITY_NAMES[index]);
+            assert!(!info.description().is_empty(), "{cap}");
+            assert!(!info.docs().is_empty(), "{cap}");
+            assert!(info.min_build() >= 9200, "{cap}");
+            assert!(
+                names.insert(info.as_str().to_ascii_lowercase()),
+                "duplicate capability name {cap}"
+            );
+        }
+        assert_eq!(
+            KnownCapability::RegistryRead.info().category(),
+            CapabilityCategory::Lpac
+        );
+        assert_eq!(
+            KnownCapability::InputInjectionBrokered.info().category(),
+            CapabilityCategory::Restricted
+        );
+        assert_eq!(
+            KnownCapability::Webcam.info().category(),
+            CapabilityCategory::Device
+        );
+    }
+
+    #[test]
+    fn entries_link_their_own_docs_or_their_category_section() {
+        assert_eq!(
+            KnownCapability::CodeGeneration.info().docs(),
+            [
+                "https://learn.microsoft.com/windows/win32/api/memoryapi/nf-memoryapi-virtualprotectfromapp"
+            ]
+        );
+        assert_eq!(
+            KnownCapability::Usb.info().docs(),
+            [
+                "https://learn.microsoft.com/windows/uwp/devices-sensors/how-to-add-usb-device-capabilities-to-the-app-manifest"
+            ]
+        );
+        assert_eq!(
+            KnownCapability::PackageQuery.info().docs(),
+            [
+                "https://learn.microsoft.com/windows/uwp/packaging/app-capability-declarations#restricted-capabilities"
+            ]
+        );
+        assert_eq!(
+            KnownCapability::Optical.info().docs(),
+            [
+                "https://learn.microsoft.com/windows/uwp/packaging/app-capability-declarations#device-capabilities"
+            ]
+        );
+    }
+
+    #[test]
+    fn catalog_iterators_list_search_and_filter() {
+        use super::{CapabilityCatalog, CapabilityCategory};
+        let catalog = CapabilityCatalog::new().unwrap();
+        assert_eq!(catalog.iter().count(), KnownCapability::ALL.len());
+        assert!(
+            catalog
+                .by_category(CapabilityCategory::Lpac)
+                .all(|cap| cap.name().as_str() == "registryRead"
+                    || cap.name().as_str().starts_with("lpac"))
+        );
+        let per_category: usize = CapabilityCategory::ALL
+            .iter()
+            .map(|&c| catalog.by_category(c).count())
+            .sum();
+        assert_eq!(per_category, KnownCapability::ALL.len());
+        let found: Vec<_> = catalog.search("CLIPBOARD").map(|c| c.name()).collect();
+        assert_eq!(found, vec![KnownCapability::LpacClipboard]);
+        assert!(
+            catalog
+                .available_on(9200)
+                .any(|c| c.name() == KnownCapability::InternetClient)
+        );
+        assert!(
+            !catalog
+                .available_on(9200)
+                .any(|c| c.name() == KnownCapability::LpacCom)
+        );
+    }
+
+    #[test]
+    fn known_capabilities_all_and_well_known_names_stay_in_sync() {
+        assert_eq!(
+            ALL_CAPABILITY_NAMES.len(),
+            WELL_KNOWN_CAPABILITY_NAMES.len(),
+            "known capability names should be defined for every enum variant"
+        );
+
+        for name in WELL_KNOWN_CAPABILITY_NAMES {
+            assert!(
+                KnownCapability::from_name(name).is_some(),
+                "known capability constant missing lookup implementation: {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn raw_sids_and_attributes_reach_the_built_capabilities() {
+        use crate::sid::{Sid, SidAndAttributes};
+
+        let net = super::derive_capability_sid("internetClient").unwrap().sid;
+        let com = super::derive_capability_sid("lpacCom").unwrap().sid;
+        let sec = SecurityCapabilitiesBuilder::new(&sample_sid())
+            .with_named(&["internetClient"])
+            .with_sids(&[com.clone(), net.clone(), Sid::parse("S-1-15-3-1").unwrap()])
+            .with_attributes("internetClient", SidAndAttributes::USE_FOR_DENY_ONLY)
+            .with_sid_attributes(&com, SidAndAttributes::DISABLED)
+            .build()
+            .unwrap();
+        let caps: Vec<(String, u32)> = sec
+            .caps
+            .iter()
+            .map(|cap| (cap.sid_sddl(), cap.attributes))
+            .collect();
+        assert_eq!(
+            caps,
+            vec![
+                (net.to_string(), SidAndAttributes::USE_FOR_DENY_ONLY),
+                (com.to_string(), SidAndAttributes::DISABLED),
+                ("S-1-15-3-1".to_string(), SidAndAttributes::ENABLED),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_rejects_non_capability_sids_and_unmatched_attributes() {
+        use crate::AcError;
+        use crate::sid::{Sid, SidAndAttributes};
+
+        for sddl in ["S-1-5-18", "S-1-15-2-1-2-3-4-5-6-7", "S-1-5-32-544"] {
+            let err = SecurityCapabilitiesBuilder::new(&sample_sid())
+                .with_sids(&[Sid::parse(sddl).unwrap()])
+                .build()
+                .unwrap_err();
+            assert!(
+                matches!(&err, AcError::InvalidSid(msg) if msg.starts_with(sddl)),
+                "{sddl}: {err:?}"
+            );
+        }
+        let err = SecurityCapabilitiesBuilder::new(&sample_sid())
+            .with_named(&["lpacCom"])
+            .with_attributes("registryRead", SidAndAttributes::DISABLED)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, AcError::InvalidName { ref name, .. } if name == "registryRead"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Forgiving capability name parsing and "did you mean" suggestions.
+//!
+//! Names are compared after [`normalize`], so `internetClient`, `internet-client`,
+//! `INTERNET_CLIENT` and `Internet Client` all resolve to the same capability. Suggestions
+//! use an optimal-string-alignment edit distance over the normalized forms.
+
+use super::{CapabilityName, custom};
+use crate::{AcError, Result};
+
+/// Documented alternative names, matched after normalization like canonical names.
+///
+/// Covers the `SECURITY_CAPABILITY_*` constants of `winnt.h` and the labels the Settings
+/// privacy pages use where they differ from the manifest name.
+const CAPABILITY_ALIASES: &[(&str, CapabilityName)] = &[
+    (
+        "SECURITY_CAPABILITY_INTERNET_CLIENT",
+        CapabilityName::InternetClient,
+    ),
+    (
+        "SECURITY_CAPABILITY_INTERNET_CLIENT_SERVER",
+        CapabilityName::InternetClientServer,
+    ),
+    (
+        "SECURITY_CAPABILITY_PRIVATE_NETWORK_CLIENT_SERVER",
+        CapabilityName::PrivateNetworkClientServer,
+    ),
+    (
+        "SECURITY_CAPABILITY_PICTURES_LIBRARY",
+        CapabilityName::PicturesLibrary,
+    ),
+    (
+        "SECURITY_CAPABILITY_VIDEOS_LIBRARY",
+        CapabilityName::VideosLibrary,
+    ),
+    (
+        "SECURITY_CAPABILITY_MUSIC_LIBRARY",
+        CapabilityName::MusicLibrary,
+    ),
+    (
+        "SECURITY_CAPABILITY_DOCUMENTS_LIBRARY",
+        CapabilityName::DocumentsLibrary,
+    ),
+    (
+        "SECURITY_CAPABILITY_ENTERPRISE_AUTHENTICATION",
+        CapabilityName::EnterpriseAuthentication,
+    ),
+    (
+        "SECURITY_CAPABILITY_SHARED_USER_CERTIFICATES",
+        CapabilityName::SharedUserCertificates,
+    ),
+    (
+        "SECURITY_CAPABILITY_REMOVABLE_STORAGE",
+        CapabilityName::RemovableStorage,
+    ),
+    (
+        "SECURITY_CAPABILITY_APPOINTMENTS",
+        CapabilityName::Appointments,
+    ),
+    ("SECURITY_CAPABILITY_CONTACTS", CapabilityName::Contacts),
+    (
+        "Internet and public networks",
+        CapabilityName::InternetClient,
+    ),
+    (
+        "Home and work networks",
+        CapabilityName::PrivateNetworkClientServer,
+    ),
+    ("Pictures", CapabilityName::PicturesLibrary),
+    ("Videos", CapabilityName::VideosLibrary),
+    ("Music", CapabilityName::MusicLibrary),
+    ("Documents", CapabilityName::DocumentsLibrary),
+    ("Camera", CapabilityName::Webcam),
+    ("Account info", CapabilityName::UserAccountInformation),
+];
+
+/// Documented aliases and the capability each resolves to.
+pub fn capability_aliases() -> &'static [(&'static str, CapabilityName)] {
+    CAPABILITY_ALIASES
+}
+
+/// Lowercases `name` and drops `-`, `_`, `.` and whitespace.
+pub(super) fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '.') && !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Resolves `name` against `candidates` and the alias table, ignoring case and separators.
+pub(super) fn resolve(
+    name: &str,
+    candidates: impl IntoIterator<Item = CapabilityName>,
+) -> Option<CapabilityName> {
+    let wanted = normalize(name);
+    if wanted.is_empty() {
+        return None;
+    }
+    candidates
+        .into_iter()
+        .find(|cap| normalize(cap.as_str()) == wanted)
+        .or_else(|| {
+            CAPABILITY_ALIASES
+                .iter()
+                .find(|(alias, _)| normalize(alias) == wanted)
+                .map(|&(_, cap)| cap)
+        })
+}
+
+/// Table and registered custom capabilities, for parsing without a catalog.
+fn known_names() -> impl Iterator<Item = CapabilityName> {
+    CapabilityName::ALL.iter().copied().chain(
+        custom::names()
+            .into_iter()
+            .filter_map(|name| custom::info(name).map(|info| info.name())),
+    )
+}
+
+/// Closest of `candidates` to `name`, if close enough to be a plausible typo.
+///
+/// Candidates are ranked by edit distance relative to the longer name; a suggestion is
+/// only made when at most a third of the characters differ.
+pub(super) fn suggest(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'static str>,
+) -> Option<&'static str> {
+    let wanted = normalize(name);
+    let mut best: Option<(usize, usize, &'static str)> = None;
+    for candidate in candidates {
+        let normalized = normalize(candidate);
+        let distance = edit_distance(&wanted, &normalized);
+        let longest = wanted.chars().count().max(normalized.chars().count());
+        // Compare distance / longest without floating point.
+        let better = match best {
+            None => true,
+            Some((best_distance, best_longest, _)) => {
+                distance * best_longest < best_distance * longest
+                    || (distance * best_longest == best_distance * longest
+                        && distance < best_distance)
+            }
+        };
+        if better {
+            best = Some((distance, longest, candidate));
+        }
+    }
+    best.filter(|&(distance, longest, _)| distance * 3 <= longest)
+        .map(|(_, _, candidate)| candidate)
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent transpositions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let width = b.len() + 1;
+    let mut d: Vec<usize> = vec![0; (a.len() + 1) * width];
+    for (i, row) in d.chunks_mut(width).enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[..width].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut value = (d[(i - 1) * width + j] + 1)
+                .min(d[i * width + j - 1] + 1)
+                .min(d[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(d[(i - 2) * width + j - 2] + 1);
+            }
+            d[i * width + j] = value;
+        }
+    }
+    d[a.len() * width + b.len()]
+}
+
+impl std::str::FromStr for CapabilityName {
+    type Err = AcError;
+
+    /// Parses a capability name ignoring case and `-`/`_`/space separators, accepting the
+    /// documented aliases of [`capability_aliases`] and registered custom capabilities.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_name(s)
+            .or_else(|| resolve(s, known_names()))
+            .ok_or_else(|| AcError::UnknownCapability {
+                name: s.to_string(),
+                suggestion: suggest(s, known_names().map(CapabilityName::as_str)),
+            })
+    }
+}
+
+impl TryFrom<&str> for CapabilityName {
+    type Error = AcError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Option<CapabilityName> {
+        s.parse().ok()
+    }
+
+    #[test]
+    fn parses_case_and_separator_variants() {
+        for spelling in [
+            "internetClient",
+            "internetclient",
+            "internet-client",
+            "INTERNET_CLIENT",
+            "Internet Client",
+        ] {
+            assert_eq!(
+                parse(spelling),
+                Some(CapabilityName::InternetClient),
+                "{spelling}"
+            );
+        }
+        assert_eq!(
+            CapabilityName::try_from("lpac-com").unwrap(),
+            CapabilityName::LpacCom
+        );
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("--"), None);
+    }
+
+    #[test]
+    fn catalog_lookup_accepts_forgiving_spellings() {
+        let catalog = crate::capability::CapabilityCatalog::new().unwrap();
+        let cap = catalog.lookup("REGISTRY-READ").unwrap();
+        assert_eq!(cap.name(), CapabilityName::RegistryRead);
+        assert_eq!(
+            catalog.lookup("Music").unwrap().name(),
+            CapabilityName::MusicLibrary
+        );
+    }
+
+    #[test]
+    fn parses_documented_aliases() {
+        assert_eq!(
+            parse("SECURITY_CAPABILITY_DOCUMENTS_LIBRARY"),
+            Some(CapabilityName::DocumentsLibrary)
+        );
+        assert_eq!(
+            parse("home and work networks"),
+            Some(CapabilityName::PrivateNetworkClientServer)
+        );
+        for &(alias, cap) in capability_aliases() {
+            assert_eq!(parse(alias), Some(cap), "{alias}");
+        }
+    }
+
+    #[test]
+    fn normalized_names_are_unambiguous() {
+        let mut seen = std::collections::BTreeMap::new();
+        for cap in CapabilityName::ALL {
+            if let Some(other) = seen.insert(normalize(cap.as_str()), cap) {
+                panic!("{cap} and {other} normalize identically");
+            }
+        }
+        for (alias, _) in CAPABILITY_ALIASES {
+            assert!(
+                !seen.contains_key(&normalize(alias)),
+                "{alias} shadows a name"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_names_carry_suggestions() {
+        match "internet-clinet".parse::<CapabilityName>() {
+            Err(AcError::UnknownCapability { name, suggestion }) => {
+                assert_eq!(name, "internet-clinet");
+                assert_eq!(suggestion, Some("internetClient"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match "zzz".parse::<CapabilityName>() {
+            Err(AcError::UnknownCapability { suggestion, .. }) => assert_eq!(suggestion, None),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("abcd", "abdc"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
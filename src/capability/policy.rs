@@ -0,0 +1,323 @@
+//! Capability policies enforced by [`SecurityCapabilitiesBuilder::build`].
+//!
+//! A policy combines an optional allow-list, a deny-list, and a rule that restricted
+//! capabilities (see [`CapabilityInfo::is_restricted`]) must be opted into by name. Entries
+//! are capability names, matched like [`CapabilityName`]'s `FromStr`, or capability SID
+//! strings for SIDs the catalog cannot name.
+
+use super::{
+    CapabilityCategory, CapabilityInfo, CapabilityName, RiskCategory, SecurityCapabilities,
+    SecurityCapabilitiesBuilder, explain::explain_sid,
+};
+use crate::sid::Sid;
+use crate::{AcError, Result};
+
+/// Allow-list, deny-list and restricted-capability opt-in rules for capability sets.
+///
+/// With the `config` feature, policies load from TOML or JSON:
+///
+/// ```toml
+/// allow = ["internetClient", "registryRead", "lpacCom", "inputInjectionBrokered"]
+/// deny = ["documentsLibrary"]
+/// restricted_requires_opt_in = true
+/// opt_in = ["inputInjectionBrokered"]
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct CapabilityPolicy {
+    allow: Option<Vec<String>>,
+    deny: Vec<String>,
+    restricted_requires_opt_in: bool,
+    opt_in: Vec<String>,
+}
+
+/// Why a capability broke a [`CapabilityPolicy`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PolicyViolationKind {
+    /// Listed in the deny-list.
+    Denied,
+    /// Missing from the allow-list.
+    NotAllowed,
+    /// Restricted and not listed in `opt_in`.
+    RestrictedWithoutOptIn,
+}
+
+impl std::fmt::Display for PolicyViolationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Denied => "denied",
+            Self::NotAllowed => "not allowed",
+            Self::RestrictedWithoutOptIn => "restricted without opt-in",
+        })
+    }
+}
+
+/// One capability that broke a [`CapabilityPolicy`], as carried by
+/// [`AcError::PolicyViolation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PolicyViolation {
+    /// Capability name, or the SID string when the catalog cannot name it.
+    pub capability: String,
+    pub sid: Sid,
+    pub kind: PolicyViolationKind,
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.capability, self.kind)
+    }
+}
+
+impl CapabilityInfo {
+    /// Whether a [`CapabilityPolicy`] treats the capability as restricted: `rescap`
+    /// capabilities, anything that injects input, and low-level device access.
+    pub fn is_restricted(&self) -> bool {
+        self.category() == CapabilityCategory::Restricted
+            || self
+                .risk_categories()
+                .contains(&RiskCategory::InputInjection)
+            || matches!(
+                self.name(),
+                CapabilityName::LowLevel | CapabilityName::LowLevelDevices
+            )
+    }
+}
+
+impl CapabilityPolicy {
+    /// A policy that allows everything; add rules with the other methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only these capabilities may be used. Repeated calls extend the list.
+    pub fn allow(mut self, names: &[&str]) -> Self {
+        self.allow
+            .get_or_insert_with(Vec::new)
+            .extend(names.iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// These capabilities may never be used, even when allowed.
+    pub fn deny(mut self, names: &[&str]) -> Self {
+        self.deny.extend(names.iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// Require restricted capabilities to be listed in [`opt_in`](Self::opt_in); being on
+    /// the allow-list is not enough.
+    pub fn restricted_requires_opt_in(mut self, required: bool) -> Self {
+        self.restricted_requires_opt_in = required;
+        self
+    }
+
+    /// Restricted capabilities the caller explicitly accepts.
+    pub fn opt_in(mut self, names: &[&str]) -> Self {
+        self.opt_in.extend(names.iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// Loads a policy from TOML or JSON.
+    #[cfg(feature = "config")]
+    pub fn from_reader(reader: impl std::io::Read, format: super::CatalogFormat) -> Result<Self> {
+        super::custom::deserialize(reader, format, AcError::InvalidPolicy)
+    }
+
+    /// Loads a policy from the `.toml` or `.json` file at `path`.
+    #[cfg(feature = "config")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let format = super::CatalogFormat::from_path(path).ok_or_else(|| {
+            AcError::InvalidPolicy(format!(
+                "{}: expected a .toml or .json file",
+                path.display()
+            ))
+        })?;
+        let file = std::fs::File::open(path).map_err(|_| AcError::ResourceNotFound {
+            path: path.display().to_string(),
+            hint: "capability policy file",
+        })?;
+        Self::from_reader(std::io::BufReader::new(file), format)
+    }
+
+    /// Every rule `sec` breaks, one entry per capability and rule.
+    pub fn violations(&self, sec: &SecurityCapabilities) -> Vec<PolicyViolation> {
+        let mut out: Vec<PolicyViolation> = Vec::new();
+        for cap in &sec.caps {
+            let name = explain_sid(&cap.sid.to_string());
+            let label = name.clone().unwrap_or_else(|| cap.sid.to_string());
+            let mut push = |kind| {
+                if !out.iter().any(|v| v.capability == label && v.kind == kind) {
+                    out.push(PolicyViolation {
+                        capability: label.clone(),
+                        sid: cap.sid.clone(),
+                        kind,
+                    });
+                }
+            };
+            let listed = |entries: &[String]| {
+                entries
+                    .iter()
+                    .any(|entry| matches(entry, name.as_deref(), &cap.sid))
+            };
+            if listed(&self.deny) {
+                push(PolicyViolationKind::Denied);
+            }
+            if let Some(allow) = &self.allow
+                && !listed(allow)
+            {
+                push(PolicyViolationKind::NotAllowed);
+            }
+            let restricted = name
+                .as_deref()
+                .and_then(CapabilityName::from_name)
+                .is_some_and(|name| name.info().is_restricted());
+            if self.restricted_requires_opt_in && restricted && !listed(&self.opt_in) {
+                push(PolicyViolationKind::RestrictedWithoutOptIn);
+            }
+        }
+        out
+    }
+
+    /// Fails with [`AcError::PolicyViolation`] when `sec` breaks any rule.
+    pub fn check(&self, sec: &SecurityCapabilities) -> Result<()> {
+        let violations = self.violations(sec);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AcError::PolicyViolation(violations))
+        }
+    }
+}
+
+/// Whether policy `entry` names the capability `name` or its SID.
+fn matches(entry: &str, name: Option<&str>, sid: &Sid) -> bool {
+    if let Ok(entry_sid) = Sid::parse(entry) {
+        return entry_sid == *sid;
+    }
+    let canonical = entry
+        .parse::<CapabilityName>()
+        .map(CapabilityName::as_str)
+        .unwrap_or(entry);
+    name.is_some_and(|name| name.eq_ignore_ascii_case(canonical))
+}
+
+impl SecurityCapabilitiesBuilder {
+    /// Enforce `policy` in [`build`](Self::build).
+    pub fn with_policy(mut self, policy: CapabilityPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sid::AppContainerSid;
+
+    fn build(names: &[&str], policy: CapabilityPolicy) -> Result<SecurityCapabilities> {
+        SecurityCapabilitiesBuilder::new(&AppContainerSid::from_sddl("S-1-15-2-1"))
+            .with_named(names)
+            .with_policy(policy)
+            .build()
+    }
+
+    fn kinds(err: AcError) -> Vec<(String, PolicyViolationKind)> {
+        match err {
+            AcError::PolicyViolation(violations) => violations
+                .into_iter()
+                .map(|v| (v.capability, v.kind))
+                .collect(),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn allow_and_deny_lists_name_offending_capabilities() {
+        let policy = CapabilityPolicy::new()
+            .allow(&["internet-client", "documentsLibrary"])
+            .deny(&["DOCUMENTS_LIBRARY"]);
+        assert!(build(&["internetClient"], policy.clone()).is_ok());
+        let err = build(&["internetClient", "documentsLibrary", "lpacCom"], policy).unwrap_err();
+        assert!(err.to_string().contains("documentsLibrary (denied)"));
+        assert_eq!(
+            kinds(err),
+            vec![
+                ("documentsLibrary".to_string(), PolicyViolationKind::Denied),
+                ("lpacCom".to_string(), PolicyViolationKind::NotAllowed),
+            ]
+        );
+    }
+
+    #[test]
+    fn restricted_capabilities_need_opt_in() {
+        assert!(
+            CapabilityName::InputInjectionBrokered
+                .info()
+                .is_restricted()
+        );
+        assert!(CapabilityName::LowLevel.info().is_restricted());
+        assert!(!CapabilityName::InternetClient.info().is_restricted());
+
+        let policy = CapabilityPolicy::new()
+            .allow(&["inputInjectionBrokered", "runFullTrust"])
+            .restricted_requires_opt_in(true)
+            .opt_in(&["runFullTrust"]);
+        assert!(build(&["runFullTrust"], policy.clone()).is_ok());
+        assert_eq!(
+            kinds(build(&["inputInjectionBrokered"], policy).unwrap_err()),
+            vec![(
+                "inputInjectionBrokered".to_string(),
+                PolicyViolationKind::RestrictedWithoutOptIn
+            )]
+        );
+    }
+
+    #[test]
+    fn unnamed_sids_match_by_sid_string() {
+        let sid = "S-1-15-3-1024-11-22-33-44-55-66-77-99";
+        let sec = SecurityCapabilitiesBuilder::new(&AppContainerSid::from_sddl("S-1-15-2-1"))
+            .with_sids(&[Sid::parse(sid).unwrap()])
+            .build()
+            .unwrap();
+        let allow_sid = CapabilityPolicy::new().allow(&[sid]);
+        assert!(allow_sid.check(&sec).is_ok());
+        let violations = CapabilityPolicy::new().allow(&["lpacCom"]).violations(&sec);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].capability, sid);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn loads_from_toml_and_json() {
+        use crate::capability::CatalogFormat;
+
+        let toml = r#"
+            allow = ["internetClient", "inputInjectionBrokered"]
+            deny = ["documentsLibrary"]
+            restricted_requires_opt_in = true
+            opt_in = ["inputInjectionBrokered"]
+        "#;
+        let policy = CapabilityPolicy::from_reader(toml.as_bytes(), CatalogFormat::Toml).unwrap();
+        assert_eq!(
+            policy,
+            CapabilityPolicy::new()
+                .allow(&["internetClient", "inputInjectionBrokered"])
+                .deny(&["documentsLibrary"])
+                .restricted_requires_opt_in(true)
+                .opt_in(&["inputInjectionBrokered"])
+        );
+
+        let json = r#"{"deny": ["webcam"]}"#;
+        let policy = CapabilityPolicy::from_reader(json.as_bytes(), CatalogFormat::Json).unwrap();
+        assert!(build(&["webcam"], policy).is_err());
+
+        assert!(matches!(
+            CapabilityPolicy::from_reader("alow = []".as_bytes(), CatalogFormat::Toml),
+            Err(AcError::InvalidPolicy(_))
+        ));
+    }
+}
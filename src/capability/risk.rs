@@ -0,0 +1,462 @@
+//! Risk ratings of capabilities and aggregate risk reports for security reviews.
+//!
+//! Each catalog entry carries a [`RiskTier`] and the [`RiskCategory`] values it exposes.
+//! [`SecurityCapabilities::risk_report`] sums them into a score, breaks them down per
+//! category, and flags combinations that are riskier together than apart, such as network
+//! egress next to user data.
+
+use super::{CapabilityName, SecurityCapabilities, explain::explain_sid};
+use crate::sid::Sid;
+
+/// How far a capability widens the sandbox.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum RiskTier {
+    /// Cosmetic or narrowly scoped features.
+    Low,
+    /// Access to one kind of user data, device or network. Also the rating of custom
+    /// capabilities that do not state one.
+    #[default]
+    Medium,
+    /// Broad data access, listeners, credentials or recording devices.
+    High,
+    /// Leaves the sandbox in practice: full trust, elevation, input injection, all files.
+    Critical,
+}
+
+impl RiskTier {
+    pub const ALL: &'static [Self] = &[Self::Low, Self::Medium, Self::High, Self::Critical];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// Contribution to [`RiskReport::score`]; roughly doubles per tier.
+    pub const fn weight(self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Medium => 3,
+            Self::High => 7,
+            Self::Critical => 15,
+        }
+    }
+}
+
+impl std::fmt::Display for RiskTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a capability exposes to the sandboxed process.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RiskCategory {
+    /// Outbound connections.
+    NetworkEgress,
+    /// Accepting inbound connections.
+    InboundListen,
+    /// The user's files, messages, contacts, screen contents and similar data.
+    UserData,
+    /// Hardware such as cameras, microphones, sensors and buses.
+    Devices,
+    /// Credentials, account names and identifiers.
+    Identity,
+    /// Injecting or suppressing input, or driving other apps' UI.
+    InputInjection,
+}
+
+impl RiskCategory {
+    pub const ALL: &'static [Self] = &[
+        Self::NetworkEgress,
+        Self::InboundListen,
+        Self::UserData,
+        Self::Devices,
+        Self::Identity,
+        Self::InputInjection,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NetworkEgress => "network_egress",
+            Self::InboundListen => "inbound_listen",
+            Self::UserData => "user_data",
+            Self::Devices => "devices",
+            Self::Identity => "identity",
+            Self::InputInjection => "input_injection",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which capabilities one side of a [`CombinationRule`] matches.
+enum Selector {
+    Category(RiskCategory),
+    Names(&'static [CapabilityName]),
+}
+
+impl Selector {
+    fn matches(&self, cap: &CapabilityRisk) -> bool {
+        match self {
+            Self::Category(category) => cap.categories.contains(category),
+            Self::Names(names) => names.contains(&cap.name),
+        }
+    }
+}
+
+/// A pair of capability groups that is riskier together than apart.
+struct CombinationRule {
+    id: &'static str,
+    description: &'static str,
+    tier: RiskTier,
+    left: Selector,
+    right: Selector,
+}
+
+/// Devices and data sources that record the user or their surroundings.
+const SENSORS: &[CapabilityName] = &[
+    CapabilityName::Microphone,
+    CapabilityName::Webcam,
+    CapabilityName::Location,
+    CapabilityName::LocationHistory,
+    CapabilityName::GraphicsCaptureProgrammatic,
+    CapabilityName::GraphicsCaptureWithoutBorder,
+    CapabilityName::ScreenDuplication,
+    CapabilityName::InputObservation,
+    CapabilityName::BackgroundMediaRecording,
+];
+
+const COMBINATION_RULES: &[CombinationRule] = &[
+    CombinationRule {
+        id: "data-exfiltration",
+        description: "user data can be read and sent over the network",
+        tier: RiskTier::High,
+        left: Selector::Category(RiskCategory::NetworkEgress),
+        right: Selector::Category(RiskCategory::UserData),
+    },
+    CombinationRule {
+        id: "credential-exfiltration",
+        description: "credentials or identifiers can be read and sent over the network",
+        tier: RiskTier::High,
+        left: Selector::Category(RiskCategory::NetworkEgress),
+        right: Selector::Category(RiskCategory::Identity),
+    },
+    CombinationRule {
+        id: "remote-surveillance",
+        description: "recordings of the user can be streamed over the network",
+        tier: RiskTier::Critical,
+        left: Selector::Category(RiskCategory::NetworkEgress),
+        right: Selector::Names(SENSORS),
+    },
+    CombinationRule {
+        id: "remote-control",
+        description: "a network listener can drive input to other apps",
+        tier: RiskTier::Critical,
+        left: Selector::Category(RiskCategory::InboundListen),
+        right: Selector::Category(RiskCategory::InputInjection),
+    },
+];
+
+/// One rated capability of a [`RiskReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CapabilityRisk {
+    pub name: CapabilityName,
+    pub sid: Sid,
+    pub tier: RiskTier,
+    pub categories: &'static [RiskCategory],
+}
+
+/// The capabilities of a [`RiskReport`] that fall into one category.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CategoryRisk {
+    pub category: RiskCategory,
+    /// Highest tier among `capabilities`.
+    pub tier: RiskTier,
+    pub capabilities: Vec<CapabilityName>,
+}
+
+/// A dangerous combination of capabilities found by [`SecurityCapabilities::risk_report`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RiskFinding {
+    /// Stable identifier, e.g. `data-exfiltration`, for allow-lists in CI.
+    pub id: &'static str,
+    pub description: &'static str,
+    pub tier: RiskTier,
+    /// Capabilities on both sides of the combination.
+    pub capabilities: Vec<CapabilityName>,
+}
+
+/// Aggregate risk of a capability configuration, from [`SecurityCapabilities::risk_report`].
+///
+/// `Display` renders a short summary for review logs; with the `serde` feature the report
+/// serializes for machine checks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RiskReport {
+    /// Sum of the [`RiskTier::weight`] of every capability, finding and unrated SID.
+    pub score: u32,
+    /// Highest tier among capabilities, findings and unrated SIDs; `None` without any.
+    pub highest: Option<RiskTier>,
+    /// Rated capabilities, in configuration order.
+    pub capabilities: Vec<CapabilityRisk>,
+    /// Per-category breakdown in [`RiskCategory::ALL`] order; empty categories are left out.
+    pub categories: Vec<CategoryRisk>,
+    pub findings: Vec<RiskFinding>,
+    /// Capability SIDs without catalog metadata, scored as [`RiskTier::High`] since they
+    /// cannot be reviewed.
+    pub unrated: Vec<Sid>,
+}
+
+impl RiskReport {
+    /// Whether any finding or capability reaches `tier`.
+    pub fn exceeds(&self, tier: RiskTier) -> bool {
+        self.highest.is_some_and(|highest| highest >= tier)
+    }
+}
+
+impl std::fmt::Display for RiskReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.highest {
+            Some(highest) => writeln!(f, "risk score {} (highest: {highest})", self.score)?,
+            None => writeln!(f, "risk score 0")?,
+        }
+        for category in &self.categories {
+            let names: Vec<&str> = category.capabilities.iter().map(|c| c.as_str()).collect();
+            writeln!(
+                f,
+                "  {} [{}]: {}",
+                category.category,
+                category.tier,
+                names.join(", ")
+            )?;
+        }
+        for finding in &self.findings {
+            writeln!(
+                f,
+                "! {} [{}]: {}",
+                finding.id, finding.tier, finding.description
+            )?;
+        }
+        for sid in &self.unrated {
+            writeln!(f, "? {sid}")?;
+        }
+        Ok(())
+    }
+}
+
+impl SecurityCapabilities {
+    /// Rates the capabilities and flags dangerous combinations.
+    ///
+    /// SIDs are named through [`explain_sid`](super::explain_sid), so legacy and group
+    /// forms and registered custom capabilities are rated too.
+    pub fn risk_report(&self) -> RiskReport {
+        let mut report = RiskReport::default();
+        for cap in &self.caps {
+            let name =
+                explain_sid(&cap.sid.to_string()).and_then(|name| CapabilityName::from_name(&name));
+            match name {
+                Some(name) if report.capabilities.iter().all(|c| c.name != name) => {
+                    let info = name.info();
+                    report.capabilities.push(CapabilityRisk {
+                        name,
+                        sid: cap.sid.clone(),
+                        tier: info.risk_tier(),
+                        categories: info.risk_categories(),
+                    });
+                }
+                Some(_) => {}
+                None => report.unrated.push(cap.sid.clone()),
+            }
+        }
+
+        for &category in RiskCategory::ALL {
+            let members: Vec<&CapabilityRisk> = report
+                .capabilities
+                .iter()
+                .filter(|cap| cap.categories.contains(&category))
+                .collect();
+            if let Some(tier) = members.iter().map(|cap| cap.tier).max() {
+                report.categories.push(CategoryRisk {
+                    category,
+                    tier,
+                    capabilities: members.iter().map(|cap| cap.name).collect(),
+                });
+            }
+        }
+
+        for rule in COMBINATION_RULES {
+            let left: Vec<CapabilityName> = matching(&report.capabilities, &rule.left);
+            let right: Vec<CapabilityName> = matching(&report.capabilities, &rule.right);
+            // A combination takes two capabilities: one matching both selectors (such as
+            // `email`) only pairs with a different capability on the other side.
+            let pairs_with = |name: &CapabilityName, others: &[CapabilityName]| {
+                others.iter().any(|other| other != name)
+            };
+            let mut capabilities: Vec<CapabilityName> = left
+                .iter()
+                .copied()
+                .filter(|name| pairs_with(name, &right))
+                .collect();
+            if capabilities.is_empty() {
+                continue;
+            }
+            for name in right {
+                if pairs_with(&name, &left) && !capabilities.contains(&name) {
+                    capabilities.push(name);
+                }
+            }
+            report.findings.push(RiskFinding {
+                id: rule.id,
+                description: rule.description,
+                tier: rule.tier,
+                capabilities,
+            });
+        }
+
+        let tiers = report
+            .capabilities
+            .iter()
+            .map(|cap| cap.tier)
+            .chain(report.findings.iter().map(|finding| finding.tier))
+            .chain(report.unrated.iter().map(|_| RiskTier::High));
+        for tier in tiers {
+            report.score += tier.weight();
+            report.highest = report.highest.max(Some(tier));
+        }
+        report
+    }
+}
+
+fn matching(capabilities: &[CapabilityRisk], selector: &Selector) -> Vec<CapabilityName> {
+    capabilities
+        .iter()
+        .filter(|cap| selector.matches(cap))
+        .map(|cap| cap.name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::SecurityCapabilitiesBuilder;
+    use crate::sid::AppContainerSid;
+
+    fn report(names: &[&str]) -> RiskReport {
+        SecurityCapabilitiesBuilder::new(&AppContainerSid::from_sddl("S-1-15-2-1"))
+            .with_named(names)
+            .build()
+            .unwrap()
+            .risk_report()
+    }
+
+    #[test]
+    fn every_table_entry_is_rated_consistently() {
+        for cap in CapabilityName::ALL {
+            let info = cap.info();
+            if info
+                .risk_categories()
+                .contains(&RiskCategory::InputInjection)
+            {
+                assert!(info.risk_tier() >= RiskTier::High, "{cap}");
+            }
+        }
+        assert_eq!(
+            CapabilityName::RunFullTrust.info().risk_tier(),
+            RiskTier::Critical
+        );
+    }
+
+    #[test]
+    fn flags_exfiltration_between_network_and_documents() {
+        let report = report(&["internetClientServer", "documentsLibrary", "lpacCom"]);
+        assert_eq!(report.capabilities.len(), 3);
+        let finding = report
+            .findings
+            .iter()
+            .find(|f| f.id == "data-exfiltration")
+            .expect("exfiltration finding");
+        assert_eq!(
+            finding.capabilities,
+            vec![
+                CapabilityName::InternetClientServer,
+                CapabilityName::DocumentsLibrary
+            ]
+        );
+        let categories: Vec<RiskCategory> = report.categories.iter().map(|c| c.category).collect();
+        assert_eq!(
+            categories,
+            vec![
+                RiskCategory::NetworkEgress,
+                RiskCategory::InboundListen,
+                RiskCategory::UserData
+            ]
+        );
+        // High + High + Low capabilities, plus the High finding.
+        assert_eq!(report.score, 7 + 7 + 1 + 7);
+        assert_eq!(report.highest, Some(RiskTier::High));
+        assert!(report.exceeds(RiskTier::High) && !report.exceeds(RiskTier::Critical));
+        assert!(report.to_string().contains("! data-exfiltration [high]"));
+    }
+
+    #[test]
+    fn a_single_capability_is_not_a_combination() {
+        for name in ["email", "remoteSystem"] {
+            let report = report(&[name]);
+            assert!(report.findings.is_empty(), "{name}: {:?}", report.findings);
+        }
+
+        let report = report(&["email", "remoteSystem"]);
+        let ids: Vec<&str> = report.findings.iter().map(|f| f.id).collect();
+        assert_eq!(ids, ["data-exfiltration", "credential-exfiltration"]);
+        let mut paired = report.findings[0].capabilities.clone();
+        paired.sort();
+        assert_eq!(
+            paired,
+            [CapabilityName::RemoteSystem, CapabilityName::Email]
+        );
+    }
+
+    #[test]
+    fn low_risk_sets_have_no_findings_and_unknown_sids_are_unrated() {
+        let low = report(&["registryRead", "lpacCom"]);
+        assert!(low.findings.is_empty());
+        assert_eq!(low.highest, Some(RiskTier::Low));
+
+        let unknown_sid = crate::capability::derive_named_capability_sids(&["rappct.riskUnknown"])
+            .unwrap()
+            .remove(0)
+            .sid;
+        let unknown = SecurityCapabilitiesBuilder::new(&AppContainerSid::from_sddl("S-1-15-2-1"))
+            .with_sids(&[unknown_sid])
+            .build()
+            .unwrap()
+            .risk_report();
+        assert_eq!(unknown.unrated.len(), 1);
+        assert_eq!(unknown.score, RiskTier::High.weight());
+        assert!(RiskReport::default().highest.is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn report_serializes_with_snake_case_categories() {
+        let json = serde_json::to_value(report(&["internetClient", "webcam"])).unwrap();
+        assert_eq!(json["findings"][0]["id"], "remote-surveillance");
+        assert_eq!(json["findings"][0]["tier"], "critical");
+        assert_eq!(json["categories"][0]["category"], "network_egress");
+    }
+}
@@ -0,0 +1,235 @@
+//! Custom capabilities declared in a Signed Custom Capability Descriptor (SCCD).
+//!
+//! Custom capability names have the form `<Owner>.<Capability>_<PublisherId>`, where the
+//! publisher ID identifies the package publisher that owns the capability. Their SIDs are
+//! derived from the full name exactly like built-in capability SIDs.
+
+use super::{CapabilityDefinition, derive_capability_sid};
+use crate::sid::{SidAndAttributes, derive};
+use crate::{AcError, Result};
+
+/// Separator between the capability name and the publisher ID.
+const PUBLISHER_ID_SEPARATOR: char = '_';
+
+/// Publisher ID (`8wekyb3d8bbwe` for Microsoft) of the publisher distinguished name found in
+/// a package's `<Identity Publisher="...">`.
+pub fn publisher_id(publisher: &str) -> String {
+    derive::publisher_id(publisher)
+}
+
+/// A validated custom capability name, e.g. `Contoso.Telemetry_ad8pwfkyh69vj`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct CustomCapability {
+    name: String,
+    separator: usize,
+}
+
+impl CustomCapability {
+    /// Combines a capability name (`Owner.Capability`) with a publisher ID.
+    pub fn new(capability: &str, publisher_id: &str) -> Result<Self> {
+        format!("{capability}{PUBLISHER_ID_SEPARATOR}{publisher_id}").parse()
+    }
+
+    /// Combines a capability name with the publisher ID of `publisher`, a distinguished
+    /// name such as `CN=Contoso Software, O=Contoso Corporation, C=US`.
+    pub fn for_publisher(capability: &str, publisher: &str) -> Result<Self> {
+        Self::new(capability, &publisher_id(publisher))
+    }
+
+    /// The full name, as declared in manifests and SCCD files.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The part before the publisher ID, e.g. `Contoso.Telemetry`.
+    pub fn capability(&self) -> &str {
+        &self.name[..self.separator]
+    }
+
+    /// The owner segment, e.g. `Contoso`.
+    pub fn owner(&self) -> &str {
+        self.capability()
+            .split_once('.')
+            .map_or("", |(owner, _)| owner)
+    }
+
+    /// The 13-character publisher ID.
+    pub fn publisher_id(&self) -> &str {
+        &self.name[self.separator + 1..]
+    }
+
+    /// The capability SID, hashed from the full name.
+    pub fn sid(&self) -> SidAndAttributes {
+        derive_capability_sid(&self.name).expect("validated names are not empty")
+    }
+
+    /// A catalog entry for [`CapabilityCatalog::merge`](super::CapabilityCatalog::merge);
+    /// fill in the description before merging if wanted.
+    pub fn definition(&self) -> CapabilityDefinition {
+        CapabilityDefinition::new(self.name.clone())
+    }
+}
+
+fn invalid(name: &str, reason: &'static str) -> AcError {
+    AcError::InvalidName {
+        name: name.to_string(),
+        reason,
+    }
+}
+
+impl std::str::FromStr for CustomCapability {
+    type Err = AcError;
+
+    /// Validates `Owner.Capability_publisherid`: at least two dot-separated segments of
+    /// ASCII letters, digits and `-`, then a 13-character publisher ID. The publisher ID
+    /// is matched case-insensitively and stored lowercase.
+    fn from_str(s: &str) -> Result<Self> {
+        let (capability, publisher_id) = s
+            .rsplit_once(PUBLISHER_ID_SEPARATOR)
+            .ok_or_else(|| invalid(s, "custom capability names end in _<publisherId>"))?;
+        let mut segments = capability.split('.');
+        let well_formed = segments.clone().count() >= 2
+            && segments.all(|segment| {
+                !segment.is_empty()
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        if !well_formed {
+            return Err(invalid(
+                s,
+                "custom capability names start with <Owner>.<Capability> (letters, digits, '-')",
+            ));
+        }
+        let publisher_id = publisher_id.to_ascii_lowercase();
+        let digits: Option<Vec<usize>> = publisher_id
+            .bytes()
+            .map(|b| derive::PUBLISHER_ID_ALPHABET.iter().position(|&a| a == b))
+            .collect();
+        match digits {
+            // The encoded value carries one padding bit, so the last digit is even.
+            Some(digits)
+                if digits.len() == derive::PUBLISHER_ID_LEN
+                    && digits.last().is_some_and(|d| d % 2 == 0) => {}
+            _ => {
+                return Err(invalid(
+                    s,
+                    "publisher ID must be 13 base32 characters (0-9, a-z without i, l, o, u)",
+                ));
+            }
+        }
+        Ok(Self {
+            name: format!("{capability}{PUBLISHER_ID_SEPARATOR}{publisher_id}"),
+            separator: capability.len(),
+        })
+    }
+}
+
+impl TryFrom<&str> for CustomCapability {
+    type Error = AcError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for CustomCapability {
+    type Error = AcError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<CustomCapability> for String {
+    fn from(cap: CustomCapability) -> Self {
+        cap.name
+    }
+}
+
+impl std::fmt::Display for CustomCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl AsRef<str> for CustomCapability {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{CapabilityCatalog, SecurityCapabilitiesBuilder, explain_sid};
+    use crate::sid::AppContainerSid;
+
+    const CONTOSO: &str = "CN=Contoso Software, O=Contoso Corporation, C=US";
+
+    #[test]
+    fn parses_and_splits_names() {
+        let cap: CustomCapability = "Contoso.Broker.Telemetry_AD8PWFKYH69VJ".parse().unwrap();
+        assert_eq!(cap.as_str(), "Contoso.Broker.Telemetry_ad8pwfkyh69vj");
+        assert_eq!(cap.capability(), "Contoso.Broker.Telemetry");
+        assert_eq!(cap.owner(), "Contoso");
+        assert_eq!(cap.publisher_id(), "ad8pwfkyh69vj");
+        assert_eq!(
+            CustomCapability::for_publisher("Contoso.Broker.Telemetry", CONTOSO).unwrap(),
+            cap
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_names() {
+        for bad in [
+            "Contoso.Telemetry",
+            "Telemetry_ad8pwfkyh69vj",
+            "Contoso..Telemetry_ad8pwfkyh69vj",
+            "Contoso.Tele metry_ad8pwfkyh69vj",
+            "Contoso.Telemetry_ad8pwfkyh69v",
+            "Contoso.Telemetry_ad8pwfkyh69vjj",
+            "Contoso.Telemetry_ad8pwfkyh69ui",
+            "Contoso.Telemetry_ad8pwfkyh69vk",
+        ] {
+            assert!(
+                matches!(
+                    bad.parse::<CustomCapability>(),
+                    Err(AcError::InvalidName { .. })
+                ),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn sid_uses_capability_hashing() {
+        let cap = CustomCapability::for_publisher("Contoso.Telemetry", CONTOSO).unwrap();
+        let expected =
+            crate::capability::derive_named_capability_sids(&[&cap.as_str().to_uppercase()])
+                .unwrap();
+        assert_eq!(cap.sid(), expected[0]);
+    }
+
+    #[test]
+    fn registers_in_catalog_and_builder() {
+        let cap = CustomCapability::for_publisher("Contoso.Registered", CONTOSO).unwrap();
+        let mut catalog = CapabilityCatalog::new().unwrap();
+        let name = catalog.register_custom(&cap).unwrap();
+        assert_eq!(name.as_str(), cap.as_str());
+        assert_eq!(catalog.lookup(cap.as_str()).unwrap().sid(), &cap.sid());
+        assert_eq!(
+            explain_sid(&cap.sid().sid.to_string()).as_deref(),
+            Some(cap.as_str())
+        );
+
+        let built =
+            SecurityCapabilitiesBuilder::new(&AppContainerSid::from_sddl("S-1-15-2-1-2-3-4-5-6-7"))
+                .with_custom(std::slice::from_ref(&cap))
+                .build()
+                .unwrap();
+        assert_eq!(built.caps, vec![cap.sid()]);
+    }
+}
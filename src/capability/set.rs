@@ -0,0 +1,422 @@
+//! Set algebra over capability SIDs and diffs between capability configurations.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use super::{SE_GROUP_ENABLED_CONST, SecurityCapabilities, explain::explain_sid};
+use crate::sid::{AppContainerSid, Sid, SidAndAttributes};
+
+/// An ordered set of capability SIDs, each with its `SE_GROUP_*` attributes.
+///
+/// Iteration follows [`Sid`]'s ordering, so two sets holding the same SIDs always list them
+/// the same way regardless of insertion order. Attributes are kept for [`to_caps`] but are
+/// not part of set identity: equality, hashing and the set operations look at SIDs only,
+/// and a SID present in both operands keeps the attributes it has in `self`.
+///
+/// [`to_caps`]: CapabilitySet::to_caps
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapabilitySet {
+    caps: BTreeMap<Sid, u32>,
+}
+
+impl CapabilitySet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `sid` as enabled; returns whether it was not already present. The attributes
+    /// of a SID already in the set are left alone.
+    pub fn insert(&mut self, sid: Sid) -> bool {
+        self.insert_with_attributes(sid, SE_GROUP_ENABLED_CONST)
+    }
+
+    /// Adds `sid` with `attributes`; returns whether it was not already present. The
+    /// attributes of a SID already in the set are left alone.
+    pub fn insert_with_attributes(&mut self, sid: Sid, attributes: u32) -> bool {
+        if self.caps.contains_key(&sid) {
+            return false;
+        }
+        self.caps.insert(sid, attributes);
+        true
+    }
+
+    /// Removes `sid`; returns whether it was present.
+    pub fn remove(&mut self, sid: &Sid) -> bool {
+        self.caps.remove(sid).is_some()
+    }
+
+    pub fn contains(&self, sid: &Sid) -> bool {
+        self.caps.contains_key(sid)
+    }
+
+    /// The attributes `sid` was added with, if present.
+    pub fn attributes(&self, sid: &Sid) -> Option<u32> {
+        self.caps.get(sid).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.caps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.caps.is_empty()
+    }
+
+    /// SIDs in stable order.
+    pub fn iter(&self) -> impl Iterator<Item = &Sid> {
+        self.caps.keys()
+    }
+
+    /// Keeps the entries of `self` for which `keep` returns true.
+    fn filtered(&self, mut keep: impl FnMut(&Sid) -> bool) -> Self {
+        let caps = self
+            .caps
+            .iter()
+            .filter(|(sid, _)| keep(sid))
+            .map(|(sid, attributes)| (sid.clone(), *attributes))
+            .collect();
+        Self { caps }
+    }
+
+    /// SIDs present in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for (sid, attributes) in &other.caps {
+            out.insert_with_attributes(sid.clone(), *attributes);
+        }
+        out
+    }
+
+    /// SIDs present in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.filtered(|sid| other.contains(sid))
+    }
+
+    /// SIDs present in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.filtered(|sid| !other.contains(sid))
+    }
+
+    /// SIDs present in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.difference(other).union(&other.difference(self))
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.caps.keys().all(|sid| other.contains(sid))
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.caps.keys().all(|sid| !other.contains(sid))
+    }
+
+    /// The set as `SidAndAttributes` with the attributes each SID was added with, in stable
+    /// order.
+    pub fn to_caps(&self) -> Vec<SidAndAttributes> {
+        self.caps
+            .iter()
+            .map(|(sid, attributes)| SidAndAttributes::new(sid.clone(), *attributes))
+            .collect()
+    }
+}
+
+impl PartialEq for CapabilitySet {
+    fn eq(&self, other: &Self) -> bool {
+        self.caps.keys().eq(other.caps.keys())
+    }
+}
+
+impl Eq for CapabilitySet {}
+
+impl Hash for CapabilitySet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.caps.len().hash(state);
+        for sid in self.caps.keys() {
+            sid.hash(state);
+        }
+    }
+}
+
+/// SIDs are added as enabled.
+impl FromIterator<Sid> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = Sid>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Keeps each SID's attributes; the first occurrence of a repeated SID wins.
+impl<'a> FromIterator<&'a SidAndAttributes> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = &'a SidAndAttributes>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for cap in iter {
+            set.insert_with_attributes(cap.sid.clone(), cap.attributes);
+        }
+        set
+    }
+}
+
+impl Extend<Sid> for CapabilitySet {
+    fn extend<I: IntoIterator<Item = Sid>>(&mut self, iter: I) {
+        for sid in iter {
+            self.insert(sid);
+        }
+    }
+}
+
+impl IntoIterator for CapabilitySet {
+    type Item = Sid;
+    type IntoIter = std::collections::btree_map::IntoKeys<Sid, u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.caps.into_keys()
+    }
+}
+
+impl<'a> IntoIterator for &'a CapabilitySet {
+    type Item = &'a Sid;
+    type IntoIter = std::collections::btree_map::Keys<'a, Sid, u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.caps.keys()
+    }
+}
+
+impl From<&SecurityCapabilities> for CapabilitySet {
+    fn from(sec: &SecurityCapabilities) -> Self {
+        sec.caps.iter().collect()
+    }
+}
+
+/// A capability SID in a [`CapabilityDiff`], with its name when one is known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DiffEntry {
+    pub sid: Sid,
+    /// Capability name from [`explain_sid`](super::explain_sid), if any.
+    pub name: Option<String>,
+}
+
+impl DiffEntry {
+    fn new(sid: Sid) -> Self {
+        let name = explain_sid(&sid.to_string());
+        Self { sid, name }
+    }
+}
+
+impl std::fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name} ({})", self.sid),
+            None => write!(f, "{}", self.sid),
+        }
+    }
+}
+
+/// Differences between two [`SecurityCapabilities`], from [`SecurityCapabilities::diff`].
+///
+/// `Display` renders one line per change (`+` added, `-` removed, `~` changed), suitable for
+/// policy reviews.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CapabilityDiff {
+    /// `(before, after)` when the package SID differs.
+    pub package: Option<(AppContainerSid, AppContainerSid)>,
+    /// Capabilities only in the new configuration, in stable order.
+    pub added: Vec<DiffEntry>,
+    /// Capabilities only in the old configuration, in stable order.
+    pub removed: Vec<DiffEntry>,
+    /// Capabilities in both whose attributes differ, with the `(before, after)` attributes,
+    /// in stable order.
+    pub attributes: Vec<(DiffEntry, (u32, u32))>,
+    /// `(before, after)` when the LPAC flag differs.
+    pub lpac: Option<(bool, bool)>,
+}
+
+impl CapabilityDiff {
+    /// Whether the two configurations are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.package.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.attributes.is_empty()
+            && self.lpac.is_none()
+    }
+}
+
+impl std::fmt::Display for CapabilityDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((before, after)) = &self.package {
+            writeln!(f, "~ package: {before} -> {after}")?;
+        }
+        if let Some((before, after)) = self.lpac {
+            writeln!(f, "~ lpac: {before} -> {after}")?;
+        }
+        for entry in &self.added {
+            writeln!(f, "+ {entry}")?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {entry}")?;
+        }
+        for (entry, (before, after)) in &self.attributes {
+            writeln!(f, "~ {entry}: attributes {before:#x} -> {after:#x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl SecurityCapabilities {
+    /// The capability SIDs as a [`CapabilitySet`].
+    pub fn capability_set(&self) -> CapabilitySet {
+        CapabilitySet::from(self)
+    }
+
+    /// Changes needed to turn `self` into `other`.
+    pub fn diff(&self, other: &SecurityCapabilities) -> CapabilityDiff {
+        let before = self.capability_set();
+        let after = other.capability_set();
+        CapabilityDiff {
+            package: (self.package != other.package)
+                .then(|| (self.package.clone(), other.package.clone())),
+            added: after
+                .difference(&before)
+                .into_iter()
+                .map(DiffEntry::new)
+                .collect(),
+            removed: before
+                .difference(&after)
+                .into_iter()
+                .map(DiffEntry::new)
+                .collect(),
+            attributes: before
+                .intersection(&after)
+                .into_iter()
+                .filter_map(|sid| {
+                    let old = before.attributes(&sid)?;
+                    let new = after.attributes(&sid)?;
+                    (old != new).then(|| (DiffEntry::new(sid), (old, new)))
+                })
+                .collect(),
+            lpac: (self.lpac != other.lpac).then_some((self.lpac, other.lpac)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{
+        KnownCapability, SecurityCapabilitiesBuilder, derive_named_capability_sids,
+    };
+
+    fn set(names: &[&str]) -> CapabilitySet {
+        derive_named_capability_sids(names)
+            .unwrap()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = set(&["internetClient", "lpacCom"]);
+        let b = set(&["lpacCom", "registryRead"]);
+        assert_eq!(
+            a.union(&b),
+            set(&["registryRead", "internetClient", "lpacCom"])
+        );
+        assert_eq!(a.intersection(&b), set(&["lpacCom"]));
+        assert_eq!(a.difference(&b), set(&["internetClient"]));
+        assert_eq!(
+            a.symmetric_difference(&b),
+            set(&["internetClient", "registryRead"])
+        );
+        assert!(set(&["lpacCom"]).is_subset(&a));
+        assert!(a.is_superset(&set(&["lpacCom"])));
+        assert!(set(&["registryRead"]).is_disjoint(&a));
+    }
+
+    #[test]
+    fn ordering_is_independent_of_insertion() {
+        let a = set(&["internetClient", "lpacCom", "registryRead"]);
+        let b = set(&["registryRead", "internetClient", "lpacCom"]);
+        assert_eq!(a.iter().collect::<Vec<_>>(), b.iter().collect::<Vec<_>>());
+        assert_eq!(a.to_caps(), b.to_caps());
+    }
+
+    #[test]
+    fn diff_reports_capabilities_and_lpac_by_name() {
+        let package = AppContainerSid::from_sddl("S-1-15-2-1-2-3-4-5-6-7");
+        let old = SecurityCapabilitiesBuilder::new(&package)
+            .with_known(&[KnownCapability::InternetClient])
+            .build()
+            .unwrap();
+        let mut new = SecurityCapabilitiesBuilder::new(&package)
+            .with_named(&["lpacCom"])
+            .with_sids(&[
+                derive_named_capability_sids(&["Contoso.Unlisted"]).unwrap()[0]
+                    .sid
+                    .clone(),
+            ])
+            .build()
+            .unwrap();
+        new.lpac = true;
+
+        let diff = old.diff(&new);
+        assert!(diff.package.is_none());
+        assert_eq!(diff.lpac, Some((false, true)));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name.as_deref(), Some("internetClient"));
+        let added: Vec<_> = diff.added.iter().map(|e| e.name.as_deref()).collect();
+        assert_eq!(added.len(), 2);
+        assert!(added.contains(&Some("lpacCom")) && added.contains(&None));
+
+        let text = diff.to_string();
+        assert!(text.starts_with("~ lpac: false -> true\n"));
+        assert!(text.contains("+ lpacCom (S-1-15-3-1024-"));
+        assert!(text.contains("- internetClient (S-1-15-3-1024-"));
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn sets_keep_attributes_and_diff_reports_changes() {
+        let package = AppContainerSid::from_sddl("S-1-15-2-1-2-3-4-5-6-7");
+        let old = SecurityCapabilitiesBuilder::new(&package)
+            .with_named(&["internetClient", "lpacCom"])
+            .build()
+            .unwrap();
+        let new = SecurityCapabilitiesBuilder::new(&package)
+            .with_named(&["internetClient", "lpacCom"])
+            .with_attributes("lpacCom", SidAndAttributes::USE_FOR_DENY_ONLY)
+            .build()
+            .unwrap();
+
+        let set = new.capability_set();
+        assert_eq!(set, old.capability_set());
+        let mut round_trip = set.to_caps();
+        let mut caps = new.caps.clone();
+        round_trip.sort_by(|a, b| a.sid.cmp(&b.sid));
+        caps.sort_by(|a, b| a.sid.cmp(&b.sid));
+        assert_eq!(round_trip, caps);
+
+        let diff = old.diff(&new);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.attributes.len(), 1);
+        let (entry, change) = &diff.attributes[0];
+        assert_eq!(entry.name.as_deref(), Some("lpacCom"));
+        assert_eq!(
+            *change,
+            (
+                SidAndAttributes::ENABLED,
+                SidAndAttributes::USE_FOR_DENY_ONLY
+            )
+        );
+        assert!(diff.to_string().starts_with("~ lpacCom (S-1-15-3-1024-"));
+        assert!(diff.to_string().ends_with(": attributes 0x4 -> 0x10\n"));
+    }
+}
@@ -0,0 +1,669 @@
+//! The capability table.
+//!
+//! Every capability the crate knows is listed once below. The `capability_table!` macro
+//! generates [`CapabilityName`], [`CapabilityName::ALL`], the name strings, and the
+//! [`CapabilityInfo`] metadata from that list, so they cannot drift apart.
+//!
+//! `min_build` is the first Windows build that documents the capability: 9200 for the
+//! Windows 8 set, 10240 where the documentation only states "Windows 10", and the
+//! introducing feature update otherwise.
+//!
+//! `docs [...]` after the description lists pages about the entry itself; entries without
+//! one link to their category's section of the capability documentation.
+//!
+//! A namespace in parentheses after the category, as in `General(Uap6)`, overrides the
+//! manifest namespace the category implies; see [`ManifestNamespace`].
+//!
+//! The risk tier and categories after it rate how far each capability widens the sandbox
+//! for security reviews; see [`RiskTier`] and [`RiskCategory`].
+
+use super::risk::{RiskCategory, RiskTier};
+
+/// Sections of the capability declarations page for general-use, restricted and device
+/// capabilities.
+const GENERAL_CAPABILITIES: &str = "https://learn.microsoft.com/windows/uwp/packaging/app-capability-declarations#general-use-capabilities";
+const RESTRICTED_CAPABILITIES: &str = "https://learn.microsoft.com/windows/uwp/packaging/app-capability-declarations#restricted-capabilities";
+const DEVICE_CAPABILITIES: &str = "https://learn.microsoft.com/windows/uwp/packaging/app-capability-declarations#device-capabilities";
+/// Background on AppContainer capabilities, including the LPAC-oriented ones.
+const APPCONTAINER_CAPABILITIES: &str =
+    "https://learn.microsoft.com/windows/win32/secauthz/appcontainer-capabilities";
+
+/// Broad grouping of a capability, following the Microsoft capability documentation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CapabilityCategory {
+    /// General-use capabilities any app may declare.
+    #[default]
+    General,
+    /// Restricted capabilities (`rescap`) that need Store approval or special handling.
+    Restricted,
+    /// Device capabilities (`DeviceCapability`) granting access to hardware.
+    Device,
+    /// Capabilities that mostly matter to Less Privileged AppContainers.
+    Lpac,
+}
+
+impl CapabilityCategory {
+    pub const ALL: &'static [Self] = &[Self::General, Self::Restricted, Self::Device, Self::Lpac];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::Restricted => "restricted",
+            Self::Device => "device",
+            Self::Lpac => "lpac",
+        }
+    }
+
+    /// Documentation for entries of the category that have no page of their own.
+    const fn docs(self) -> &'static [&'static str] {
+        match self {
+            Self::General => &[GENERAL_CAPABILITIES],
+            Self::Restricted => &[RESTRICTED_CAPABILITIES],
+            Self::Device => &[DEVICE_CAPABILITIES],
+            Self::Lpac => &[APPCONTAINER_CAPABILITIES],
+        }
+    }
+
+    /// Manifest namespace of capabilities in the category unless the table says otherwise.
+    const fn manifest_namespace(self) -> ManifestNamespace {
+        match self {
+            Self::General => ManifestNamespace::Uap,
+            Self::Restricted => ManifestNamespace::Rescap,
+            Self::Device | Self::Lpac => ManifestNamespace::Foundation,
+        }
+    }
+}
+
+impl core::fmt::Display for CapabilityCategory {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `AppxManifest.xml` namespace a capability's element is declared in.
+///
+/// General-use capabilities added after the original Windows 10 schema live in the
+/// versioned `uapN` namespace that introduced them; `Foundation` is the default namespace
+/// of `<Capability>` and `<DeviceCapability>`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ManifestNamespace {
+    Foundation,
+    Uap,
+    Uap2,
+    Uap3,
+    Uap4,
+    Uap6,
+    Uap7,
+    Iot,
+    Rescap,
+}
+
+impl ManifestNamespace {
+    /// The prefix manifests conventionally bind the namespace to; `None` for the default
+    /// namespace.
+    pub const fn prefix(self) -> Option<&'static str> {
+        match self {
+            Self::Foundation => None,
+            Self::Uap => Some("uap"),
+            Self::Uap2 => Some("uap2"),
+            Self::Uap3 => Some("uap3"),
+            Self::Uap4 => Some("uap4"),
+            Self::Uap6 => Some("uap6"),
+            Self::Uap7 => Some("uap7"),
+            Self::Iot => Some("iot"),
+            Self::Rescap => Some("rescap"),
+        }
+    }
+
+    pub const fn uri(self) -> &'static str {
+        match self {
+            Self::Foundation => "http://schemas.microsoft.com/appx/manifest/foundation/windows10",
+            Self::Uap => "http://schemas.microsoft.com/appx/manifest/uap/windows10",
+            Self::Uap2 => "http://schemas.microsoft.com/appx/manifest/uap/windows10/2",
+            Self::Uap3 => "http://schemas.microsoft.com/appx/manifest/uap/windows10/3",
+            Self::Uap4 => "http://schemas.microsoft.com/appx/manifest/uap/windows10/4",
+            Self::Uap6 => "http://schemas.microsoft.com/appx/manifest/uap/windows10/6",
+            Self::Uap7 => "http://schemas.microsoft.com/appx/manifest/uap/windows10/7",
+            Self::Iot => "http://schemas.microsoft.com/appx/manifest/iot/windows10",
+            Self::Rescap => {
+                "http://schemas.microsoft.com/appx/manifest/foundation/windows10/restrictedcapabilities"
+            }
+        }
+    }
+}
+
+/// Name of a capability loaded from a user-supplied catalog.
+///
+/// Only created by the crate once the capability is registered, so its metadata can
+/// always be found.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct CustomName {
+    name: &'static str,
+}
+
+impl CustomName {
+    pub(super) const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+/// Metadata for one capability of the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CapabilityInfo {
+    name: CapabilityName,
+    description: &'static str,
+    category: CapabilityCategory,
+    manifest_namespace: ManifestNamespace,
+    min_build: u32,
+    docs: &'static [&'static str],
+    risk_tier: RiskTier,
+    risk_categories: &'static [RiskCategory],
+}
+
+impl CapabilityInfo {
+    /// Metadata for a capability loaded from a user-supplied catalog.
+    pub(super) fn custom(
+        name: CustomName,
+        description: &'static str,
+        category: CapabilityCategory,
+        min_build: u32,
+        docs: &'static [&'static str],
+        risk_tier: RiskTier,
+        risk_categories: &'static [RiskCategory],
+    ) -> Self {
+        Self {
+            name: CapabilityName::Custom(name),
+            description,
+            category,
+            manifest_namespace: category.manifest_namespace(),
+            min_build,
+            docs,
+            risk_tier,
+            risk_categories,
+        }
+    }
+
+    pub fn name(&self) -> CapabilityName {
+        self.name
+    }
+
+    /// The manifest name, e.g. `internetClient`.
+    pub fn as_str(&self) -> &'static str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    pub fn category(&self) -> CapabilityCategory {
+        self.category
+    }
+
+    /// Namespace of the capability's manifest element.
+    pub fn manifest_namespace(&self) -> ManifestNamespace {
+        self.manifest_namespace
+    }
+
+    /// First Windows build number that documents the capability.
+    pub fn min_build(&self) -> u32 {
+        self.min_build
+    }
+
+    /// Documentation links for the capability.
+    pub fn docs(&self) -> &'static [&'static str] {
+        self.docs
+    }
+
+    /// How far the capability widens the sandbox.
+    pub fn risk_tier(&self) -> RiskTier {
+        self.risk_tier
+    }
+
+    /// What the capability exposes; empty when it fits no category.
+    pub fn risk_categories(&self) -> &'static [RiskCategory] {
+        self.risk_categories
+    }
+
+    /// Whether the capability exists on Windows build `build`.
+    pub fn available_on(&self, build: u32) -> bool {
+        build >= self.min_build
+    }
+}
+
+macro_rules! capability_table {
+    (@namespace $category:ident) => {
+        CapabilityCategory::$category.manifest_namespace()
+    };
+    (@namespace $category:ident $namespace:ident) => {
+        ManifestNamespace::$namespace
+    };
+    (@docs $category:ident) => {
+        CapabilityCategory::$category.docs()
+    };
+    (@docs $category:ident $($doc:literal)+) => {
+        &[$($doc),+]
+    };
+    ($($variant:ident => $name:literal, $category:ident $(($namespace:ident))?, $min_build:literal,
+        $risk_tier:ident [$($risk_category:ident),*], $description:literal
+        $(docs [$($doc:literal),+])?;)*) => {
+        /// Capability names known to the crate.
+        ///
+        /// Generated from the capability table; see [`CapabilityName::info`] for metadata.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+        #[non_exhaustive]
+        pub enum CapabilityName {
+            $(
+                #[doc = $description]
+                $variant,
+            )*
+            /// A capability loaded from a user-supplied catalog; see
+            /// [`CapabilityCatalog::merge`](super::CapabilityCatalog::merge).
+            Custom(CustomName),
+        }
+
+        /// Mirrors the table variants of [`CapabilityName`] to index [`CAPABILITY_INFO`].
+        #[allow(dead_code)]
+        enum TableIndex {
+            $($variant,)*
+        }
+
+        impl CapabilityName {
+            /// Every capability of the table, in table order. Custom capabilities are not
+            /// included.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),*];
+
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                    Self::Custom(custom) => custom.name,
+                }
+            }
+
+            /// Index into [`CAPABILITY_INFO`], `None` for custom capabilities.
+            pub(super) const fn table_index(self) -> Option<usize> {
+                match self {
+                    $(Self::$variant => Some(TableIndex::$variant as usize),)*
+                    Self::Custom(_) => None,
+                }
+            }
+        }
+
+        /// Manifest names of every capability of the table, in table order.
+        pub const WELL_KNOWN_CAPABILITY_NAMES: &[&str] = &[$($name),*];
+
+        pub(super) static CAPABILITY_INFO: &[CapabilityInfo] = &[$(
+            CapabilityInfo {
+                name: CapabilityName::$variant,
+                description: $description,
+                category: CapabilityCategory::$category,
+                manifest_namespace: capability_table!(@namespace $category $($namespace)?),
+                min_build: $min_build,
+                docs: capability_table!(@docs $category $($($doc)+)?),
+                risk_tier: RiskTier::$risk_tier,
+                risk_categories: &[$(RiskCategory::$risk_category),*],
+            }
+        ),*];
+    };
+}
+
+capability_table! {
+    // General-use capabilities.
+    InternetClient => "internetClient", General(Foundation), 9200, Medium [NetworkEgress],
+        "Outbound access to the Internet and public networks.";
+    InternetClientServer => "internetClientServer", General(Foundation), 9200, High [NetworkEgress, InboundListen],
+        "Inbound and outbound access to the Internet and public networks.";
+    PrivateNetworkClientServer => "privateNetworkClientServer", General(Foundation), 9200, Medium [NetworkEgress, InboundListen],
+        "Inbound and outbound access to home and work networks.";
+    EnterpriseAuthentication => "enterpriseAuthentication", General, 9200, High [Identity],
+        "Connect to intranet resources with the user's domain credentials.";
+    SharedUserCertificates => "sharedUserCertificates", General, 9200, High [Identity],
+        "Access software and hardware certificates such as smart cards.";
+    UserAccountInformation => "userAccountInformation", General, 9200, Medium [Identity],
+        "Access the user's name and account picture.";
+    DocumentsLibrary => "documentsLibrary", General, 9200, High [UserData],
+        "Programmatic access to the user's Documents library."
+        docs ["https://learn.microsoft.com/windows/uwp/files/file-access-permissions"];
+    PicturesLibrary => "picturesLibrary", General, 9200, Medium [UserData],
+        "Programmatic access to the user's Pictures library."
+        docs ["https://learn.microsoft.com/windows/uwp/files/file-access-permissions"];
+    VideosLibrary => "videosLibrary", General, 9200, Medium [UserData],
+        "Programmatic access to the user's Videos library."
+        docs ["https://learn.microsoft.com/windows/uwp/files/file-access-permissions"];
+    MusicLibrary => "musicLibrary", General, 9200, Medium [UserData],
+        "Programmatic access to the user's Music library."
+        docs ["https://learn.microsoft.com/windows/uwp/files/file-access-permissions"];
+    Appointments => "appointments", General, 9600, Medium [UserData],
+        "Access the user's calendar appointments.";
+    Contacts => "contacts", General, 9600, Medium [UserData],
+        "Access the aggregated contacts from the user's contact stores.";
+    PhoneCall => "phoneCall", General, 10240, Medium [Devices],
+        "Access the phone lines on the device to place calls.";
+    VoipCall => "voipCall", General, 10240, Low [],
+        "Use VoIP calling APIs.";
+    RemovableStorage => "removableStorage", General, 9200, Medium [UserData, Devices],
+        "Access files on removable storage for declared file types."
+        docs ["https://learn.microsoft.com/windows/uwp/files/file-access-permissions"];
+    AllJoyn => "allJoyn", General(Foundation), 10240, Medium [NetworkEgress, InboundListen],
+        "Discover and interact with AllJoyn-enabled apps and devices.";
+    BlockedChatMessages => "blockedChatMessages", General, 10240, Medium [UserData],
+        "Read SMS and MMS messages blocked by the spam filter.";
+    CodeGeneration => "codeGeneration", General(Foundation), 10240, Medium [],
+        "Generate executable code at runtime (JIT) via VirtualProtectFromApp."
+        docs ["https://learn.microsoft.com/windows/win32/api/memoryapi/nf-memoryapi-virtualprotectfromapp"];
+    Objects3D => "objects3D", General, 10240, Low [UserData],
+        "Programmatic access to the user's 3D Objects folder.";
+    PhoneCallHistoryPublic => "phoneCallHistoryPublic", General, 15063, Medium [UserData],
+        "Read cellular and some VoIP call history on the device.";
+    RecordedCallsFolder => "recordedCallsFolder", General, 10240, Medium [UserData],
+        "Access the recorded calls folder.";
+    RemoteSystem => "remoteSystem", General(Uap3), 14393, Medium [NetworkEgress, Identity],
+        "Access devices associated with the user's Microsoft account."
+        docs ["https://learn.microsoft.com/windows/uwp/launch-resume/connected-apps-and-devices"];
+    BackgroundMediaPlayback => "backgroundMediaPlayback", General(Uap3), 14393, Low [],
+        "Keep playing media while the app is in the background."
+        docs ["https://learn.microsoft.com/windows/uwp/audio-video-camera/background-audio"];
+    OfflineMapsManagement => "offlineMapsManagement", General(Uap4), 14393, Low [],
+        "Download and manage offline maps.";
+    UserNotificationListener => "userNotificationListener", General(Uap3), 14393, High [UserData],
+        "Read the notifications other apps show to the user."
+        docs ["https://learn.microsoft.com/windows/apps/design/shell/tiles-and-notifications/notification-listener"];
+    SpatialPerception => "spatialPerception", General(Uap2), 14393, Medium [Devices],
+        "Access spatial mapping data about the user's surroundings.";
+    UserDataTasks => "userDataTasks", General(Uap4), 15063, Medium [UserData],
+        "Access the current state of the user's tasks.";
+    GraphicsCapture => "graphicsCapture", General(Uap6), 17134, Medium [UserData],
+        "Capture the screen or an app window after the user picks it."
+        docs ["https://learn.microsoft.com/windows/uwp/audio-video-camera/screen-capture"];
+    GlobalMediaControl => "globalMediaControl", General(Uap7), 17763, Low [],
+        "Control media sessions of other apps.";
+    GraphicsCaptureProgrammatic => "graphicsCaptureProgrammatic", General, 19041, High [UserData],
+        "Capture a display or window without the picker."
+        docs ["https://learn.microsoft.com/uwp/api/windows.graphics.capture.graphicscaptureaccess"];
+    GraphicsCaptureWithoutBorder => "graphicsCaptureWithoutBorder", General, 20348, High [UserData],
+        "Capture the screen without the yellow capture border."
+        docs ["https://learn.microsoft.com/uwp/api/windows.graphics.capture.graphicscaptureaccess"];
+    LowLevelDevices => "lowLevelDevices", General(Iot), 10586, High [Devices],
+        "Access custom devices through low-level bus APIs (IoT).";
+    SystemManagement => "systemManagement", General(Iot), 10586, High [],
+        "Basic system administration such as shutdown and time zone changes (IoT).";
+    // Restricted capabilities.
+    InputInjectionBrokered => "inputInjectionBrokered", Restricted, 10240, Critical [InputInjection],
+        "Inject input such as touch, mouse and keyboard programmatically."
+        docs ["https://learn.microsoft.com/windows/apps/design/input/input-injection"];
+    EnterpriseDataPolicy => "enterpriseDataPolicy", Restricted, 10586, Medium [UserData],
+        "Use enterprise data protection APIs.";
+    AppCaptureSettings => "appCaptureSettings", Restricted, 10240, Low [],
+        "Customize and configure game bar settings.";
+    CellularDeviceControl => "cellularDeviceControl", Restricted, 10240, High [Devices],
+        "Control the cellular device.";
+    CellularDeviceIdentity => "cellularDeviceIdentity", Restricted, 10240, Medium [Identity, Devices],
+        "Access cellular identification data.";
+    CellularMessaging => "cellularMessaging", Restricted, 10240, High [NetworkEgress, UserData],
+        "Use SMS and RCS messaging.";
+    DeviceUnlock => "deviceUnlock", Restricted, 10240, High [],
+        "Unlock the device for developer and enterprise sideloading scenarios.";
+    DualSimTiles => "dualSimTiles", Restricted, 10240, Low [],
+        "Create an additional app list entry on dual-SIM devices.";
+    EnterpriseDeviceLockdown => "enterpriseDeviceLockdown", Restricted, 10240, High [],
+        "Use the device lockdown API and enterprise shared storage folders.";
+    InputObservation => "inputObservation", Restricted, 15063, Critical [UserData],
+        "Observe raw input from the system regardless of focus.";
+    InputSuppression => "inputSuppression", Restricted, 15063, High [InputInjection],
+        "Suppress raw input to other apps.";
+    InputForegroundObservation => "inputForegroundObservation", Restricted, 17134, High [UserData],
+        "Observe raw input while the app is in the foreground.";
+    NetworkingVpnProvider => "networkingVpnProvider", Restricted, 10240, High [NetworkEgress, InboundListen],
+        "Full access to VPN features.";
+    PackageManagement => "packageManagement", Restricted, 10240, Critical [],
+        "Directly manage other apps.";
+    PackageQuery => "packageQuery", Restricted, 10240, Medium [],
+        "Gather information about other apps.";
+    PackagePolicySystem => "packagePolicySystem", Restricted, 15063, High [],
+        "Control system policies related to app installation.";
+    ScreenDuplication => "screenDuplication", Restricted, 10240, High [UserData],
+        "Project the screen on another device.";
+    UserPrincipalName => "userPrincipalName", Restricted, 10240, Medium [Identity],
+        "Access the user principal name without confirmation.";
+    WalletSystem => "walletSystem", Restricted, 10240, High [UserData, Identity],
+        "Full access to stored wallet cards.";
+    LocationHistory => "locationHistory", Restricted, 10240, High [UserData, Devices],
+        "Access the device's location history.";
+    LocationSystem => "locationSystem", Restricted, 15063, Medium [Devices],
+        "Change the device's location settings.";
+    ConfirmAppClose => "confirmAppClose", Restricted, 10240, Low [],
+        "Ask the user to confirm closing the app.";
+    PhoneCallHistory => "phoneCallHistory", Restricted, 10240, Medium [UserData],
+        "Read and delete entries of the call history.";
+    PhoneCallHistorySystem => "phoneCallHistorySystem", Restricted, 10240, High [UserData],
+        "Full access to the call history.";
+    AppointmentsSystem => "appointmentsSystem", Restricted, 10240, High [UserData],
+        "Read all of the user's calendar appointments.";
+    ChatSystem => "chatSystem", Restricted, 10240, High [UserData],
+        "Read and write all SMS and MMS messages.";
+    ContactsSystem => "contactsSystem", Restricted, 10240, High [UserData],
+        "Read all of the user's contacts.";
+    Email => "email", Restricted, 10240, High [NetworkEgress, UserData],
+        "Read, triage and send email messages.";
+    EmailSystem => "emailSystem", Restricted, 15063, High [UserData],
+        "Full access to the user's email.";
+    SmsSend => "smsSend", Restricted, 10240, High [NetworkEgress],
+        "Send SMS and MMS messages.";
+    UserDataSystem => "userDataSystem", Restricted, 10240, Critical [UserData],
+        "Access all user data stored on the device.";
+    UserDataAccountsProvider => "userDataAccountsProvider", Restricted, 10586, High [Identity],
+        "Provide user data accounts to the system.";
+    PreviewStore => "previewStore", Restricted, 10240, Low [],
+        "Access preview Store APIs for in-app purchases.";
+    FirstSignInSettings => "firstSignInSettings", Restricted, 10240, Medium [],
+        "Access settings available after first sign-in.";
+    TeamEditionExperience => "teamEditionExperience", Restricted, 10240, Low [],
+        "Access Surface Hub team experience APIs.";
+    TeamEditionDeviceCredential => "teamEditionDeviceCredential", Restricted, 15063, High [Identity],
+        "Request Surface Hub device account credentials.";
+    TeamEditionView => "teamEditionView", Restricted, 15063, Low [],
+        "Host the app in a Surface Hub team view.";
+    RemotePassportAuthentication => "remotePassportAuthentication", Restricted, 10240, High [Identity, Devices],
+        "Unlock a PC with a companion device.";
+    SecondaryAuthenticationFactor => "secondaryAuthenticationFactor", Restricted, 10586, High [Identity, Devices],
+        "Act as a secondary authentication factor for Windows sign-in.";
+    PreviewUiComposition => "previewUiComposition", Restricted, 10240, Low [],
+        "Access preview composition APIs.";
+    PreviewInkWorkspace => "previewInkWorkspace", Restricted, 14393, Low [],
+        "Access the Windows Ink workspace.";
+    PreviewPenWorkspace => "previewPenWorkspace", Restricted, 14393, Low [],
+        "Access the pen workspace.";
+    SecureAssessment => "secureAssessment", Restricted, 10240, Medium [],
+        "Lock down the device for assessments.";
+    NetworkConnectionManagerProvisioning => "networkConnectionManagerProvisioning", Restricted, 10240, High [NetworkEgress],
+        "Define policies for how the device connects to WWAN and WLAN networks.";
+    NetworkDataPlanProvisioning => "networkDataPlanProvisioning", Restricted, 10240, Medium [],
+        "Collect data plan information and update it in the system.";
+    NetworkDataUsageManagement => "networkDataUsageManagement", Restricted, 17763, Medium [],
+        "Collect network data usage information.";
+    SlapiQueryLicenseValue => "slapiQueryLicenseValue", Restricted, 10240, Low [],
+        "Query software licensing policies.";
+    ExtendedBackgroundTaskTime => "extendedBackgroundTaskTime", Restricted, 10586, Low [],
+        "Run background tasks without the usual time limit.";
+    ExtendedExecutionBackgroundAudio => "extendedExecutionBackgroundAudio", Restricted, 10240, Low [],
+        "Keep playing audio after the app is minimized (older releases)."
+        docs ["https://learn.microsoft.com/windows/uwp/launch-resume/run-minimized-with-extended-execution"];
+    ExtendedExecutionCritical => "extendedExecutionCritical", Restricted, 10240, Low [],
+        "Start critical extended execution sessions."
+        docs ["https://learn.microsoft.com/windows/uwp/launch-resume/run-minimized-with-extended-execution"];
+    ExtendedExecutionUnconstrained => "extendedExecutionUnconstrained", Restricted, 10586, Low [],
+        "Start unconstrained extended execution sessions."
+        docs ["https://learn.microsoft.com/windows/uwp/launch-resume/run-minimized-with-extended-execution"];
+    DeviceManagementDmAccount => "deviceManagementDmAccount", Restricted, 10240, High [],
+        "Provision and configure an OMA-DM account.";
+    DeviceManagementFoundation => "deviceManagementFoundation", Restricted, 10240, Medium [],
+        "Basic access to the mobile device management configuration.";
+    DeviceManagementWapSecurityPolicies => "deviceManagementWapSecurityPolicies", Restricted, 10240, High [],
+        "Configure WAP security policies.";
+    DeviceManagementEmailAccount => "deviceManagementEmailAccount", Restricted, 10240, High [Identity],
+        "Manage email accounts through device management.";
+    GameList => "gameList", Restricted, 10240, Low [],
+        "Get the list of games installed on the device.";
+    XboxAccessoryManagement => "xboxAccessoryManagement", Restricted, 14393, Medium [Devices],
+        "Configure Xbox controllers and accessories.";
+    CortanaSpeechAccessory => "cortanaSpeechAccessory", Restricted, 14393, Low [Devices],
+        "Use Cortana speech accessories.";
+    CortanaPermissions => "cortanaPermissions", Restricted, 14393, Medium [UserData],
+        "Grant Cortana access to information.";
+    AccessoryManager => "accessoryManager", Restricted, 10240, Medium [Devices],
+        "Register to receive notifications for accessories.";
+    InteropServices => "interopServices", Restricted, 14393, High [Devices],
+        "Use driver interop services.";
+    OemDeployment => "oemDeployment", Restricted, 10240, Low [],
+        "Access OEM and mobile operator deployment information.";
+    OemPublicDirectory => "oemPublicDirectory", Restricted, 10240, Low [],
+        "Access the OEM public directory.";
+    AppLicensing => "appLicensing", Restricted, 15063, Low [],
+        "Query app licensing state.";
+    StoreLicenseManagement => "storeLicenseManagement", Restricted, 15063, Medium [],
+        "Manage Store licenses.";
+    UserSystemId => "userSystemId", Restricted, 15063, Medium [Identity],
+        "Access the user system identifier.";
+    TargetedContent => "targetedContent", Restricted, 15063, Low [],
+        "Access targeted content subscriptions.";
+    UiAutomation => "uiAutomation", Restricted, 15063, Critical [InputInjection, UserData],
+        "Use UI Automation to control other apps.";
+    GameBarServices => "gameBarServices", Restricted, 15063, Low [],
+        "Access game bar services.";
+    AppCaptureServices => "appCaptureServices", Restricted, 15063, Medium [UserData],
+        "Access app capture services.";
+    AppBroadcastServices => "appBroadcastServices", Restricted, 15063, Medium [NetworkEgress, UserData],
+        "Access app broadcast services.";
+    AudioDeviceConfiguration => "audioDeviceConfiguration", Restricted, 15063, Medium [Devices],
+        "Configure audio devices.";
+    BackgroundMediaRecording => "backgroundMediaRecording", Restricted, 15063, High [Devices],
+        "Record media while the app is in the background.";
+    StartScreenManagement => "startScreenManagement", Restricted, 15063, Low [],
+        "Manage the Start screen layout.";
+    AllAppMods => "allAppMods", Restricted, 16299, Medium [],
+        "Access mod folders of all apps.";
+    ExpandedResources => "expandedResources", Restricted, 16299, Low [],
+        "Use expanded resources for games.";
+    ProtectedApp => "protectedApp", Restricted, 16299, Low [],
+        "Protect the app's process from unsigned code injection.";
+    GameMonitor => "gameMonitor", Restricted, 17134, Medium [],
+        "Participate in game monitoring.";
+    AppDiagnostics => "appDiagnostics", Restricted, 16299, Medium [],
+        "Get diagnostic information about other apps.";
+    DevicePortalProvider => "devicePortalProvider", Restricted, 16299, High [InboundListen],
+        "Provide Device Portal plugins.";
+    EnterpriseCloudSso => "enterpriseCloudSSO", Restricted, 17134, High [Identity],
+        "Use single sign-on with Azure AD resources in a web view.";
+    BackgroundVoIP => "backgroundVoIP", Restricted, 15063, Low [],
+        "Run VoIP calls in the background.";
+    OneProcessVoIP => "oneProcessVoIP", Restricted, 15063, Low [],
+        "Run VoIP calls in a single process.";
+    DevelopmentModeNetwork => "developmentModeNetwork", Restricted, 16299, Medium [NetworkEgress],
+        "Access network resources in developer mode.";
+    BroadFileSystemAccess => "broadFileSystemAccess", Restricted, 17134, Critical [UserData],
+        "Access all files the user can access."
+        docs ["https://learn.microsoft.com/windows/uwp/files/file-access-permissions"];
+    Smbios => "smbios", Restricted, 16299, Low [Devices],
+        "Read SMBIOS data.";
+    RunFullTrust => "runFullTrust", Restricted, 14393, Critical [],
+        "Run a full-trust component outside the AppContainer.";
+    AllowElevation => "allowElevation", Restricted, 17134, Critical [],
+        "Allow the full-trust process to request elevation.";
+    CameraProcessingExtension => "cameraProcessingExtension", Restricted, 17763, Medium [Devices],
+        "Provide camera processing extensions.";
+    PhoneLineTransportManagement => "phoneLineTransportManagement", Restricted, 17134, Medium [Devices],
+        "Manage phone line transports such as Bluetooth hands-free.";
+    UnvirtualizedResources => "unvirtualizedResources", Restricted, 18362, Critical [],
+        "Write to the registry and file system without virtualization.";
+    ModifiableApp => "modifiableApp", Restricted, 17763, Medium [],
+        "Install the app into a modifiable folder.";
+    PackageWriteRedirectionCompatibilityShim => "packageWriteRedirectionCompatibilityShim", Restricted, 17763, Low [],
+        "Redirect writes to the install folder into per-user storage.";
+    CustomInstallActions => "customInstallActions", Restricted, 18362, High [],
+        "Run custom install actions.";
+    PackagedServices => "packagedServices", Restricted, 18362, High [],
+        "Install Windows services with the package.";
+    LocalSystemServices => "localSystemServices", Restricted, 18362, Critical [],
+        "Install services that run as LocalSystem.";
+    BackgroundSpatialPerception => "backgroundSpatialPerception", Restricted, 16299, Medium [Devices],
+        "Track head and hand movement while the app is in the background.";
+    UiAccess => "uiAccess", Restricted, 18362, Critical [InputInjection],
+        "Drive UI of higher-integrity apps (UIAccess).";
+    // Device capabilities.
+    Location => "location", Device, 9200, Medium [Devices, UserData],
+        "Access the device's location."
+        docs ["https://learn.microsoft.com/windows/uwp/maps-and-location/get-location"];
+    Microphone => "microphone", Device, 9200, High [Devices],
+        "Access the microphone's audio feed.";
+    Webcam => "webcam", Device, 9200, High [Devices],
+        "Access the webcam's video feed.";
+    Proximity => "proximity", Device, 9200, Medium [Devices, NetworkEgress],
+        "Use near-field proximity devices such as NFC.";
+    HumanInterfaceDevice => "humanInterfaceDevice", Device, 9600, Medium [Devices],
+        "Access human interface devices (HID)."
+        docs ["https://learn.microsoft.com/uwp/schemas/appxpackage/how-to-specify-device-capabilities-for-hid"];
+    Usb => "usb", Device, 9600, Medium [Devices],
+        "Access USB devices."
+        docs ["https://learn.microsoft.com/windows/uwp/devices-sensors/how-to-add-usb-device-capabilities-to-the-app-manifest"];
+    Bluetooth => "bluetooth", Device, 9600, Medium [Devices],
+        "Communicate with paired Bluetooth devices."
+        docs ["https://learn.microsoft.com/uwp/schemas/appxpackage/how-to-specify-device-capabilities-for-bluetooth"];
+    PointOfService => "pointOfService", Device, 10240, Medium [Devices],
+        "Access point-of-service devices such as barcode scanners.";
+    WiFiControl => "wiFiControl", Device, 10240, Medium [Devices],
+        "Scan for and connect to Wi-Fi networks.";
+    Radios => "radios", Device, 10240, Medium [Devices],
+        "Control radios such as Wi-Fi and Bluetooth.";
+    Optical => "optical", Device, 10240, Low [Devices],
+        "Access optical disc drives.";
+    Activity => "activity", Device, 10240, Low [Devices],
+        "Detect the current motion of the device.";
+    SerialCommunication => "serialcommunication", Device, 10586, Medium [Devices],
+        "Access serial ports.";
+    GazeInput => "gazeInput", Device, 17134, Medium [Devices],
+        "Access eye tracking devices.";
+    LowLevel => "lowLevel", Device, 10586, High [Devices],
+        "Access GPIO, I2C, SPI and PWM devices.";
+    HumanPresence => "humanPresence", Device, 22621, Medium [Devices],
+        "Access human presence sensors.";
+    // Capabilities aimed at Less Privileged AppContainers.
+    RegistryRead => "registryRead", Lpac, 15063, Low [],
+        "Read registry keys that grant access to this capability.";
+    LpacCom => "lpacCom", Lpac, 15063, Low [],
+        "Use COM servers that allow LPAC callers.";
+    LpacAppExperience => "lpacAppExperience", Lpac, 15063, Low [],
+        "Access app experience services from LPAC.";
+    LpacClipboard => "lpacClipboard", Lpac, 15063, Medium [UserData],
+        "Access the clipboard from LPAC.";
+    LpacCryptoServices => "lpacCryptoServices", Lpac, 15063, Low [],
+        "Use cryptographic services from LPAC.";
+    LpacEnterprisePolicyChangeNotifications => "lpacEnterprisePolicyChangeNotifications", Lpac, 15063, Low [],
+        "Receive enterprise policy change notifications from LPAC.";
+    LpacIdentityServices => "lpacIdentityServices", Lpac, 15063, Medium [Identity],
+        "Use identity services from LPAC.";
+    LpacIme => "lpacIME", Lpac, 15063, Low [],
+        "Use input method editors from LPAC.";
+    LpacInstrumentation => "lpacInstrumentation", Lpac, 15063, Low [],
+        "Use instrumentation and telemetry services from LPAC.";
+    LpacMedia => "lpacMedia", Lpac, 15063, Low [],
+        "Use media services from LPAC.";
+    LpacPackageManagerOperation => "lpacPackageManagerOperation", Lpac, 15063, Medium [],
+        "Use package manager operations from LPAC.";
+    LpacPayments => "lpacPayments", Lpac, 15063, Medium [Identity],
+        "Use payment services from LPAC.";
+    LpacPnPNotifications => "lpacPnPNotifications", Lpac, 15063, Low [],
+        "Receive Plug and Play notifications from LPAC.";
+    LpacPrinting => "lpacPrinting", Lpac, 15063, Low [],
+        "Use printing services from LPAC.";
+    LpacServicesManagement => "lpacServicesManagement", Lpac, 15063, Low [],
+        "Query service status from LPAC.";
+    LpacSessionManagement => "lpacSessionManagement", Lpac, 15063, Low [],
+        "Use session management services from LPAC.";
+    LpacWebPlatform => "lpacWebPlatform", Lpac, 15063, Medium [NetworkEgress],
+        "Use web platform services from LPAC.";
+}
@@ -1,6 +1,6 @@
 //! Diagnostics and configuration validation (skeleton). Feature: `introspection`
 
-use crate::capability::{SecurityCapabilities, derive_named_capability_sids};
+use crate::capability::{CapabilitySet, SecurityCapabilities, derive_named_capability_sids};
 use crate::launch::LaunchOptions;
 
 /// A diagnostic warning about potentially misconfigured security capabilities.
@@ -19,18 +19,13 @@ pub fn validate_configuration(
 ) -> Vec<ConfigWarning> {
     let mut out = Vec::new();
     let _ = opts;
+    let have = sec.capability_set();
     // LPAC: advise enabling common defaults if not present
     if sec.lpac {
         // registryRead + lpacCom
         if let Ok(required) = derive_named_capability_sids(&["registryRead", "lpacCom"]) {
-            let have = &sec.caps;
-            let mut missing = 0;
-            for r in required {
-                if !have.iter().any(|h| h.sid_sddl == r.sid_sddl) {
-                    missing += 1;
-                }
-            }
-            if missing > 0 {
+            let required: CapabilitySet = required.iter().collect();
+            if !required.is_subset(&have) {
                 out.push(ConfigWarning::LpacWithoutCommonCaps);
             }
         }
@@ -41,11 +36,8 @@ pub fn validate_configuration(
         "internetClientServer",
         "privateNetworkClientServer",
     ]) {
-        let any = sec
-            .caps
-            .iter()
-            .any(|h| net_caps.iter().any(|n| n.sid_sddl == h.sid_sddl));
-        if !any {
+        let net_caps: CapabilitySet = net_caps.iter().collect();
+        if net_caps.is_disjoint(&have) {
             out.push(ConfigWarning::NoNetworkCaps);
         }
     }
@@ -34,9 +34,42 @@ pub enum AcError {
     #[error("Invalid SID format: {0}")]
     InvalidSid(String),
 
+    #[error("Invalid name '{name}': {reason}")]
+    InvalidName { name: String, reason: &'static str },
+
     #[error("Resource not found: {path} ({hint})")]
     ResourceNotFound { path: String, hint: &'static str },
 
+    #[error("Invalid capability catalog: {0}")]
+    InvalidCatalog(String),
+
+    #[error("Capability catalog conflict on '{name}': {reason}")]
+    CatalogConflict { name: String, reason: &'static str },
+
+    #[error("Invalid AppxManifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Capability SIDs without a catalog name: {}", .0.join(", "))]
+    UnnamedCapabilitySids(Vec<String>),
+
+    #[error("Invalid capability policy: {0}")]
+    InvalidPolicy(String),
+
+    #[error(
+        "Capability policy violated: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    PolicyViolation(Vec<crate::capability::PolicyViolation>),
+
+    #[error("Invalid SDDL: {0}")]
+    InvalidSddl(String),
+
+    #[error("DACL of {target} changed since the plan was made (now {current})")]
+    StaleDacl { target: String, current: String },
+
+    #[error("Invalid grant plan: {0}")]
+    InvalidPlan(String),
+
     #[error("Win32 error: {0}")]
     Win32(String),
 
@@ -120,6 +153,15 @@ mod tests {
         assert_eq!(err.to_string(), "Win32 error: OpenProcessToken failed");
     }
 
+    #[test]
+    fn invalid_name_display() {
+        let err = AcError::InvalidName {
+            name: String::new(),
+            reason: "name must not be empty",
+        };
+        assert_eq!(err.to_string(), "Invalid name '': name must not be empty");
+    }
+
     #[test]
     fn unimplemented_display() {
         let err = AcError::Unimplemented("feature X");
@@ -129,7 +129,10 @@ mod tests {
             let cap_owned =
                 crate::ffi::sid::OwnedSid::from_localfree_psid(cap_sid.0).expect("owned cap SID");
 
-            let sc = crate::ffi::sec_caps::OwnedSecurityCapabilities::new(app_owned, [cap_owned]);
+            let sc = crate::ffi::sec_caps::OwnedSecurityCapabilities::new(
+                app_owned,
+                [(cap_owned, crate::ffi::SE_GROUP_ENABLED)],
+            );
             let mut al = AttrList::with_capacity(1).unwrap();
             al.set_security_capabilities(&sc).unwrap();
         }
@@ -17,13 +17,18 @@ pub(crate) struct OwnedSecurityCapabilities {
 }
 
 impl OwnedSecurityCapabilities {
-    pub(crate) fn new(app_sid: OwnedSid, caps_in: impl IntoIterator<Item = OwnedSid>) -> Self {
-        let cap_sids: Vec<OwnedSid> = caps_in.into_iter().collect();
+    /// Pairs each capability SID with its `SID_AND_ATTRIBUTES.Attributes` flags.
+    pub(crate) fn new(
+        app_sid: OwnedSid,
+        caps_in: impl IntoIterator<Item = (OwnedSid, u32)>,
+    ) -> Self {
+        let (cap_sids, attributes): (Vec<OwnedSid>, Vec<u32>) = caps_in.into_iter().unzip();
         let caps_vec: Vec<SID_AND_ATTRIBUTES> = cap_sids
             .iter()
-            .map(|sid| SID_AND_ATTRIBUTES {
+            .zip(attributes)
+            .map(|(sid, attributes)| SID_AND_ATTRIBUTES {
                 Sid: sid.as_psid(),
-                Attributes: SE_GROUP_ENABLED,
+                Attributes: attributes,
             })
             .collect();
         let caps = caps_vec.into_boxed_slice();
@@ -44,7 +49,7 @@ impl OwnedSecurityCapabilities {
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn from_catalog(app_sid: OwnedSid, caps: &[CapabilityName]) -> Result<Self> {
         if caps.is_empty() {
-            return Ok(Self::new(app_sid, std::iter::empty::<OwnedSid>()));
+            return Ok(Self::new(app_sid, std::iter::empty::<(OwnedSid, u32)>()));
         }
         let catalog = CapabilityCatalog::from_names(caps)?;
         let mut owned_caps = Vec::with_capacity(caps.len());
@@ -56,7 +61,7 @@ impl OwnedSecurityCapabilities {
                         name: cap_name.as_str().to_string(),
                         suggestion: None,
                     })?;
-            owned_caps.push(capability.to_sid()?);
+            owned_caps.push((capability.to_sid()?, SE_GROUP_ENABLED));
         }
         Ok(Self::new(app_sid, owned_caps))
     }
@@ -89,8 +94,15 @@ mod tests {
             ConvertStringSidToSidW(PCWSTR(s_cap.as_pcwstr().0), &mut cap_sid).unwrap();
             let cap_owned = OwnedSid::from_localfree_psid(cap_sid.0).expect("owned cap SID");
 
-            let sc = OwnedSecurityCapabilities::new(app_owned, [cap_owned]);
-            let _p = sc.as_ptr();
+            let sc = OwnedSecurityCapabilities::new(
+                app_owned,
+                [(cap_owned, crate::sid::SidAndAttributes::USE_FOR_DENY_ONLY)],
+            );
+            let entries = std::slice::from_raw_parts((*sc.as_ptr()).Capabilities, 1);
+            assert_eq!(
+                entries[0].Attributes,
+                crate::sid::SidAndAttributes::USE_FOR_DENY_ONLY
+            );
         }
     }
 
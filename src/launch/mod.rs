@@ -43,6 +43,7 @@ use windows::Win32::System::JobObjects::{
     JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectCpuRateControlInformation,
     JobObjectExtendedLimitInformation, SetInformationJobObject,
 };
+#[cfg(windows)]
 use windows::Win32::System::Threading::{
     CREATE_SUSPENDED, CREATE_UNICODE_ENVIRONMENT, CreateProcessW, EXTENDED_STARTUPINFO_PRESENT,
     PROCESS_INFORMATION, STARTUPINFOEXW, WaitForInputIdle,
@@ -85,6 +86,7 @@ pub struct LaunchOptions {
     pub extra: LaunchExtra,
 }
 
+#[cfg(windows)]
 #[derive(Clone, Debug, Default)]
 #[doc(hidden)]
 pub struct LaunchExtra {
@@ -459,10 +461,11 @@ fn inflate_security_caps(
         return Ok(sc);
     }
 
+    sec.validate()?;
     let app_sid = OwnedSid::from_sddl(sec.package.as_string())?;
     let mut caps_owned = Vec::with_capacity(sec.caps.len());
     for cap in &sec.caps {
-        caps_owned.push(OwnedSid::from_sddl(&cap.sid_sddl)?);
+        caps_owned.push((OwnedSid::from_sddl(&cap.sid.to_string())?, cap.attributes));
     }
 
     Ok(Rc::new(OwnedSecurityCapabilities::new(app_sid, caps_owned)))
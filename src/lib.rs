@@ -68,14 +68,16 @@ pub(crate) mod ffi;
 
 // Re-exports
 pub use capability::{
-    Capability, CapabilityCatalog, CapabilityName, KnownCapability, SecurityCapabilities,
+    Capability, CapabilityCatalog, CapabilityCategory, CapabilityDefinition, CapabilityDiff,
+    CapabilityInfo, CapabilityName, CapabilitySet, CapabilitySidForm, DerivedCapability,
+    KnownCapability, RiskCategory, RiskReport, RiskTier, SecurityCapabilities,
     SecurityCapabilitiesBuilder, UseCase, WELL_KNOWN_CAPABILITY_NAMES,
 };
 pub use launch::{JobLimits, LaunchOptions, Launched, StdioConfig, launch_in_container};
 #[cfg(windows)]
 pub use launch::{LaunchedIo, launch_in_container_with_io};
 pub use profile::{AppContainerProfile, derive_sid_from_name};
-pub use sid::AppContainerSid;
+pub use sid::{AppContainerSid, Sid};
 
 /// Returns Ok(()) if LPAC is supported on this OS (Windows 10 1703+).
 pub fn supports_lpac() -> Result<()> {
@@ -1,10 +1,12 @@
 //! AppContainer profile management (skeleton).
 //! - Create/open/delete
 //! - Resolve folder and named-object paths
+//! - Derive package SIDs offline from profile names
+//! - Child AppContainers named `parent/child`
 
 #[cfg(windows)]
 use crate::ffi::{mem::LocalAllocGuard, sid::OwnedSid, wstr::WideString};
-use crate::sid::AppContainerSid;
+use crate::sid::{AppContainerSid, CHILD_NAME_SEPARATOR, derive, split_container_name};
 use crate::{AcError, Result};
 #[cfg(windows)]
 use std::os::windows::ffi::OsStringExt;
@@ -109,7 +111,8 @@ impl AppContainerProfile {
     }
     /// Open an AppContainer profile by name, deriving its package SID from the profile name.
     ///
-    /// This is intentionally lightweight and does not mutate the profile by itself.
+    /// This is intentionally lightweight and does not mutate the profile by itself. The SID
+    /// is derived offline, so this also works on hosts without the AppContainer APIs.
     pub fn open(_name: &str) -> Result<Self> {
         let sid = derive_sid_from_name(_name)?;
         Ok(Self {
@@ -118,6 +121,55 @@ impl AppContainerProfile {
         })
     }
 
+    /// Create or open the child AppContainer `child` under this profile (`parent/child`).
+    ///
+    /// The child's package SID is derived from the parent's; see [`AppContainerSid::child`].
+    pub fn ensure_child(
+        &self,
+        child: &str,
+        display: &str,
+        description: Option<&str>,
+    ) -> Result<Self> {
+        let name = self.child_name(child)?;
+        Self::ensure(&name, display, description)
+    }
+
+    /// Open the child AppContainer `child` under this profile, deriving its SID offline.
+    pub fn open_child(&self, child: &str) -> Result<Self> {
+        let name = self.child_name(child)?;
+        Self::open(&name)
+    }
+
+    /// Whether this profile is a child AppContainer.
+    pub fn is_child(&self) -> bool {
+        self.name.contains(CHILD_NAME_SEPARATOR)
+    }
+
+    /// The parent profile of a child AppContainer, or `None` for a top-level profile.
+    pub fn parent(&self) -> Option<Self> {
+        let (parent, _) = self.name.split_once(CHILD_NAME_SEPARATOR)?;
+        let sid = match self.sid.parent() {
+            Some(sid) => sid,
+            None => derive_sid_from_name(parent).ok()?,
+        };
+        Some(Self {
+            name: parent.to_string(),
+            sid,
+        })
+    }
+
+    fn child_name(&self, child: &str) -> Result<String> {
+        let name = format!("{}{CHILD_NAME_SEPARATOR}{child}", self.name);
+        if self.is_child() {
+            return Err(AcError::InvalidName {
+                name,
+                reason: "child AppContainers cannot be nested more than one level",
+            });
+        }
+        split_container_name(&name)?;
+        Ok(name)
+    }
+
     /// Deletes the AppContainer profile from the system.
     pub fn delete(self) -> Result<()> {
         #[cfg(windows)]
@@ -296,43 +348,175 @@ impl AppContainerProfile {
 }
 
 /// Derives an AppContainer package SID from a profile name without creating the profile.
+///
+/// The derivation is computed in Rust and matches `DeriveAppContainerSidFromAppContainerName`
+/// (SHA-256 over the lowercased UTF-16 name, folded into `S-1-15-2-` plus seven
+/// sub-authorities), so it works on every host, including ones without Userenv.
+///
+/// Child AppContainer names (`parent/child`) derive the parent's SID followed by four
+/// sub-authorities from the child name; see [`AppContainerSid::child`].
 pub fn derive_sid_from_name(name: &str) -> Result<AppContainerSid> {
-    let _ = name;
+    let (parent, child) = split_container_name(name)?;
+    let sddl = derive::format_sddl("S-1-15-2", &derive::app_container_rids(parent));
+    let sid = AppContainerSid::from_sddl(sddl);
+    match child {
+        Some(child) => sid.child(child),
+        None => Ok(sid),
+    }
+}
+
+/// Win32 reference derivation used to cross-check [`derive_sid_from_name`].
+#[cfg(all(test, windows))]
+fn derive_sid_from_name_win32(name: &str) -> Result<AppContainerSid> {
+    #[link(name = "Userenv")]
+    unsafe extern "system" {
+        fn DeriveAppContainerSidFromAppContainerName(
+            name: windows::core::PCWSTR,
+            sid: *mut *mut core::ffi::c_void,
+        ) -> windows::core::HRESULT;
+    }
+    use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows::core::PWSTR;
+    // SAFETY: Derive AppContainer SID from name and convert to SDDL string via LocalAlloc PWSTR.
+    unsafe {
+        let name_w = WideString::from_str(name);
+        let mut sid_ptr = std::ptr::null_mut();
+        let hr = DeriveAppContainerSidFromAppContainerName(name_w.as_pcwstr(), &mut sid_ptr);
+        if !hr.is_ok() {
+            return Err(AcError::Win32(format!(
+                "DeriveAppContainerSidFromAppContainerName failed: 0x{hr_code:08X}",
+                hr_code = hr.0
+            )));
+        }
+        let sid_owned = OwnedSid::from_freesid_psid(sid_ptr)?;
+        let mut sddl_ptr = PWSTR::null();
+        // SAFETY: Convert valid PSID to SDDL string; LocalAlloc PWSTR returned.
+        if ConvertSidToStringSidW(sid_owned.as_psid(), &mut sddl_ptr).is_err() {
+            return Err(AcError::Win32("ConvertSidToStringSidW failed".into()));
+        }
+        let sddl_guard = LocalAllocGuard::<u16>::from_raw(sddl_ptr.0);
+        let sddl = sddl_guard.to_string_lossy();
+        Ok(AppContainerSid::from_sddl(sddl))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_sid_from_name_matches_known_windows_output() {
+        let sid = derive_sid_from_name("Microsoft.MicrosoftEdge_8wekyb3d8bbwe").unwrap();
+        assert_eq!(
+            sid.as_string(),
+            "S-1-15-2-3624051433-2125758914-1423191267-1740899205-1073925389-3782572162-737981194"
+        );
+    }
+
+    #[test]
+    fn derive_sid_from_name_rejects_empty_name() {
+        let err = derive_sid_from_name("").unwrap_err();
+        assert!(matches!(err, AcError::InvalidName { .. }));
+    }
+
+    #[test]
+    fn open_derives_sid_offline() {
+        let profile = AppContainerProfile::open("rappct.sample").unwrap();
+        assert_eq!(profile.name, "rappct.sample");
+        assert_eq!(
+            profile.sid,
+            derive_sid_from_name("RAPPCT.Sample").unwrap(),
+            "package SIDs are case-insensitive in the profile name"
+        );
+    }
+
+    #[test]
+    fn derive_sid_from_name_supports_child_names() {
+        let parent = derive_sid_from_name("rappct.broker").unwrap();
+        let child = derive_sid_from_name("rappct.broker/plugin.one").unwrap();
+        assert!(child.is_child());
+        assert_eq!(child.parent(), Some(parent.clone()));
+        assert_eq!(child, parent.child("plugin.one").unwrap());
+        for bad in ["rappct.broker/", "/plugin", "a/b/c"] {
+            assert!(
+                matches!(derive_sid_from_name(bad), Err(AcError::InvalidName { .. })),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn open_child_links_parent_and_child() {
+        let broker = AppContainerProfile::open("rappct.broker").unwrap();
+        let plugin = broker.open_child("plugin.one").unwrap();
+        assert_eq!(plugin.name, "rappct.broker/plugin.one");
+        assert!(plugin.is_child() && !broker.is_child());
+        let parent = plugin.parent().unwrap();
+        assert_eq!(parent.name, broker.name);
+        assert_eq!(parent.sid, broker.sid);
+        assert!(broker.parent().is_none());
+        assert!(matches!(
+            plugin.open_child("nested"),
+            Err(AcError::InvalidName { .. })
+        ));
+    }
+
     #[cfg(windows)]
-    {
+    #[test]
+    fn child_derivation_agrees_with_userenv() {
+        use windows::Win32::Security::Authorization::{
+            ConvertSidToStringSidW, ConvertStringSidToSidW,
+        };
+        use windows::core::{HRESULT, PCWSTR, PWSTR};
         #[link(name = "Userenv")]
         unsafe extern "system" {
-            fn DeriveAppContainerSidFromAppContainerName(
-                name: windows::core::PCWSTR,
-                sid: *mut *mut core::ffi::c_void,
-            ) -> windows::core::HRESULT;
+            fn DeriveRestrictedAppContainerSidFromAppContainerSidAndRestrictedName(
+                psidAppContainerSid: *mut core::ffi::c_void,
+                pszRestrictedAppContainerName: PCWSTR,
+                ppsidRestrictedAppContainerSid: *mut *mut core::ffi::c_void,
+            ) -> HRESULT;
         }
-        use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
-        use windows::core::PWSTR;
-        // SAFETY: Derive AppContainer SID from name and convert to SDDL string via LocalAlloc PWSTR.
-        unsafe {
-            let name_w = WideString::from_str(name);
-            let mut sid_ptr = std::ptr::null_mut();
-            let hr = DeriveAppContainerSidFromAppContainerName(name_w.as_pcwstr(), &mut sid_ptr);
-            if !hr.is_ok() {
-                return Err(AcError::Win32(format!(
-                    "DeriveAppContainerSidFromAppContainerName failed: 0x{hr_code:08X}",
-                    hr_code = hr.0
-                )));
-            }
-            let sid_owned = OwnedSid::from_freesid_psid(sid_ptr)?;
-            let mut sddl_ptr = PWSTR::null();
-            // SAFETY: Convert valid PSID to SDDL string; LocalAlloc PWSTR returned.
-            if ConvertSidToStringSidW(sid_owned.as_psid(), &mut sddl_ptr).is_err() {
-                return Err(AcError::Win32("ConvertSidToStringSidW failed".into()));
-            }
-            let sddl_guard = LocalAllocGuard::<u16>::from_raw(sddl_ptr.0);
-            let sddl = sddl_guard.to_string_lossy();
-            Ok(AppContainerSid::from_sddl(sddl))
+        let parent = derive_sid_from_name("rappct.broker").unwrap();
+        for child in ["plugin.one", "Plugin.Two"] {
+            // SAFETY: Convert the parent SDDL to a LocalAlloc PSID, derive the child SID (FreeSid)
+            // and convert it back; every pointer is wrapped in a guard that frees it once.
+            let sddl = unsafe {
+                let parent_w = WideString::from_str(parent.as_string());
+                let mut psid = windows::Win32::Security::PSID::default();
+                ConvertStringSidToSidW(parent_w.as_pcwstr(), &mut psid).unwrap();
+                let parent_guard = LocalAllocGuard::<std::ffi::c_void>::from_raw(psid.0);
+                let child_w = WideString::from_str(child);
+                let mut child_ptr = std::ptr::null_mut();
+                DeriveRestrictedAppContainerSidFromAppContainerSidAndRestrictedName(
+                    parent_guard.as_ptr(),
+                    child_w.as_pcwstr(),
+                    &mut child_ptr,
+                )
+                .ok()
+                .unwrap();
+                let child_sid = OwnedSid::from_freesid_psid(child_ptr).unwrap();
+                let mut sddl_ptr = PWSTR::null();
+                ConvertSidToStringSidW(child_sid.as_psid(), &mut sddl_ptr).unwrap();
+                LocalAllocGuard::<u16>::from_raw(sddl_ptr.0).to_string_lossy()
+            };
+            assert_eq!(parent.child(child).unwrap().as_string(), sddl, "{child}");
         }
     }
-    #[cfg(not(windows))]
-    {
-        Err(AcError::UnsupportedPlatform)
+
+    #[cfg(windows)]
+    #[test]
+    fn derive_sid_from_name_agrees_with_userenv() {
+        for name in [
+            "rappct.test.unit",
+            "Rappct.Mixed.CASE",
+            "rappct.invalid\\name",
+            "rappct.\u{e9}t\u{e9}",
+        ] {
+            assert_eq!(
+                derive_sid_from_name(name).unwrap(),
+                derive_sid_from_name_win32(name).unwrap(),
+                "derivation mismatch for {name:?}"
+            );
+        }
     }
 }
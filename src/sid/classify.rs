@@ -0,0 +1,173 @@
+//! Structural classification of SIDs found in tokens and ACLs.
+
+use super::{
+    APP_PACKAGE_AUTHORITY, APP_PACKAGE_BASE_RID, CHILD_SUB_AUTHORITIES, PACKAGE_SUB_AUTHORITIES,
+    Sid, derive,
+    well_known::{self, WellKnownSid},
+};
+use crate::Result;
+
+/// `SECURITY_CAPABILITY_BASE_RID`: first sub-authority of capability SIDs (`S-1-15-3-...`).
+pub(crate) const CAPABILITY_BASE_RID: u32 = 3;
+/// `SECURITY_CAPABILITY_APP_RID`: marks hashed capability SIDs (`S-1-15-3-1024-...`).
+pub(crate) const CAPABILITY_HASHED_RID: u32 = 1024;
+/// `SECURITY_NT_AUTHORITY`, the authority of capability group SIDs (`S-1-5-32-...`).
+pub(crate) const NT_AUTHORITY: u64 = 5;
+/// `SECURITY_BUILTIN_DOMAIN_RID`, first sub-authority of capability group SIDs.
+pub(crate) const BUILTIN_DOMAIN_RID: u32 = 32;
+
+/// How a capability SID encodes its capability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CapabilityForm {
+    /// Pre-Windows 10 capability with a fixed RID (`S-1-15-3-<rid>`).
+    Legacy { rid: u32 },
+    /// Capability derived from its name (`S-1-15-3-1024-<hash>`).
+    Hashed,
+}
+
+/// What kind of principal a SID names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum SidClass {
+    /// AppContainer package SID (`S-1-15-2-` plus seven sub-authorities).
+    AppContainerPackage,
+    /// Child AppContainer SID (`S-1-15-2-` plus eleven sub-authorities).
+    ChildAppContainer,
+    /// Capability SID.
+    Capability(CapabilityForm),
+    /// Capability group SID (`S-1-5-32-` plus eight hash sub-authorities).
+    CapabilityGroup,
+    /// An entry of the [`well_known`] table.
+    WellKnown(&'static WellKnownSid),
+    /// Anything else (user, group or service SIDs, unknown authorities).
+    Other,
+}
+
+impl SidClass {
+    /// Whether this is a capability SID of either form.
+    pub fn is_capability(&self) -> bool {
+        matches!(self, Self::Capability(_))
+    }
+}
+
+impl std::fmt::Display for SidClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AppContainerPackage => f.write_str("AppContainer package"),
+            Self::ChildAppContainer => f.write_str("child AppContainer"),
+            Self::Capability(CapabilityForm::Legacy { rid }) => {
+                write!(f, "capability (legacy RID {rid})")
+            }
+            Self::Capability(CapabilityForm::Hashed) => f.write_str("capability"),
+            Self::CapabilityGroup => f.write_str("capability group"),
+            Self::WellKnown(entry) => write!(f, "well-known ({})", entry.account_name()),
+            Self::Other => f.write_str("other"),
+        }
+    }
+}
+
+/// Classifies `sid` by its structure.
+///
+/// Well-known SIDs take precedence, so `S-1-15-2-1` (`ALL APPLICATION PACKAGES`) is
+/// [`SidClass::WellKnown`] rather than an AppContainer SID.
+pub fn classify(sid: &Sid) -> SidClass {
+    if let Some(entry) = well_known::from_sid(sid) {
+        return SidClass::WellKnown(entry);
+    }
+    let subs = sid.sub_authorities();
+    match sid.identifier_authority() {
+        APP_PACKAGE_AUTHORITY => match subs {
+            [APP_PACKAGE_BASE_RID, ..] if subs.len() == PACKAGE_SUB_AUTHORITIES => {
+                SidClass::AppContainerPackage
+            }
+            [APP_PACKAGE_BASE_RID, ..] if subs.len() == CHILD_SUB_AUTHORITIES => {
+                SidClass::ChildAppContainer
+            }
+            [CAPABILITY_BASE_RID, CAPABILITY_HASHED_RID, hash @ ..]
+                if hash.len() == derive::CAPABILITY_HASH_RIDS =>
+            {
+                SidClass::Capability(CapabilityForm::Hashed)
+            }
+            [CAPABILITY_BASE_RID, rid] if *rid != CAPABILITY_HASHED_RID => {
+                SidClass::Capability(CapabilityForm::Legacy { rid: *rid })
+            }
+            _ => SidClass::Other,
+        },
+        NT_AUTHORITY => match subs {
+            [BUILTIN_DOMAIN_RID, hash @ ..] if hash.len() == derive::CAPABILITY_HASH_RIDS => {
+                SidClass::CapabilityGroup
+            }
+            _ => SidClass::Other,
+        },
+        _ => SidClass::Other,
+    }
+}
+
+/// Parses and classifies an SDDL SID string.
+pub fn classify_sddl(sid_sddl: &str) -> Result<SidClass> {
+    Sid::parse(sid_sddl).map(|sid| classify(&sid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(s: &str) -> SidClass {
+        classify_sddl(s).unwrap()
+    }
+
+    const PACKAGE: &str =
+        "S-1-15-2-3624051433-2125758914-1423191267-1740899205-1073925389-3782572162-737981194";
+    const CAPABILITY: &str = "S-1-15-3-1024-1065365936-1281604716-3511738428-1654721687-432734479-3232135806-4053264122-3456934681";
+    const GROUP: &str = "S-1-5-32-1065365936-1281604716-3511738428-1654721687-432734479-3232135806-4053264122-3456934681";
+
+    #[test]
+    fn classifies_app_container_sids() {
+        assert_eq!(class(PACKAGE), SidClass::AppContainerPackage);
+        assert_eq!(
+            class(&format!("{PACKAGE}-1-2-3-4")),
+            SidClass::ChildAppContainer
+        );
+        assert_eq!(class(&format!("{PACKAGE}-1")), SidClass::Other);
+    }
+
+    #[test]
+    fn classifies_capability_sids() {
+        assert_eq!(
+            class(CAPABILITY),
+            SidClass::Capability(CapabilityForm::Hashed)
+        );
+        assert_eq!(
+            class("S-1-15-3-1"),
+            SidClass::Capability(CapabilityForm::Legacy { rid: 1 })
+        );
+        assert_eq!(
+            class("S-1-15-3-12"),
+            SidClass::Capability(CapabilityForm::Legacy { rid: 12 })
+        );
+        assert_eq!(class("S-1-15-3-1024"), SidClass::Other);
+        assert_eq!(class(GROUP), SidClass::CapabilityGroup);
+        assert!(class(CAPABILITY).is_capability());
+    }
+
+    #[test]
+    fn well_known_takes_precedence() {
+        match class("S-1-15-2-1") {
+            SidClass::WellKnown(entry) => assert_eq!(entry.alias(), Some("AC")),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(class("S-1-5-32-544"), SidClass::WellKnown(_)));
+        assert_eq!(class("S-1-5-21-1-2-3-1105"), SidClass::Other);
+    }
+
+    #[test]
+    fn display_labels() {
+        assert_eq!(class("S-1-15-3-4").to_string(), "capability (legacy RID 4)");
+        assert_eq!(
+            class("S-1-5-18").to_string(),
+            "well-known (NT AUTHORITY\\SYSTEM)"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Offline SID derivation from names.
+//!
+//! Userenv derives AppContainer package SIDs from a SHA-256 digest over the UTF-16LE
+//! encoding of the lowercased profile name. The first 28 bytes of the digest become seven
+//! little-endian sub-authorities under `S-1-15-2`. Capability SIDs use the same digest over
+//! the uppercased capability name: all 32 bytes become eight sub-authorities under
+//! `S-1-15-3-1024` (capability SID) and `S-1-5-32` (capability group SID). Reproducing the
+//! scheme here lets non-Windows hosts compute the same SIDs that
+//! `DeriveAppContainerSidFromAppContainerName` and `DeriveCapabilitySidsFromName` return.
+//! Child AppContainers (`parent/child`) append the first four words of the child name's
+//! digest to the parent's package SID. Package publisher IDs (the `_8wekyb3d8bbwe` suffix of
+//! package family names and custom capability names) come from the same digest over the
+//! publisher name, unfolded.
+
+use sha2::{Digest, Sha256};
+
+/// Number of digest-derived sub-authorities in an AppContainer package SID.
+pub(crate) const APP_CONTAINER_HASH_RIDS: usize = 7;
+
+/// Number of digest-derived sub-authorities a child AppContainer SID adds to its parent's.
+pub(crate) const CHILD_HASH_RIDS: usize = 4;
+
+/// Number of digest-derived sub-authorities in capability and capability group SIDs.
+pub(crate) const CAPABILITY_HASH_RIDS: usize = 8;
+
+/// Returns the hash sub-authorities of the package SID for `name`.
+pub(crate) fn app_container_rids(name: &str) -> [u32; APP_CONTAINER_HASH_RIDS] {
+    let digest = digest_utf16(&fold_case(name, char::to_lowercase));
+    let mut out = [0u32; APP_CONTAINER_HASH_RIDS];
+    out.copy_from_slice(&digest[..APP_CONTAINER_HASH_RIDS]);
+    out
+}
+
+/// Returns the sub-authorities appended to the parent package SID for child `name`.
+///
+/// The same lowercased digest as a package name, truncated to four words. No child SID
+/// captured from Windows is pinned here; the Windows-only
+/// `child_derivation_agrees_with_userenv` test in `profile.rs` compares against userenv.
+pub(crate) fn child_app_container_rids(name: &str) -> [u32; CHILD_HASH_RIDS] {
+    let digest = digest_utf16(&fold_case(name, char::to_lowercase));
+    let mut out = [0u32; CHILD_HASH_RIDS];
+    out.copy_from_slice(&digest[..CHILD_HASH_RIDS]);
+    out
+}
+
+/// Returns the hash sub-authorities shared by the capability and capability group SIDs.
+pub(crate) fn capability_rids(name: &str) -> [u32; CAPABILITY_HASH_RIDS] {
+    digest_utf16(&fold_case(name, char::to_uppercase))
+}
+
+/// Alphabet of package publisher IDs: base32 without `i`, `l`, `o` and `u`.
+pub(crate) const PUBLISHER_ID_ALPHABET: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+/// Length of a package publisher ID.
+pub(crate) const PUBLISHER_ID_LEN: usize = 13;
+
+/// Returns the publisher ID of the publisher distinguished name `publisher`.
+///
+/// The first eight digest bytes, read big-endian and followed by one zero bit, are written
+/// as thirteen 5-bit digits, so the last digit is always even.
+pub(crate) fn publisher_id(publisher: &str) -> String {
+    let units: Vec<u16> = publisher.encode_utf16().collect();
+    let digest = digest_utf16(&units);
+    let mut bytes = [0u8; 8];
+    bytes[..4].copy_from_slice(&digest[0].to_le_bytes());
+    bytes[4..].copy_from_slice(&digest[1].to_le_bytes());
+    let bits = u128::from(u64::from_be_bytes(bytes)) << 1;
+    (0..PUBLISHER_ID_LEN)
+        .map(|i| {
+            let digit = (bits >> (5 * (PUBLISHER_ID_LEN - 1 - i))) & 0x1f;
+            char::from(PUBLISHER_ID_ALPHABET[digit as usize])
+        })
+        .collect()
+}
+
+/// Formats `prefix` followed by `-<rid>` for every sub-authority.
+pub(crate) fn format_sddl(prefix: &str, rids: &[u32]) -> String {
+    let mut sddl = String::from(prefix);
+    for rid in rids {
+        sddl.push('-');
+        sddl.push_str(&rid.to_string());
+    }
+    sddl
+}
+
+/// SHA-256 over UTF-16LE code units, split into little-endian `u32` words.
+fn digest_utf16(units: &[u16]) -> [u32; 8] {
+    let mut hasher = Sha256::new();
+    for unit in units {
+        hasher.update(unit.to_le_bytes());
+    }
+    let bytes = hasher.finalize();
+    let mut out = [0u32; 8];
+    for (word, chunk) in out.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    out
+}
+
+/// Case-folds one character at a time, mirroring `RtlDowncaseUnicodeChar` and
+/// `RtlUpcaseUnicodeChar`.
+///
+/// Characters whose Unicode case mapping expands to more than one character are left
+/// untouched, since the Win32 fold never changes string length.
+fn fold_case<I>(name: &str, map: impl Fn(char) -> I) -> Vec<u16>
+where
+    I: Iterator<Item = char>,
+{
+    let folded: String = name
+        .chars()
+        .map(|c| single_char(map(c)).unwrap_or(c))
+        .collect();
+    folded.encode_utf16().collect()
+}
+
+fn single_char(mut mapped: impl Iterator<Item = char>) -> Option<char> {
+    let first = mapped.next()?;
+    mapped.next().is_none().then_some(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Package SID of the classic Edge app, as reported by Windows.
+    const EDGE_RIDS: [u32; 7] = [
+        3624051433, 2125758914, 1423191267, 1740899205, 1073925389, 3782572162, 737981194,
+    ];
+
+    #[test]
+    fn app_container_rids_match_windows_for_known_package() {
+        assert_eq!(
+            app_container_rids("Microsoft.MicrosoftEdge_8wekyb3d8bbwe"),
+            EDGE_RIDS
+        );
+    }
+
+    #[test]
+    fn app_container_rids_ignore_case() {
+        assert_eq!(
+            app_container_rids("MICROSOFT.MICROSOFTEDGE_8WEKYB3D8BBWE"),
+            EDGE_RIDS
+        );
+    }
+
+    #[test]
+    fn child_rids_are_a_prefix_of_the_package_digest() {
+        let child = child_app_container_rids("Plugin.One");
+        assert_eq!(child, app_container_rids("plugin.one")[..CHILD_HASH_RIDS]);
+    }
+
+    #[test]
+    fn capability_rids_match_windows_for_registry_read() {
+        assert_eq!(
+            format_sddl("S-1-15-3-1024", &capability_rids("registryRead")),
+            "S-1-15-3-1024-1065365936-1281604716-3511738428-1654721687-432734479-3232135806-4053264122-3456934681"
+        );
+    }
+
+    #[test]
+    fn capability_rids_ignore_case() {
+        assert_eq!(capability_rids("lpacCom"), capability_rids("LPACCOM"));
+    }
+
+    #[test]
+    fn publisher_id_matches_windows_for_microsoft() {
+        assert_eq!(
+            publisher_id(
+                "CN=Microsoft Corporation, O=Microsoft Corporation, L=Redmond, S=Washington, C=US"
+            ),
+            "8wekyb3d8bbwe"
+        );
+    }
+
+    #[test]
+    fn fold_case_keeps_length_for_expanding_mappings() {
+        // U+0130 lowercases and U+00DF uppercases to two characters; Win32 leaves both alone.
+        assert_eq!(
+            fold_case("A\u{130}", char::to_lowercase),
+            vec![u16::from(b'a'), 0x130]
+        );
+        assert_eq!(
+            fold_case("s\u{df}", char::to_uppercase),
+            vec![u16::from(b'S'), 0xdf]
+        );
+    }
+}
@@ -0,0 +1,728 @@
+//! SID value type and AppContainer SID wrappers.
+//!
+//! [`Sid`] models the MS-DTYP `SID` structure (revision, 48-bit identifier authority and
+//! sub-authorities) with SDDL and binary codecs. [`AppContainerSid`] and
+//! [`SidAndAttributes`] are built on top of it, so comparisons are by value rather than by
+//! how the SID string was spelled.
+
+use crate::{AcError, Result};
+
+mod classify;
+pub(crate) mod derive;
+pub mod well_known;
+
+pub(crate) use classify::{
+    BUILTIN_DOMAIN_RID, CAPABILITY_BASE_RID, CAPABILITY_HASHED_RID, NT_AUTHORITY,
+};
+pub use classify::{CapabilityForm, SidClass, classify, classify_sddl};
+
+/// AppContainer SID prefix: revision 1, identifier authority 15 (App Package),
+/// sub-authority 2 (AppContainer).
+const AC_SID_PREFIX: &str = "S-1-15-2-";
+
+/// Identifier authority of app package SIDs (`SECURITY_APP_PACKAGE_AUTHORITY`).
+pub(crate) const APP_PACKAGE_AUTHORITY: u64 = 15;
+/// First sub-authority of AppContainer package SIDs (`SECURITY_APP_PACKAGE_BASE_RID`).
+pub(crate) const APP_PACKAGE_BASE_RID: u32 = 2;
+
+/// A security identifier (MS-DTYP `SID`).
+///
+/// Ordering and hashing follow the SID value: revision, identifier authority, then
+/// sub-authorities. `S-1-5-32-544`, `s-1-5-32-544` and `S-1-0x5-32-544` all parse to the
+/// same value and format back as `S-1-5-32-544`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Sid {
+    revision: u8,
+    authority: u64,
+    sub_authorities: Vec<u32>,
+}
+
+impl Sid {
+    /// The only SID revision Windows defines (`SID_REVISION`).
+    pub const REVISION: u8 = 1;
+    /// Maximum number of sub-authorities in a SID (`SID_MAX_SUB_AUTHORITIES`).
+    pub const MAX_SUB_AUTHORITIES: usize = 15;
+    /// Largest identifier authority representable in the 48-bit field.
+    pub const MAX_AUTHORITY: u64 = (1 << 48) - 1;
+
+    /// Creates a revision-1 SID from an identifier authority and sub-authorities.
+    pub fn new(authority: u64, sub_authorities: &[u32]) -> Result<Self> {
+        if authority > Self::MAX_AUTHORITY {
+            return Err(AcError::InvalidSid(format!(
+                "identifier authority {authority} exceeds 48 bits"
+            )));
+        }
+        if sub_authorities.len() > Self::MAX_SUB_AUTHORITIES {
+            return Err(AcError::InvalidSid(format!(
+                "{} sub-authorities exceed the maximum of {}",
+                sub_authorities.len(),
+                Self::MAX_SUB_AUTHORITIES
+            )));
+        }
+        Ok(Self::from_parts(authority, sub_authorities.to_vec()))
+    }
+
+    /// Crate-internal constructor for values that are valid by construction.
+    pub(crate) fn from_parts(authority: u64, sub_authorities: Vec<u32>) -> Self {
+        debug_assert!(authority <= Self::MAX_AUTHORITY);
+        debug_assert!(sub_authorities.len() <= Self::MAX_SUB_AUTHORITIES);
+        Self {
+            revision: Self::REVISION,
+            authority,
+            sub_authorities,
+        }
+    }
+
+    /// Parses an SDDL SID string such as `S-1-5-32-544`.
+    ///
+    /// The `S` prefix is case-insensitive, and the identifier authority and sub-authorities
+    /// may be written in decimal or as `0x`-prefixed hexadecimal.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut parts = s.split('-');
+        if !parts.next().is_some_and(|p| p.eq_ignore_ascii_case("S")) {
+            return Err(AcError::InvalidSid(format!(
+                "expected 'S-' prefix in '{s}'"
+            )));
+        }
+        let revision = match parts.next().map(parse_number) {
+            Some(Some(1)) => Self::REVISION,
+            _ => {
+                return Err(AcError::InvalidSid(format!(
+                    "unsupported or missing revision in '{s}'"
+                )));
+            }
+        };
+        let authority = match parts.next().map(parse_number) {
+            Some(Some(value)) if value <= Self::MAX_AUTHORITY => value,
+            _ => {
+                return Err(AcError::InvalidSid(format!(
+                    "invalid identifier authority in '{s}'"
+                )));
+            }
+        };
+        let mut sub_authorities = Vec::new();
+        for part in parts {
+            if part.is_empty() {
+                return Err(AcError::InvalidSid(format!(
+                    "missing sub-authority value in '{s}'"
+                )));
+            }
+            let value = parse_number(part)
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| {
+                    AcError::InvalidSid(format!(
+                        "invalid sub-authority component '{part}' in '{s}'"
+                    ))
+                })?;
+            sub_authorities.push(value);
+        }
+        if sub_authorities.len() > Self::MAX_SUB_AUTHORITIES {
+            return Err(AcError::InvalidSid(format!(
+                "too many sub-authorities in '{s}'"
+            )));
+        }
+        Ok(Self {
+            revision,
+            authority,
+            sub_authorities,
+        })
+    }
+
+    /// Decodes the binary MS-DTYP `SID` layout.
+    ///
+    /// `bytes` must contain exactly one SID (see [`Sid::byte_len`]).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (sid, used) = Self::decode_prefix(bytes)?;
+        if used != bytes.len() {
+            return Err(AcError::InvalidSid(format!(
+                "{} trailing bytes after binary SID",
+                bytes.len() - used
+            )));
+        }
+        Ok(sid)
+    }
+
+    /// Decodes a binary SID from the start of `bytes`, returning it with its length.
+    pub(crate) fn decode_prefix(bytes: &[u8]) -> Result<(Self, usize)> {
+        if bytes.len() < 8 {
+            return Err(AcError::InvalidSid(format!(
+                "binary SID header needs 8 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        if bytes[0] != Self::REVISION {
+            return Err(AcError::InvalidSid(format!(
+                "unsupported binary SID revision {}",
+                bytes[0]
+            )));
+        }
+        let count = usize::from(bytes[1]);
+        if count > Self::MAX_SUB_AUTHORITIES {
+            return Err(AcError::InvalidSid(format!(
+                "binary SID declares {count} sub-authorities"
+            )));
+        }
+        let needed = 8 + 4 * count;
+        if bytes.len() < needed {
+            return Err(AcError::InvalidSid(format!(
+                "binary SID needs {needed} bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let authority = bytes[2..8]
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+        let sub_authorities = bytes[8..needed]
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        Ok((
+            Self {
+                revision: Self::REVISION,
+                authority,
+                sub_authorities,
+            },
+            needed,
+        ))
+    }
+
+    /// Encodes the SID in the binary MS-DTYP `SID` layout.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.byte_len());
+        out.push(self.revision);
+        out.push(self.sub_authorities.len() as u8);
+        out.extend_from_slice(&self.authority.to_be_bytes()[2..]);
+        for sub in &self.sub_authorities {
+            out.extend_from_slice(&sub.to_le_bytes());
+        }
+        out
+    }
+
+    /// Size of the binary encoding in bytes (`GetLengthSid`).
+    pub fn byte_len(&self) -> usize {
+        8 + 4 * self.sub_authorities.len()
+    }
+
+    /// SID revision (always [`Sid::REVISION`]).
+    pub fn revision(&self) -> u8 {
+        self.revision
+    }
+
+    /// The 48-bit identifier authority.
+    pub fn identifier_authority(&self) -> u64 {
+        self.authority
+    }
+
+    /// The sub-authority values in order.
+    pub fn sub_authorities(&self) -> &[u32] {
+        &self.sub_authorities
+    }
+
+    /// The relative identifier (last sub-authority), if any.
+    pub fn rid(&self) -> Option<u32> {
+        self.sub_authorities.last().copied()
+    }
+}
+
+fn parse_number(part: &str) -> Option<u64> {
+    match part.strip_prefix("0x").or_else(|| part.strip_prefix("0X")) {
+        Some(hex) if !hex.is_empty() => u64::from_str_radix(hex, 16).ok(),
+        Some(_) => None,
+        None if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) => part.parse().ok(),
+        None => None,
+    }
+}
+
+impl std::fmt::Display for Sid {
+    /// Formats the SID like `ConvertSidToStringSidW`: authorities that do not fit in 32 bits
+    /// are written as 12-digit hexadecimal.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "S-{}-", self.revision)?;
+        if self.authority > u64::from(u32::MAX) {
+            write!(f, "0x{:012X}", self.authority)?;
+        } else {
+            write!(f, "{}", self.authority)?;
+        }
+        for sub in &self.sub_authorities {
+            write!(f, "-{sub}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Sid {
+    type Err = AcError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Sid {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Sid {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", from = "String"))]
+pub struct AppContainerSid {
+    // Canonical SDDL when `sid` parsed, otherwise the caller's string verbatim. `sid` is a
+    // function of `sddl`, so the derived comparisons are by value for parsed SIDs.
+    sddl: String,
+    sid: Option<Sid>,
+}
+
+impl AppContainerSid {
+    /// Create from an SDDL string without validation.
+    ///
+    /// Parseable strings are stored in canonical form; anything else is kept verbatim for
+    /// backwards compatibility.
+    pub fn from_sddl(s: impl Into<String>) -> Self {
+        let sddl: String = s.into();
+        match Sid::parse(&sddl) {
+            Ok(sid) => Self::from_sid_unchecked(sid),
+            Err(_) => Self { sddl, sid: None },
+        }
+    }
+
+    /// Wraps a SID that is known to be an AppContainer SID.
+    pub(crate) fn from_sid_unchecked(sid: Sid) -> Self {
+        Self {
+            sddl: sid.to_string(),
+            sid: Some(sid),
+        }
+    }
+
+    /// Create from an SDDL string, validating that it looks like a well-formed
+    /// AppContainer SID (`S-1-15-2-<sub-authorities>`).
+    ///
+    /// Accepted shapes are package SIDs (seven hash sub-authorities), child SIDs (eleven) and
+    /// the AppContainer-wide groups `S-1-15-2-1`/`S-1-15-2-2`.
+    ///
+    /// Returns `Err(AcError::InvalidSid)` if the string does not start with
+    /// the AppContainer SID prefix, contains non-numeric sub-authority
+    /// components, or has any other sub-authority count.
+    pub fn try_from_sddl(s: impl Into<String>) -> Result<Self> {
+        let sddl: String = s.into();
+        Self::try_from(Sid::parse(&sddl)?)
+    }
+
+    /// Returns the SDDL string representation of the SID.
+    pub fn as_string(&self) -> &str {
+        &self.sddl
+    }
+
+    /// Returns the parsed SID, or `None` if this value was created from an unparseable string.
+    pub fn as_sid(&self) -> Option<&Sid> {
+        self.sid.as_ref()
+    }
+
+    /// Whether this is a child AppContainer SID (`parent/child` name).
+    pub fn is_child(&self) -> bool {
+        self.sid
+            .as_ref()
+            .is_some_and(|sid| has_app_container_shape(sid, CHILD_SUB_AUTHORITIES))
+    }
+
+    /// The parent package SID of a child AppContainer, or `None` for anything else.
+    pub fn parent(&self) -> Option<AppContainerSid> {
+        let sid = self.sid.as_ref().filter(|_| self.is_child())?;
+        Some(Self::from_sid_unchecked(Sid::from_parts(
+            sid.identifier_authority(),
+            sid.sub_authorities()[..PACKAGE_SUB_AUTHORITIES].to_vec(),
+        )))
+    }
+
+    /// Derives the SID of the child AppContainer `child_name` under this package.
+    ///
+    /// Intended to agree with
+    /// `DeriveRestrictedAppContainerSidFromAppContainerSidAndRestrictedName` (checked against
+    /// userenv by a Windows-only test); `self` must be a package SID, since children cannot be
+    /// nested.
+    pub fn child(&self, child_name: &str) -> Result<AppContainerSid> {
+        validate_name_component(child_name, child_name)?;
+        let Some(sid) = self
+            .sid
+            .as_ref()
+            .filter(|sid| has_app_container_shape(sid, PACKAGE_SUB_AUTHORITIES))
+        else {
+            return Err(AcError::InvalidSid(format!(
+                "child AppContainers require a package SID parent, got '{}'",
+                self.sddl
+            )));
+        };
+        let mut subs = sid.sub_authorities().to_vec();
+        subs.extend_from_slice(&derive::child_app_container_rids(child_name));
+        Ok(Self::from_sid_unchecked(Sid::from_parts(
+            sid.identifier_authority(),
+            subs,
+        )))
+    }
+}
+
+/// Sub-authority count of a package SID: base RID plus the name hash.
+pub(crate) const PACKAGE_SUB_AUTHORITIES: usize = 1 + derive::APP_CONTAINER_HASH_RIDS;
+/// Sub-authority count of a child AppContainer SID: the parent's plus the child name hash.
+pub(crate) const CHILD_SUB_AUTHORITIES: usize = PACKAGE_SUB_AUTHORITIES + derive::CHILD_HASH_RIDS;
+
+/// Separator between the parent and child parts of a child AppContainer name.
+pub const CHILD_NAME_SEPARATOR: char = '/';
+
+fn has_app_container_shape(sid: &Sid, sub_authorities: usize) -> bool {
+    sid.identifier_authority() == APP_PACKAGE_AUTHORITY
+        && sid.sub_authorities().len() == sub_authorities
+        && sid.sub_authorities()[0] == APP_PACKAGE_BASE_RID
+}
+
+/// Splits an AppContainer name into its parent and optional child part.
+///
+/// Only one level of nesting exists, so `a/b/c` is rejected, as are empty parts.
+pub(crate) fn split_container_name(name: &str) -> Result<(&str, Option<&str>)> {
+    let (parent, child) = match name.split_once(CHILD_NAME_SEPARATOR) {
+        Some((parent, child)) => (parent, Some(child)),
+        None => (name, None),
+    };
+    validate_name_component(name, parent)?;
+    if let Some(child) = child {
+        validate_name_component(name, child)?;
+    }
+    Ok((parent, child))
+}
+
+fn validate_name_component(name: &str, component: &str) -> Result<()> {
+    let reason = if component.is_empty() {
+        "AppContainer name must not be empty"
+    } else if component.contains(CHILD_NAME_SEPARATOR) {
+        "child AppContainers cannot be nested more than one level"
+    } else {
+        return Ok(());
+    };
+    Err(AcError::InvalidName {
+        name: name.to_string(),
+        reason,
+    })
+}
+
+impl TryFrom<Sid> for AppContainerSid {
+    type Error = AcError;
+
+    fn try_from(sid: Sid) -> Result<Self> {
+        let subs = sid.sub_authorities();
+        if sid.identifier_authority() != APP_PACKAGE_AUTHORITY
+            || subs.first() != Some(&APP_PACKAGE_BASE_RID)
+        {
+            return Err(AcError::InvalidSid(format!(
+                "expected prefix '{AC_SID_PREFIX}', got '{sid}'"
+            )));
+        }
+        if subs.len() < 2 {
+            return Err(AcError::InvalidSid(
+                "missing sub-authority values after prefix".into(),
+            ));
+        }
+        if !matches!(
+            subs.len(),
+            2 | PACKAGE_SUB_AUTHORITIES | CHILD_SUB_AUTHORITIES
+        ) {
+            return Err(AcError::InvalidSid(format!(
+                "unexpected sub-authority count {} in AppContainer SID '{sid}'",
+                subs.len()
+            )));
+        }
+        Ok(Self::from_sid_unchecked(sid))
+    }
+}
+
+impl From<AppContainerSid> for String {
+    fn from(sid: AppContainerSid) -> Self {
+        sid.sddl
+    }
+}
+
+impl From<String> for AppContainerSid {
+    fn from(s: String) -> Self {
+        Self::from_sddl(s)
+    }
+}
+
+impl std::fmt::Display for AppContainerSid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.sddl)
+    }
+}
+
+impl AsRef<str> for AppContainerSid {
+    fn as_ref(&self) -> &str {
+        self.as_string()
+    }
+}
+
+/// A capability SID paired with its Win32 attribute flags.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SidAndAttributes {
+    /// The capability SID.
+    pub sid: Sid,
+    /// Win32 `SID_AND_ATTRIBUTES.Attributes` flags (typically `SE_GROUP_ENABLED`).
+    pub attributes: u32,
+}
+
+impl SidAndAttributes {
+    /// No flags: the capability is present but disabled.
+    pub const DISABLED: u32 = 0;
+    /// `SE_GROUP_MANDATORY`.
+    pub const MANDATORY: u32 = 0x0000_0001;
+    /// `SE_GROUP_ENABLED_BY_DEFAULT`.
+    pub const ENABLED_BY_DEFAULT: u32 = 0x0000_0002;
+    /// `SE_GROUP_ENABLED`, the attributes builders assign by default.
+    pub const ENABLED: u32 = 0x0000_0004;
+    /// `SE_GROUP_USE_FOR_DENY_ONLY`: the SID only matches deny ACEs.
+    pub const USE_FOR_DENY_ONLY: u32 = 0x0000_0010;
+
+    /// Pairs `sid` with `attributes`.
+    pub fn new(sid: Sid, attributes: u32) -> Self {
+        Self { sid, attributes }
+    }
+
+    /// SDDL string representation of the SID.
+    pub fn sid_sddl(&self) -> String {
+        self.sid.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_from_sddl_accepts_valid_sid() {
+        let sid = AppContainerSid::try_from_sddl("S-1-15-2-1").unwrap();
+        assert_eq!(sid.as_string(), "S-1-15-2-1");
+    }
+
+    #[test]
+    fn try_from_sddl_accepts_multi_component_sid() {
+        let s =
+            "S-1-15-2-1430448594-2639229838-973813799-439329657-1197984847-4069167804-277127516";
+        let sid = AppContainerSid::try_from_sddl(s).unwrap();
+        assert_eq!(sid.as_string(), s);
+    }
+
+    #[test]
+    fn try_from_sddl_rejects_wrong_prefix() {
+        let err = AppContainerSid::try_from_sddl("S-1-5-21-123").unwrap_err();
+        assert!(err.to_string().contains("expected prefix"));
+    }
+
+    #[test]
+    fn try_from_sddl_rejects_empty_tail() {
+        let err = AppContainerSid::try_from_sddl("S-1-15-2-").unwrap_err();
+        assert!(err.to_string().contains("missing sub-authority"));
+    }
+
+    #[test]
+    fn try_from_sddl_rejects_non_numeric_component() {
+        let err = AppContainerSid::try_from_sddl("S-1-15-2-abc").unwrap_err();
+        assert!(err.to_string().contains("invalid sub-authority"));
+    }
+
+    #[test]
+    fn try_from_sddl_rejects_garbage() {
+        assert!(AppContainerSid::try_from_sddl("not-a-sid").is_err());
+    }
+
+    #[test]
+    fn from_sddl_still_accepts_anything() {
+        // Unchecked constructor remains for backwards compatibility
+        let sid = AppContainerSid::from_sddl("anything");
+        assert_eq!(sid.as_string(), "anything");
+    }
+
+    #[test]
+    fn app_container_sid_from_sddl_and_as_string() {
+        let sid = AppContainerSid::from_sddl("S-1-15-2-1-2-3-4-5-6");
+        assert_eq!(sid.as_string(), "S-1-15-2-1-2-3-4-5-6");
+    }
+
+    #[test]
+    fn app_container_sid_display() {
+        let sid = AppContainerSid::from_sddl("S-1-15-2-0");
+        assert_eq!(sid.to_string(), "S-1-15-2-0");
+    }
+
+    #[test]
+    fn app_container_sid_as_ref() {
+        let sid = AppContainerSid::from_sddl("S-1-15-2-0");
+        let s: &str = sid.as_ref();
+        assert_eq!(s, "S-1-15-2-0");
+    }
+
+    #[test]
+    fn sid_and_attributes_struct() {
+        let sa = SidAndAttributes {
+            sid: Sid::parse("S-1-15-2-0").unwrap(),
+            attributes: 0,
+        };
+        assert_eq!(sa.sid_sddl(), "S-1-15-2-0");
+        assert_eq!(sa.attributes, 0);
+    }
+
+    #[test]
+    fn sid_parses_and_formats_canonically() {
+        let sid = Sid::parse("s-1-0x5-32-0x220").unwrap();
+        assert_eq!(sid.identifier_authority(), 5);
+        assert_eq!(sid.sub_authorities(), &[32, 544]);
+        assert_eq!(sid.rid(), Some(544));
+        assert_eq!(sid.to_string(), "S-1-5-32-544");
+        assert_eq!("S-1-5-32-544".parse::<Sid>().unwrap(), sid);
+    }
+
+    #[test]
+    fn sid_formats_wide_authority_as_hex() {
+        let sid = Sid::new(0x1234_5678_9ABC, &[7]).unwrap();
+        assert_eq!(sid.to_string(), "S-1-0x123456789ABC-7");
+        assert_eq!(Sid::parse("S-1-0x123456789abc-7").unwrap(), sid);
+        assert_eq!(Sid::parse("S-1-20015998343868-7").unwrap(), sid);
+    }
+
+    #[test]
+    fn sid_rejects_malformed_strings() {
+        for bad in [
+            "",
+            "X-1-5",
+            "S-2-5-18",
+            "S-1",
+            "S-1-0x1000000000000",
+            "S-1-5-",
+            "S-1-5-4294967296",
+            "S-1-5-+1",
+            "S-1-5-0x",
+            "S-1-5-1-2-3-4-5-6-7-8-9-10-11-12-13-14-15-16",
+        ] {
+            assert!(Sid::parse(bad).is_err(), "accepted {bad:?}");
+        }
+    }
+
+    #[test]
+    fn sid_binary_roundtrip_matches_ms_dtyp_layout() {
+        let sid = Sid::parse("S-1-5-32-544").unwrap();
+        let bytes = sid.to_bytes();
+        assert_eq!(
+            bytes,
+            vec![1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 0x20, 0x02, 0, 0]
+        );
+        assert_eq!(sid.byte_len(), bytes.len());
+        assert_eq!(Sid::from_bytes(&bytes).unwrap(), sid);
+    }
+
+    #[test]
+    fn sid_from_bytes_rejects_truncated_or_trailing_data() {
+        let bytes = Sid::parse("S-1-5-18").unwrap().to_bytes();
+        assert!(Sid::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(Sid::from_bytes(&extra).is_err());
+        let mut bad_revision = bytes;
+        bad_revision[0] = 2;
+        assert!(Sid::from_bytes(&bad_revision).is_err());
+    }
+
+    #[test]
+    fn sid_ordering_and_hash_follow_value() {
+        use std::collections::{BTreeSet, HashSet};
+        let a = Sid::parse("S-1-5-32-544").unwrap();
+        let b = Sid::parse("S-1-0x5-32-544").unwrap();
+        let c = Sid::parse("S-1-5-32-545").unwrap();
+        assert!(a < c);
+        assert_eq!(BTreeSet::from([a.clone(), b.clone(), c.clone()]).len(), 2);
+        assert_eq!(HashSet::from([a, b, c]).len(), 2);
+    }
+
+    #[test]
+    fn app_container_sid_equality_ignores_spelling() {
+        let a = AppContainerSid::from_sddl("S-1-15-2-1-2");
+        let b = AppContainerSid::from_sddl("s-1-0xF-2-1-0x2");
+        assert_eq!(a, b);
+        assert_eq!(b.as_string(), "S-1-15-2-1-2");
+        assert_eq!(b.as_sid(), Some(&Sid::parse("S-1-15-2-1-2").unwrap()));
+    }
+
+    #[test]
+    fn app_container_sid_try_from_sid_checks_prefix() {
+        assert!(AppContainerSid::try_from(Sid::parse("S-1-15-2-5").unwrap()).is_ok());
+        assert!(AppContainerSid::try_from(Sid::parse("S-1-15-3-5").unwrap()).is_err());
+        assert!(AppContainerSid::try_from(Sid::parse("S-1-15-2").unwrap()).is_err());
+    }
+
+    const PARENT: &str =
+        "S-1-15-2-1430448594-2639229838-973813799-439329657-1197984847-4069167804-277127516";
+
+    #[test]
+    fn try_from_sddl_rejects_unexpected_sub_authority_counts() {
+        let err = AppContainerSid::try_from_sddl("S-1-15-2-1-2-3").unwrap_err();
+        assert!(err.to_string().contains("sub-authority count"));
+        assert!(AppContainerSid::try_from_sddl(format!("{PARENT}-1-2-3-4-5")).is_err());
+    }
+
+    #[test]
+    fn child_sid_extends_parent_by_four_sub_authorities() {
+        let parent = AppContainerSid::try_from_sddl(PARENT).unwrap();
+        let child = parent.child("plugin.one").unwrap();
+        let subs = child.as_sid().unwrap().sub_authorities();
+        assert_eq!(subs.len(), CHILD_SUB_AUTHORITIES);
+        assert!(child.as_string().starts_with(PARENT));
+        assert!(child.is_child());
+        assert!(!parent.is_child());
+        assert_eq!(child.parent(), Some(parent.clone()));
+        assert_eq!(parent.parent(), None);
+        assert_eq!(child, parent.child("PLUGIN.ONE").unwrap());
+        assert_eq!(
+            AppContainerSid::try_from_sddl(child.as_string()).unwrap(),
+            child
+        );
+    }
+
+    #[test]
+    fn children_cannot_be_nested_or_unnamed() {
+        let parent = AppContainerSid::try_from_sddl(PARENT).unwrap();
+        let child = parent.child("a").unwrap();
+        assert!(matches!(child.child("b"), Err(AcError::InvalidSid(_))));
+        assert!(matches!(parent.child(""), Err(AcError::InvalidName { .. })));
+        assert!(matches!(
+            parent.child("a/b"),
+            Err(AcError::InvalidName { .. })
+        ));
+        assert!(AppContainerSid::from_sddl("garbage").child("a").is_err());
+    }
+
+    #[test]
+    fn split_container_name_handles_one_level() {
+        assert_eq!(split_container_name("app").unwrap(), ("app", None));
+        assert_eq!(
+            split_container_name("app/plugin").unwrap(),
+            ("app", Some("plugin"))
+        );
+        for bad in ["", "/plugin", "app/", "app/a/b"] {
+            assert!(
+                matches!(split_container_name(bad), Err(AcError::InvalidName { .. })),
+                "{bad:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Well-known SIDs and their SDDL aliases.
+//!
+//! The table mirrors the SID strings accepted by `ConvertStringSidToSidW` (`AC`, `WD`, `BA`,
+//! `SY`, the integrity labels `LW`/`ME`/`HI`/`SI`, ...) plus well-known accounts that have no
+//! alias, such as `ALL RESTRICTED APPLICATION PACKAGES` (`S-1-15-2-2`). Lookups work in both
+//! directions and need no Windows APIs, so policies and ACL dumps can be read anywhere.
+//!
+//! Domain-relative aliases (`DA`, `DU`, `LA`, ...) only name a RID; resolve them against a
+//! domain SID with [`resolve_in_domain`].
+
+use super::Sid;
+use crate::{AcError, Result};
+
+/// Broad grouping of a well-known SID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum WellKnownCategory {
+    /// Null, World, Local and Creator authorities (`S-1-0` through `S-1-3`).
+    Universal,
+    /// `NT AUTHORITY` principals (`S-1-5-x`) other than builtin groups.
+    NtAuthority,
+    /// `BUILTIN` local groups (`S-1-5-32-x`).
+    Builtin,
+    /// `APPLICATION PACKAGE AUTHORITY` groups (`S-1-15-2-1`, `S-1-15-2-2`).
+    AppPackage,
+    /// Mandatory integrity labels (`S-1-16-x`).
+    IntegrityLevel,
+    /// Authentication assertion identities (`S-1-18-x`).
+    AuthenticationAssertion,
+    /// Accounts relative to a domain SID (`S-1-5-21-<domain>-<rid>`).
+    DomainRelative,
+}
+
+/// One entry of the well-known SID table.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WellKnownSid {
+    alias: Option<&'static str>,
+    domain: &'static str,
+    name: &'static str,
+    authority: u64,
+    // For `DomainRelative` entries this holds only the RID.
+    sub_authorities: &'static [u32],
+    category: WellKnownCategory,
+}
+
+impl WellKnownSid {
+    /// Two-letter SDDL alias, if the SID has one.
+    pub fn alias(&self) -> Option<&'static str> {
+        self.alias
+    }
+
+    /// Account name as reported by `LookupAccountSidW` on an English system.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Account domain (`NT AUTHORITY`, `BUILTIN`, ...); empty for World, Creator and
+    /// domain-relative accounts.
+    pub fn domain(&self) -> &'static str {
+        self.domain
+    }
+
+    /// `DOMAIN\name`, or just the name when the domain is empty.
+    pub fn account_name(&self) -> String {
+        if self.domain.is_empty() {
+            self.name.to_string()
+        } else {
+            format!("{}\\{}", self.domain, self.name)
+        }
+    }
+
+    /// Authority grouping of the entry (builtin group, integrity label, ...).
+    pub fn category(&self) -> WellKnownCategory {
+        self.category
+    }
+
+    /// The SID, or `None` for domain-relative entries (see [`WellKnownSid::sid_in_domain`]).
+    pub fn sid(&self) -> Option<Sid> {
+        (self.category != WellKnownCategory::DomainRelative)
+            .then(|| Sid::from_parts(self.authority, self.sub_authorities.to_vec()))
+    }
+
+    /// RID of a domain-relative entry.
+    pub fn domain_rid(&self) -> Option<u32> {
+        (self.category == WellKnownCategory::DomainRelative).then(|| self.sub_authorities[0])
+    }
+
+    /// The SID within `domain` (`S-1-5-21-a-b-c`). Absolute entries ignore `domain`.
+    pub fn sid_in_domain(&self, domain: &Sid) -> Result<Sid> {
+        let Some(rid) = self.domain_rid() else {
+            return Ok(Sid::from_parts(
+                self.authority,
+                self.sub_authorities.to_vec(),
+            ));
+        };
+        if !is_domain_sid(domain) {
+            return Err(AcError::InvalidSid(format!(
+                "expected a domain SID (S-1-5-21-a-b-c), got '{domain}'"
+            )));
+        }
+        let mut subs = domain.sub_authorities().to_vec();
+        subs.push(rid);
+        Ok(Sid::from_parts(domain.identifier_authority(), subs))
+    }
+}
+
+macro_rules! well_known {
+    ($($alias:expr, $domain:expr, $name:expr, $authority:expr, [$($sub:expr),*], $category:ident;)*) => {
+        &[$(WellKnownSid {
+            alias: $alias,
+            domain: $domain,
+            name: $name,
+            authority: $authority,
+            sub_authorities: &[$($sub),*],
+            category: WellKnownCategory::$category,
+        }),*]
+    };
+}
+
+const NT: &str = "NT AUTHORITY";
+const BUILTIN: &str = "BUILTIN";
+const APP: &str = "APPLICATION PACKAGE AUTHORITY";
+const LABEL: &str = "Mandatory Label";
+
+static TABLE: &[WellKnownSid] = well_known![
+    None, "", "NULL SID", 0, [0], Universal;
+    Some("WD"), "", "Everyone", 1, [0], Universal;
+    None, "", "LOCAL", 2, [0], Universal;
+    None, "", "CONSOLE LOGON", 2, [1], Universal;
+    Some("CO"), "", "CREATOR OWNER", 3, [0], Universal;
+    Some("CG"), "", "CREATOR GROUP", 3, [1], Universal;
+    None, "", "CREATOR OWNER SERVER", 3, [2], Universal;
+    None, "", "CREATOR GROUP SERVER", 3, [3], Universal;
+    Some("OW"), "", "OWNER RIGHTS", 3, [4], Universal;
+    None, NT, "DIALUP", 5, [1], NtAuthority;
+    Some("NU"), NT, "NETWORK", 5, [2], NtAuthority;
+    None, NT, "BATCH", 5, [3], NtAuthority;
+    Some("IU"), NT, "INTERACTIVE", 5, [4], NtAuthority;
+    Some("SU"), NT, "SERVICE", 5, [6], NtAuthority;
+    Some("AN"), NT, "ANONYMOUS LOGON", 5, [7], NtAuthority;
+    None, NT, "PROXY", 5, [8], NtAuthority;
+    Some("ED"), NT, "ENTERPRISE DOMAIN CONTROLLERS", 5, [9], NtAuthority;
+    Some("PS"), NT, "SELF", 5, [10], NtAuthority;
+    Some("AU"), NT, "Authenticated Users", 5, [11], NtAuthority;
+    Some("RC"), NT, "RESTRICTED", 5, [12], NtAuthority;
+    None, NT, "TERMINAL SERVER USER", 5, [13], NtAuthority;
+    None, NT, "REMOTE INTERACTIVE LOGON", 5, [14], NtAuthority;
+    None, NT, "This Organization", 5, [15], NtAuthority;
+    None, NT, "IUSR", 5, [17], NtAuthority;
+    Some("SY"), NT, "SYSTEM", 5, [18], NtAuthority;
+    Some("LS"), NT, "LOCAL SERVICE", 5, [19], NtAuthority;
+    Some("NS"), NT, "NETWORK SERVICE", 5, [20], NtAuthority;
+    Some("WR"), NT, "WRITE RESTRICTED", 5, [33], NtAuthority;
+    None, NT, "NTLM Authentication", 5, [64, 10], NtAuthority;
+    None, NT, "SChannel Authentication", 5, [64, 14], NtAuthority;
+    None, NT, "Digest Authentication", 5, [64, 21], NtAuthority;
+    Some("UD"), NT, "USER MODE DRIVERS", 5, [84, 0, 0, 0, 0, 0], NtAuthority;
+    None, NT, "Local account", 5, [113], NtAuthority;
+    None, NT, "Local account and member of Administrators group", 5, [114], NtAuthority;
+    None, NT, "Other Organization", 5, [1000], NtAuthority;
+    Some("BA"), BUILTIN, "Administrators", 5, [32, 544], Builtin;
+    Some("BU"), BUILTIN, "Users", 5, [32, 545], Builtin;
+    Some("BG"), BUILTIN, "Guests", 5, [32, 546], Builtin;
+    Some("PU"), BUILTIN, "Power Users", 5, [32, 547], Builtin;
+    Some("AO"), BUILTIN, "Account Operators", 5, [32, 548], Builtin;
+    Some("SO"), BUILTIN, "Server Operators", 5, [32, 549], Builtin;
+    Some("PO"), BUILTIN, "Print Operators", 5, [32, 550], Builtin;
+    Some("BO"), BUILTIN, "Backup Operators", 5, [32, 551], Builtin;
+    Some("RE"), BUILTIN, "Replicator", 5, [32, 552], Builtin;
+    Some("RU"), BUILTIN, "Pre-Windows 2000 Compatible Access", 5, [32, 554], Builtin;
+    Some("RD"), BUILTIN, "Remote Desktop Users", 5, [32, 555], Builtin;
+    Some("NO"), BUILTIN, "Network Configuration Operators", 5, [32, 556], Builtin;
+    None, BUILTIN, "Incoming Forest Trust Builders", 5, [32, 557], Builtin;
+    Some("MU"), BUILTIN, "Performance Monitor Users", 5, [32, 558], Builtin;
+    Some("LU"), BUILTIN, "Performance Log Users", 5, [32, 559], Builtin;
+    None, BUILTIN, "Windows Authorization Access Group", 5, [32, 560], Builtin;
+    None, BUILTIN, "Terminal Server License Servers", 5, [32, 561], Builtin;
+    None, BUILTIN, "Distributed COM Users", 5, [32, 562], Builtin;
+    Some("IS"), BUILTIN, "IIS_IUSRS", 5, [32, 568], Builtin;
+    Some("CY"), BUILTIN, "Cryptographic Operators", 5, [32, 569], Builtin;
+    Some("ER"), BUILTIN, "Event Log Readers", 5, [32, 573], Builtin;
+    Some("CD"), BUILTIN, "Certificate Service DCOM Access", 5, [32, 574], Builtin;
+    Some("RA"), BUILTIN, "RDS Remote Access Servers", 5, [32, 575], Builtin;
+    Some("ES"), BUILTIN, "RDS Endpoint Servers", 5, [32, 576], Builtin;
+    Some("MS"), BUILTIN, "RDS Management Servers", 5, [32, 577], Builtin;
+    Some("HA"), BUILTIN, "Hyper-V Administrators", 5, [32, 578], Builtin;
+    Some("AA"), BUILTIN, "Access Control Assistance Operators", 5, [32, 579], Builtin;
+    Some("RM"), BUILTIN, "Remote Management Users", 5, [32, 580], Builtin;
+    None, BUILTIN, "Storage Replica Administrators", 5, [32, 582], Builtin;
+    None, BUILTIN, "Device Owners", 5, [32, 583], Builtin;
+    Some("AC"), APP, "ALL APPLICATION PACKAGES", 15, [2, 1], AppPackage;
+    None, APP, "ALL RESTRICTED APPLICATION PACKAGES", 15, [2, 2], AppPackage;
+    None, LABEL, "Untrusted Mandatory Level", 16, [0], IntegrityLevel;
+    Some("LW"), LABEL, "Low Mandatory Level", 16, [4096], IntegrityLevel;
+    Some("ME"), LABEL, "Medium Mandatory Level", 16, [8192], IntegrityLevel;
+    Some("MP"), LABEL, "Medium Plus Mandatory Level", 16, [8448], IntegrityLevel;
+    Some("HI"), LABEL, "High Mandatory Level", 16, [12288], IntegrityLevel;
+    Some("SI"), LABEL, "System Mandatory Level", 16, [16384], IntegrityLevel;
+    None, LABEL, "Protected Process Mandatory Level", 16, [20480], IntegrityLevel;
+    Some("AS"), "", "Authentication authority asserted identity", 18, [1], AuthenticationAssertion;
+    Some("SS"), "", "Service asserted identity", 18, [2], AuthenticationAssertion;
+    Some("RO"), "", "Enterprise Read-only Domain Controllers", 5, [498], DomainRelative;
+    Some("LA"), "", "Administrator", 5, [500], DomainRelative;
+    Some("LG"), "", "Guest", 5, [501], DomainRelative;
+    Some("DA"), "", "Domain Admins", 5, [512], DomainRelative;
+    Some("DU"), "", "Domain Users", 5, [513], DomainRelative;
+    Some("DG"), "", "Domain Guests", 5, [514], DomainRelative;
+    Some("DC"), "", "Domain Computers", 5, [515], DomainRelative;
+    Some("DD"), "", "Domain Controllers", 5, [516], DomainRelative;
+    Some("CA"), "", "Cert Publishers", 5, [517], DomainRelative;
+    Some("SA"), "", "Schema Admins", 5, [518], DomainRelative;
+    Some("EA"), "", "Enterprise Admins", 5, [519], DomainRelative;
+    Some("PA"), "", "Group Policy Creator Owners", 5, [520], DomainRelative;
+    Some("CN"), "", "Cloneable Domain Controllers", 5, [522], DomainRelative;
+    Some("AP"), "", "Protected Users", 5, [525], DomainRelative;
+    Some("KA"), "", "Key Admins", 5, [526], DomainRelative;
+    Some("EK"), "", "Enterprise Key Admins", 5, [527], DomainRelative;
+    Some("RS"), "", "RAS and IAS Servers", 5, [553], DomainRelative;
+];
+
+/// The whole table, absolute entries first.
+pub fn all() -> &'static [WellKnownSid] {
+    TABLE
+}
+
+/// Finds an entry by SDDL alias (`"AC"`, `"sy"`, ...). ASCII case-insensitive.
+pub fn from_alias(alias: &str) -> Option<&'static WellKnownSid> {
+    TABLE
+        .iter()
+        .find(|e| e.alias.is_some_and(|a| a.eq_ignore_ascii_case(alias)))
+}
+
+/// Finds an entry by account name, with or without its domain
+/// (`"SYSTEM"`, `"NT AUTHORITY\\SYSTEM"`). Case-insensitive.
+pub fn from_name(name: &str) -> Option<&'static WellKnownSid> {
+    let (domain, account) = match name.rsplit_once('\\') {
+        Some((domain, account)) => (Some(domain), account),
+        None => (None, name),
+    };
+    TABLE.iter().find(|e| {
+        e.name.eq_ignore_ascii_case(account)
+            && domain.is_none_or(|d| e.domain.eq_ignore_ascii_case(d))
+    })
+}
+
+/// Finds an entry by alias or account name.
+pub fn lookup(alias_or_name: &str) -> Option<&'static WellKnownSid> {
+    from_alias(alias_or_name).or_else(|| from_name(alias_or_name))
+}
+
+/// Finds the entry describing `sid`.
+///
+/// Domain account SIDs (`S-1-5-21-a-b-c-<rid>`) match the domain-relative entry for their
+/// RID.
+pub fn from_sid(sid: &Sid) -> Option<&'static WellKnownSid> {
+    if let Some(rid) = domain_account_rid(sid) {
+        return TABLE.iter().find(|e| e.domain_rid() == Some(rid));
+    }
+    TABLE.iter().find(|e| {
+        e.category != WellKnownCategory::DomainRelative
+            && e.authority == sid.identifier_authority()
+            && e.sub_authorities == sid.sub_authorities()
+    })
+}
+
+/// SDDL alias for `sid`, if it has one that is not domain-relative.
+pub fn alias_for(sid: &Sid) -> Option<&'static str> {
+    from_sid(sid)
+        .filter(|e| e.category != WellKnownCategory::DomainRelative)
+        .and_then(WellKnownSid::alias)
+}
+
+/// Formats `sid` the way SDDL writers do: the alias when there is one, otherwise the SID
+/// string.
+pub fn to_sddl_token(sid: &Sid) -> String {
+    alias_for(sid).map_or_else(|| sid.to_string(), str::to_string)
+}
+
+/// Resolves an alias, account name or SID string to a SID.
+///
+/// Domain-relative aliases fail here because the domain is unknown; use
+/// [`resolve_in_domain`] for those.
+pub fn resolve(s: &str) -> Result<Sid> {
+    match lookup(s) {
+        Some(entry) => entry.sid().ok_or_else(|| {
+            AcError::InvalidSid(format!(
+                "'{s}' is relative to a domain; resolve it with resolve_in_domain"
+            ))
+        }),
+        None => Sid::parse(s),
+    }
+}
+
+/// Like [`resolve`], resolving domain-relative aliases against `domain`.
+pub fn resolve_in_domain(s: &str, domain: &Sid) -> Result<Sid> {
+    match lookup(s) {
+        Some(entry) => entry.sid_in_domain(domain),
+        None => Sid::parse(s),
+    }
+}
+
+/// Classifies `sid` structurally, covering SIDs that are not in the table (any
+/// `S-1-5-32-x`, `S-1-16-x` or domain account).
+pub fn category(sid: &Sid) -> Option<WellKnownCategory> {
+    if let Some(entry) = from_sid(sid) {
+        return Some(entry.category);
+    }
+    let subs = sid.sub_authorities();
+    match (sid.identifier_authority(), subs) {
+        (16, [_]) => Some(WellKnownCategory::IntegrityLevel),
+        (5, [32, _]) => Some(WellKnownCategory::Builtin),
+        (18, [_]) => Some(WellKnownCategory::AuthenticationAssertion),
+        _ if domain_account_rid(sid).is_some() => Some(WellKnownCategory::DomainRelative),
+        _ => None,
+    }
+}
+
+/// Whether `sid` is a mandatory integrity label (`S-1-16-<level>`).
+pub fn is_integrity_label(sid: &Sid) -> bool {
+    integrity_level(sid).is_some()
+}
+
+/// The integrity level RID of a mandatory label (`0x1000` for Low, `0x2000` for Medium, ...).
+pub fn integrity_level(sid: &Sid) -> Option<u32> {
+    match (sid.identifier_authority(), sid.sub_authorities()) {
+        (16, [level]) => Some(*level),
+        _ => None,
+    }
+}
+
+/// Whether `sid` is one of the two AppContainer-wide groups (`AC` or
+/// `ALL RESTRICTED APPLICATION PACKAGES`).
+pub fn is_all_app_packages(sid: &Sid) -> bool {
+    matches!(
+        (sid.identifier_authority(), sid.sub_authorities()),
+        (15, [2, 1]) | (15, [2, 2])
+    )
+}
+
+/// Whether `sid` names a domain (`S-1-5-21-a-b-c`).
+pub fn is_domain_sid(sid: &Sid) -> bool {
+    matches!(
+        (sid.identifier_authority(), sid.sub_authorities()),
+        (5, [21, _, _, _])
+    )
+}
+
+fn domain_account_rid(sid: &Sid) -> Option<u32> {
+    match (sid.identifier_authority(), sid.sub_authorities()) {
+        (5, [21, _, _, _, rid]) => Some(*rid),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn sid(s: &str) -> Sid {
+        Sid::parse(s).unwrap()
+    }
+
+    #[test]
+    fn aliases_resolve_to_documented_sids() {
+        for (alias, expected) in [
+            ("AC", "S-1-15-2-1"),
+            ("WD", "S-1-1-0"),
+            ("BA", "S-1-5-32-544"),
+            ("SY", "S-1-5-18"),
+            ("LW", "S-1-16-4096"),
+            ("ME", "S-1-16-8192"),
+            ("HI", "S-1-16-12288"),
+            ("UD", "S-1-5-84-0-0-0-0-0"),
+        ] {
+            assert_eq!(resolve(alias).unwrap(), sid(expected), "{alias}");
+        }
+        assert_eq!(resolve("ac").unwrap(), sid("S-1-15-2-1"));
+    }
+
+    #[test]
+    fn names_resolve_with_or_without_domain() {
+        assert_eq!(
+            resolve("ALL RESTRICTED APPLICATION PACKAGES").unwrap(),
+            sid("S-1-15-2-2")
+        );
+        assert_eq!(
+            resolve("application package authority\\all restricted application packages").unwrap(),
+            sid("S-1-15-2-2")
+        );
+        assert_eq!(resolve("NT AUTHORITY\\SYSTEM").unwrap(), sid("S-1-5-18"));
+        assert!(from_name("BUILTIN\\SYSTEM").is_none());
+    }
+
+    #[test]
+    fn reverse_lookup_round_trips() {
+        let entry = from_sid(&sid("S-1-15-2-2")).unwrap();
+        assert_eq!(
+            entry.account_name(),
+            "APPLICATION PACKAGE AUTHORITY\\ALL RESTRICTED APPLICATION PACKAGES"
+        );
+        assert_eq!(entry.alias(), None);
+        assert_eq!(to_sddl_token(&sid("S-1-5-32-544")), "BA");
+        assert_eq!(to_sddl_token(&sid("S-1-15-2-2")), "S-1-15-2-2");
+        for entry in all().iter().filter(|e| e.sid().is_some()) {
+            assert_eq!(from_sid(&entry.sid().unwrap()), Some(entry));
+        }
+    }
+
+    #[test]
+    fn table_has_unique_aliases_and_sids() {
+        let mut aliases = HashSet::new();
+        let mut sids = HashSet::new();
+        for entry in all() {
+            if let Some(alias) = entry.alias() {
+                assert_eq!(alias.len(), 2, "{alias}");
+                assert!(aliases.insert(alias), "duplicate alias {alias}");
+            }
+            let key = (entry.authority, entry.sub_authorities, entry.category);
+            assert!(sids.insert(key), "duplicate SID for {}", entry.name());
+        }
+    }
+
+    #[test]
+    fn domain_relative_aliases_need_a_domain() {
+        assert!(resolve("DA").is_err());
+        let domain = sid("S-1-5-21-1-2-3");
+        assert_eq!(
+            resolve_in_domain("DA", &domain).unwrap(),
+            sid("S-1-5-21-1-2-3-512")
+        );
+        assert_eq!(resolve_in_domain("SY", &domain).unwrap(), sid("S-1-5-18"));
+        assert!(resolve_in_domain("DA", &sid("S-1-5-32")).is_err());
+        let admin = sid("S-1-5-21-1-2-3-500");
+        assert_eq!(from_sid(&admin).and_then(WellKnownSid::alias), Some("LA"));
+        assert_eq!(alias_for(&admin), None);
+    }
+
+    #[test]
+    fn classification_helpers() {
+        assert_eq!(
+            category(&sid("S-1-15-2-1")),
+            Some(WellKnownCategory::AppPackage)
+        );
+        assert_eq!(
+            category(&sid("S-1-5-32-999")),
+            Some(WellKnownCategory::Builtin)
+        );
+        assert_eq!(
+            category(&sid("S-1-5-21-1-2-3-1105")),
+            Some(WellKnownCategory::DomainRelative)
+        );
+        assert_eq!(category(&sid("S-1-15-2-1-2-3-4-5-6-7")), None);
+        assert_eq!(integrity_level(&sid("S-1-16-8192")), Some(0x2000));
+        assert!(!is_integrity_label(&sid("S-1-5-18")));
+        assert!(is_all_app_packages(&sid("S-1-15-2-2")));
+        assert!(is_domain_sid(&sid("S-1-5-21-1-2-3")));
+    }
+
+    #[test]
+    fn unknown_strings_fall_back_to_sid_parsing() {
+        assert_eq!(resolve("S-1-5-32-545").unwrap(), sid("S-1-5-32-545"));
+        assert!(resolve("not-a-sid").is_err());
+    }
+}
@@ -1,8 +1,9 @@
 //! Token introspection (skeleton).
 
+use crate::capability::{CapabilityCatalog, SidLabel};
 #[cfg(windows)]
 use crate::ffi::mem::LocalAllocGuard;
-use crate::sid::AppContainerSid;
+use crate::sid::{AppContainerSid, Sid};
 use crate::{AcError, Result};
 
 #[cfg(windows)]
@@ -46,6 +47,18 @@ pub struct TokenInfo {
     pub capability_sids: Vec<String>,
 }
 
+impl TokenInfo {
+    /// Labels every entry of [`capability_sids`](Self::capability_sids) with its SID class
+    /// and, where `catalog` knows it, the capability name. Unparseable entries are skipped.
+    pub fn label_capabilities(&self, catalog: &CapabilityCatalog) -> Vec<SidLabel> {
+        self.capability_sids
+            .iter()
+            .filter_map(|s| Sid::parse(s).ok())
+            .map(|sid| catalog.label_sid(&sid))
+            .collect()
+    }
+}
+
 /// Queries the current process token for AppContainer/LPAC status and capabilities.
 pub fn query_current_process_token() -> Result<TokenInfo> {
     #[cfg(windows)]
@@ -274,6 +287,32 @@ fn is_win32_error(err: &windows::core::Error, code: u32) -> bool {
     err.code() == HRESULT::from_win32(code)
 }
 
+#[cfg(test)]
+mod label_tests {
+    use super::TokenInfo;
+    use crate::capability::{CapabilityCatalog, CapabilityName};
+    use crate::sid::{CapabilityForm, SidClass};
+
+    #[test]
+    fn label_capabilities_classifies_each_entry() {
+        let info = TokenInfo {
+            capability_sids: vec![
+                "S-1-15-3-1024-2405443489-874036122-4286035555-1823921565-1746547431-2453885448-3625952902-991631256".into(),
+                "S-1-15-3-1".into(),
+                "not-a-sid".into(),
+            ],
+            ..TokenInfo::default()
+        };
+        let labels = info.label_capabilities(&CapabilityCatalog::new().unwrap());
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].capability, Some(CapabilityName::LpacCom));
+        assert_eq!(
+            labels[1].class,
+            SidClass::Capability(CapabilityForm::Legacy { rid: 1 })
+        );
+    }
+}
+
 #[cfg(test)]
 #[cfg(windows)]
 mod tests {
@@ -0,0 +1,281 @@
+#![cfg(feature = "appx")]
+
+use rappct::capability::{
+    AppxManifestCapabilities, CapabilityName, ManifestCapabilityKind, ManifestWarning,
+    derive_named_capability_sids, publisher_id,
+};
+use rappct::sid::{AppContainerSid, Sid};
+use rappct::{
+    AcError, CapabilityCatalog, KnownCapability, SecurityCapabilitiesBuilder, derive_sid_from_name,
+};
+
+const SANDBOXED_TOOL: &str = include_str!("fixtures/appx/sandboxed_tool.xml");
+const WINDOWS81: &str = include_str!("fixtures/appx/windows81.xml");
+
+#[test]
+fn imports_identity_and_capabilities() {
+    let manifest = SecurityCapabilitiesBuilder::from_appx_manifest(SANDBOXED_TOOL.as_bytes())
+        .expect("parse manifest");
+
+    let identity = manifest.identity().expect("identity");
+    assert_eq!(
+        identity.profile_name(),
+        format!(
+            "contoso.sandboxedtool_{}",
+            publisher_id("CN=Contoso Software, O=Contoso Corporation, C=US")
+        )
+    );
+    assert_eq!(
+        identity.publisher,
+        "CN=Contoso Software, O=Contoso Corporation, C=US"
+    );
+    assert_eq!(identity.version.as_deref(), Some("1.4.0.0"));
+    assert_eq!(identity.processor_architecture.as_deref(), Some("x64"));
+
+    let kinds: Vec<_> = manifest.declared().iter().map(|c| c.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ManifestCapabilityKind::Foundation,
+            ManifestCapabilityKind::Foundation,
+            ManifestCapabilityKind::Uap,
+            ManifestCapabilityKind::Uap,
+            ManifestCapabilityKind::Custom,
+            ManifestCapabilityKind::Restricted,
+            ManifestCapabilityKind::Restricted,
+            ManifestCapabilityKind::Device,
+            ManifestCapabilityKind::Device,
+            ManifestCapabilityKind::Device,
+        ]
+    );
+    assert_eq!(
+        manifest.capabilities(),
+        [
+            CapabilityName::InternetClient,
+            CapabilityName::PrivateNetworkClientServer,
+            CapabilityName::DocumentsLibrary,
+            CapabilityName::RemovableStorage,
+            CapabilityName::RunFullTrust,
+            CapabilityName::Webcam,
+            CapabilityName::Usb,
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_and_restricted_entries() {
+    let manifest = AppxManifestCapabilities::parse(SANDBOXED_TOOL).unwrap();
+    let warnings = manifest.warnings();
+    assert_eq!(warnings.len(), 4, "{warnings:?}");
+    assert!(warnings.contains(&ManifestWarning::RestrictedCapability {
+        capability: CapabilityName::RunFullTrust
+    }));
+    assert!(warnings.contains(&ManifestWarning::UnknownCapability {
+        kind: ManifestCapabilityKind::Device,
+        name: "microphon".into(),
+        suggestion: Some("microphone"),
+    }));
+    assert!(warnings.iter().any(|w| matches!(
+        w,
+        ManifestWarning::UnknownCapability { kind: ManifestCapabilityKind::Custom, name, .. }
+            if name == "Contoso.Telemetry_2x8bzqmbffkpw"
+    )));
+    assert!(warnings.iter().any(|w| w.to_string()
+        == "unknown capability <rescap:Capability Name=\"notARealCapabilityName\">"));
+}
+
+#[test]
+fn builds_security_capabilities_for_the_identity() {
+    let manifest = AppxManifestCapabilities::parse(WINDOWS81).unwrap();
+    assert!(manifest.warnings().is_empty());
+    assert_eq!(
+        manifest.capabilities(),
+        [
+            CapabilityName::PicturesLibrary,
+            CapabilityName::InternetClient,
+            CapabilityName::Location,
+        ]
+    );
+    let caps = manifest.with_identity_sid().unwrap().build().unwrap();
+    assert_eq!(
+        caps.package,
+        derive_sid_from_name(&format!("legacy.viewer_{}", publisher_id("CN=Legacy"))).unwrap()
+    );
+    assert_eq!(caps.caps.len(), 3);
+}
+
+#[test]
+fn profile_sid_matches_the_package_family_sid() {
+    let manifest = AppxManifestCapabilities::parse(
+        r#"<Package><Identity Name="Microsoft.MicrosoftEdge"
+            Publisher="CN=Microsoft Corporation, O=Microsoft Corporation, L=Redmond, S=Washington, C=US" />
+        </Package>"#,
+    )
+    .unwrap();
+    let identity = manifest.identity().unwrap();
+    assert_eq!(
+        identity.profile_name(),
+        "microsoft.microsoftedge_8wekyb3d8bbwe"
+    );
+    assert_eq!(
+        identity.derive_profile_sid().unwrap().as_string(),
+        "S-1-15-2-3624051433-2125758914-1423191267-1740899205-1073925389-3782572162-737981194"
+    );
+}
+
+#[test]
+fn rejects_malformed_manifests() {
+    for xml in [
+        "<Package><Capabilities>",
+        "<Manifest />",
+        "<Package><Identity Publisher=\"CN=x\" /></Package>",
+        "<Package><Identity Name=\"x\" /></Package>",
+        "<Package><Capabilities><Capability /></Capabilities></Package>",
+    ] {
+        assert!(
+            matches!(
+                AppxManifestCapabilities::parse(xml),
+                Err(AcError::InvalidManifest(_))
+            ),
+            "{xml}"
+        );
+    }
+    let empty = AppxManifestCapabilities::parse("<Package />").unwrap();
+    assert!(empty.identity().is_none() && empty.declared().is_empty());
+    assert!(empty.with_identity_sid().is_err());
+}
+
+fn package() -> AppContainerSid {
+    derive_sid_from_name("rappct.appx_export").unwrap()
+}
+
+/// Capability SIDs for names the catalog does not know.
+fn unlisted_sids(names: &[&str]) -> Vec<Sid> {
+    derive_named_capability_sids(names)
+        .unwrap()
+        .into_iter()
+        .map(|cap| cap.sid)
+        .collect()
+}
+
+#[test]
+fn exports_capabilities_with_namespaces() {
+    let catalog = CapabilityCatalog::new().unwrap();
+    let builder = SecurityCapabilitiesBuilder::new(&package())
+        .with_known(&[
+            KnownCapability::Webcam,
+            KnownCapability::InternetClient,
+            KnownCapability::DocumentsLibrary,
+            KnownCapability::RunFullTrust,
+        ])
+        .with_lpac_defaults();
+    let xml = builder.to_appx_capabilities(&catalog).unwrap();
+    assert_eq!(
+        xml,
+        "<Capabilities \
+         xmlns:uap=\"http://schemas.microsoft.com/appx/manifest/uap/windows10\" \
+         xmlns:rescap=\"http://schemas.microsoft.com/appx/manifest/foundation/windows10/restrictedcapabilities\">\n\
+         \x20 <Capability Name=\"internetClient\" />\n\
+         \x20 <uap:Capability Name=\"documentsLibrary\" />\n\
+         \x20 <rescap:Capability Name=\"runFullTrust\" />\n\
+         \x20 <!-- LPAC-only, no manifest element: registryRead -->\n\
+         \x20 <!-- LPAC-only, no manifest element: lpacCom -->\n\
+         \x20 <DeviceCapability Name=\"webcam\" />\n\
+         </Capabilities>\n"
+    );
+}
+
+#[test]
+fn exports_foundation_general_capabilities_without_prefix() {
+    let xml = SecurityCapabilitiesBuilder::new(&package())
+        .with_known(&[
+            KnownCapability::AllJoyn,
+            KnownCapability::CodeGeneration,
+            KnownCapability::InternetClientServer,
+        ])
+        .to_appx_capabilities(&CapabilityCatalog::new().unwrap())
+        .unwrap();
+    assert_eq!(
+        xml,
+        "<Capabilities>\n\
+         \x20 <Capability Name=\"allJoyn\" />\n\
+         \x20 <Capability Name=\"codeGeneration\" />\n\
+         \x20 <Capability Name=\"internetClientServer\" />\n\
+         </Capabilities>\n"
+    );
+    let reimported = AppxManifestCapabilities::parse(&format!("<Package>{xml}</Package>")).unwrap();
+    assert!(
+        reimported
+            .declared()
+            .iter()
+            .all(|c| c.kind == ManifestCapabilityKind::Foundation)
+    );
+    assert_eq!(reimported.capabilities().len(), 3);
+}
+
+#[test]
+fn exports_versioned_uap_namespaces() {
+    let xml = SecurityCapabilitiesBuilder::new(&package())
+        .with_known(&[
+            KnownCapability::GraphicsCapture,
+            KnownCapability::GlobalMediaControl,
+            KnownCapability::BackgroundMediaPlayback,
+            KnownCapability::LowLevelDevices,
+        ])
+        .to_appx_capabilities(&CapabilityCatalog::new().unwrap())
+        .unwrap();
+    assert_eq!(
+        xml,
+        "<Capabilities \
+         xmlns:uap6=\"http://schemas.microsoft.com/appx/manifest/uap/windows10/6\" \
+         xmlns:uap7=\"http://schemas.microsoft.com/appx/manifest/uap/windows10/7\" \
+         xmlns:uap3=\"http://schemas.microsoft.com/appx/manifest/uap/windows10/3\" \
+         xmlns:iot=\"http://schemas.microsoft.com/appx/manifest/iot/windows10\">\n\
+         \x20 <uap6:Capability Name=\"graphicsCapture\" />\n\
+         \x20 <uap7:Capability Name=\"globalMediaControl\" />\n\
+         \x20 <uap3:Capability Name=\"backgroundMediaPlayback\" />\n\
+         \x20 <iot:Capability Name=\"lowLevelDevices\" />\n\
+         </Capabilities>\n"
+    );
+    let reimported = AppxManifestCapabilities::parse(&format!("<Package>{xml}</Package>")).unwrap();
+    let kinds: Vec<_> = reimported.declared().iter().map(|c| c.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ManifestCapabilityKind::Uap,
+            ManifestCapabilityKind::Uap,
+            ManifestCapabilityKind::Uap,
+            ManifestCapabilityKind::Iot,
+        ]
+    );
+    assert!(reimported.warnings().is_empty());
+}
+
+#[test]
+fn exported_fragment_round_trips_through_import() {
+    let imported = AppxManifestCapabilities::parse(WINDOWS81).unwrap();
+    let caps = imported.with_identity_sid().unwrap().build().unwrap();
+    let xml = caps
+        .to_appx_capabilities(&CapabilityCatalog::new().unwrap())
+        .unwrap();
+    let reimported = AppxManifestCapabilities::parse(&format!("<Package>{xml}</Package>")).unwrap();
+    assert_eq!(reimported.capabilities(), imported.capabilities());
+}
+
+#[test]
+fn unnamed_sids_are_reported() {
+    let mut caps = SecurityCapabilitiesBuilder::new(&package())
+        .with_named(&["internetClient"])
+        .with_sids(&unlisted_sids(&["rappctUnlistedOne", "rappctUnlistedTwo"]))
+        .build()
+        .unwrap();
+    caps.caps.swap(0, 2);
+    let expected: Vec<String> = [&caps.caps[0], &caps.caps[1]]
+        .iter()
+        .map(|cap| cap.sid.to_string())
+        .collect();
+    match caps.to_appx_capabilities(&CapabilityCatalog::new().unwrap()) {
+        Err(AcError::UnnamedCapabilitySids(sids)) => assert_eq!(sids, expected),
+        other => panic!("unexpected {other:?}"),
+    }
+}
@@ -294,3 +294,90 @@ fn grant_to_package_updates_directory_default_inheritance_dacl() {
 
     profile.delete().ok();
 }
+
+#[cfg(windows)]
+#[test]
+fn revoke_from_package_removes_granted_directory_ace() {
+    let root = tempfile::tempdir().expect("temp dir");
+    let dir_path = root.path().join("acl-dir-revoke");
+    std::fs::create_dir_all(&dir_path).expect("create dir");
+
+    let name = format!("rappct.test.acl.dir.revoke.{}", std::process::id());
+    let profile =
+        AppContainerProfile::ensure(&name, "rappct acl", Some("acl test")).expect("ensure profile");
+    let sid_str = profile.sid.as_string().to_string();
+
+    acl::grant_to_package(
+        ResourcePath::Directory(dir_path.clone()),
+        &profile.sid,
+        AccessMask(0x120089),
+    )
+    .expect("grant directory access");
+    assert!(security_sddl_for_path(&dir_path).contains(&sid_str));
+
+    let removed = acl::revoke_from_package(
+        ResourcePath::Directory(dir_path.clone()),
+        &profile.sid,
+        Some(AccessMask(0x120089)),
+    )
+    .expect("revoke directory access");
+    assert_eq!(removed, 1);
+
+    let after = security_sddl_for_path(&dir_path);
+    assert!(
+        !after.contains(&sid_str),
+        "post-revoke directory DACL still contains SID {sid_str}: {after}"
+    );
+    let again = acl::revoke_from_package(ResourcePath::Directory(dir_path), &profile.sid, None)
+        .expect("second revoke");
+    assert_eq!(again, 0);
+
+    profile.delete().ok();
+}
+
+#[cfg(windows)]
+#[test]
+fn grant_guard_restores_file_dacl_on_drop() {
+    let temp = tempfile::NamedTempFile::new().expect("temp file");
+    let path = temp.path().to_path_buf();
+
+    let name = format!("rappct.test.acl.guard.{}", std::process::id());
+    let profile =
+        AppContainerProfile::ensure(&name, "rappct acl", Some("acl test")).expect("ensure profile");
+    let sid_str = profile.sid.as_string().to_string();
+
+    let before = security_sddl_for_path(&path);
+    let guard = acl::GrantGuard::new(
+        ResourcePath::File(path.clone()),
+        profile.sid.as_sid().expect("package SID"),
+        AccessMask(0x120089),
+    )
+    .expect("guarded grant");
+    assert!(security_sddl_for_path(&path).contains(&sid_str));
+    drop(guard);
+
+    assert_eq!(security_sddl_for_path(&path), before);
+
+    profile.delete().ok();
+}
+
+#[cfg(windows)]
+#[test]
+fn grant_plan_reads_registry_dacl_without_write_access() {
+    // Readable by every user, but only TrustedInstaller may change its DACL.
+    let spec = "HKLM\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion".to_string();
+
+    let name = format!("rappct.test.acl.regplan.{}", std::process::id());
+    let profile =
+        AppContainerProfile::ensure(&name, "rappct acl", Some("acl test")).expect("ensure profile");
+
+    let plan = acl::GrantPlan::for_package(
+        ResourcePath::RegistryKey(spec),
+        &profile.sid,
+        AccessMask(KEY_READ.0),
+    )
+    .expect("plan against read-only registry key");
+    assert!(plan.changes_dacl());
+
+    profile.delete().ok();
+}
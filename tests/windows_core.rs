@@ -44,7 +44,7 @@ fn capability_catalog_resolves_known_entries() {
             .capability(name)
             .unwrap_or_else(|| panic!("missing capability {name}"));
         assert_eq!(cap.name(), name);
-        assert!(!cap.sid().sid_sddl.is_empty());
+        assert!(!cap.sid().sid.sub_authorities().is_empty());
     }
 }
 
@@ -93,18 +93,18 @@ fn capability_catalog_matches_sid_lookup() {
             .unwrap_or_else(|| panic!("no SID returned for {name}"));
 
         assert_eq!(
-            catalog.lookup_sid(&primary.sid_sddl),
+            catalog.lookup_sid(&primary.sid.to_string()),
             Some(name),
             "catalog lookup by SID should return {name:?}"
         );
         assert_eq!(
-            capability.sid().sid_sddl,
-            primary.sid_sddl,
-            "catalog and direct derivation should agree on SDDL"
+            capability.sid().sid,
+            primary.sid,
+            "catalog and direct derivation should agree on the SID"
         );
 
-        let catalog_sid = LocalSid::from_sddl(&capability.sid().sid_sddl);
-        let derived_sid = LocalSid::from_sddl(&primary.sid_sddl);
+        let catalog_sid = LocalSid::from_sddl(&capability.sid().sid.to_string());
+        let derived_sid = LocalSid::from_sddl(&primary.sid.to_string());
 
         // SAFETY: SID pointers originate from ConvertStringSidToSidW and remain valid while these assertions run.
         unsafe {
@@ -140,7 +140,7 @@ fn capability_catalog_matches_sid_lookup() {
 fn capability_try_from_str_handles_known_and_unknown() {
     let cap = Capability::try_from_str("internetClient").expect("internetClient capability");
     assert_eq!(cap.name(), CapabilityName::InternetClient);
-    assert!(!cap.sid().sid_sddl.is_empty());
+    assert!(!cap.sid().sid.sub_authorities().is_empty());
 
     match Capability::try_from_str("internetClientX") {
         Err(AcError::UnknownCapability { .. }) => {}
@@ -174,7 +174,7 @@ fn capability_derivation_repeated_calls_are_successful() {
         let caps =
             capability::derive_named_capability_sids(&["internetClient"]).expect("derive caps");
         assert_eq!(caps.len(), 1);
-        assert!(!caps[0].sid_sddl.is_empty());
+        assert!(!caps[0].sid.sub_authorities().is_empty());
     }
 }
 
@@ -214,7 +214,7 @@ fn derive_arbitrary_capability_name_succeeds() {
     let caps =
         capability::derive_named_capability_sids(&["arbitraryName"]).expect("derive custom name");
     assert!(!caps.is_empty());
-    assert!(!caps[0].sid_sddl.is_empty());
+    assert!(!caps[0].sid.sub_authorities().is_empty());
 }
 
 #[cfg(windows)]
@@ -146,7 +146,7 @@ fn launch_appcontainer_token_matches_profile() {
         .with_known(&[KnownCapability::InternetClient])
         .build()
         .expect("build caps");
-    let expected_caps: Vec<String> = caps.caps.iter().map(|c| c.sid_sddl.clone()).collect();
+    let expected_caps: Vec<String> = caps.caps.iter().map(|c| c.sid.to_string()).collect();
     let opts = LaunchOptions {
         exe: cmd_exe(),
         cmdline: Some(" /C choice /D Y /T 2 > NUL 2>&1".to_string()),
@@ -239,7 +239,7 @@ fn launch_lpac_token_sets_flag_and_caps() {
         .lpac(true)
         .build()
         .expect("build caps");
-    let expected_caps: Vec<String> = caps.caps.iter().map(|c| c.sid_sddl.clone()).collect();
+    let expected_caps: Vec<String> = caps.caps.iter().map(|c| c.sid.to_string()).collect();
     let opts = LaunchOptions {
         exe: cmd_exe(),
         cmdline: Some(" /C choice /D Y /T 2 > NUL 2>&1".to_string()),